@@ -1,53 +1,156 @@
 use anyhow::Result;
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, NaiveDate};
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::time;
-use rdev::{listen, EventType};
+use rdev::listen;
 
+use crate::config::settings::{AppConfig, ShortSessionAction};
+use crate::daemon::afk::AfkHysteresis;
 use crate::daemon::database::connection::Database;
+use crate::daemon::ipc::{self, DaemonStatus};
+use crate::daemon::media;
+use crate::daemon::notifications;
 use crate::models::session::Session;
 use crate::daemon::tracker::{monitor::AppMonitor};
 use crate::daemon::tracker::parser;
 
+/// A window that took focus but hasn't been stable long enough to start a session
+#[derive(Debug, Clone, PartialEq)]
+struct PendingSwitch {
+    app: String,
+    window: Option<String>,
+    since: DateTime<Local>,
+}
+
+impl PendingSwitch {
+    /// Record the focused window; returns the time it first took focus once it has been stable for `debounce`
+    fn observe(pending: &mut Option<PendingSwitch>, app: &str, window: &Option<String>, now: DateTime<Local>, debounce: chrono::Duration) -> Option<DateTime<Local>> {
+        let since = match pending {
+            Some(candidate) if candidate.app == app && candidate.window == *window => candidate.since,
+            _ => {
+                *pending = Some(PendingSwitch { app: app.to_string(), window: window.clone(), since: now });
+                now
+            }
+        };
+        if now.signed_duration_since(since) < debounce {
+            return None;
+        }
+        *pending = None;
+        Some(since)
+    }
+}
+
+/// Poll interval that backs off while nothing changes or the machine is on battery
+#[derive(Debug)]
+struct PollBackoff {
+    interval: Duration,
+    unchanged_polls: u32,
+}
+
+impl PollBackoff {
+    const FAST: Duration = Duration::from_millis(100);
+    const SLOW: Duration = Duration::from_secs(1);
+    // About a second of identical detections before slowing down on AC power
+    const UNCHANGED_BEFORE_BACKOFF: u32 = 10;
+
+    fn new() -> Self {
+        PollBackoff { interval: Self::FAST, unchanged_polls: 0 }
+    }
+
+    /// Interval until the next poll; a changed detection goes straight back to fast polling
+    fn next(&mut self, changed: bool, on_battery: bool) -> Duration {
+        if changed {
+            self.unchanged_polls = 0;
+            self.interval = Self::FAST;
+        } else {
+            self.unchanged_polls += 1;
+            if on_battery || self.unchanged_polls >= Self::UNCHANGED_BEFORE_BACKOFF {
+                self.interval = (self.interval * 2).min(Self::SLOW);
+            }
+        }
+        self.interval
+    }
+}
+
 pub struct Daemon {
     database: Database,
     monitor: AppMonitor,
     current_app: String,
     current_window: Option<String>,
+    current_fullscreen: bool, // Only compared when fullscreen_category is set
+    dnd_active: Arc<AtomicBool>, // Do-Not-Disturb state, polled in the background when dnd_category or dnd_tag is set
+    current_dnd: bool, // DND state the current session started with
+    screen_locked: Arc<AtomicBool>, // Set while the screen is locked, when lock_is_afk is on
+    afk_state: AfkHysteresis, // Idle readings smoothed over afk_hysteresis_checks
     current_session: Option<Session>,
     last_input: Arc<Mutex<DateTime<Local>>>,
+    config: AppConfig,
+    notified_limits: HashSet<(String, NaiveDate)>, // Category limits already announced, per day
+    notified_app_limits: HashMap<(String, NaiveDate), DateTime<Local>>, // When each app limit was last announced, per day
+    status: Option<Arc<Mutex<DaemonStatus>>>, // Shared with the status socket when it is enabled
+    pending_switch: Option<PendingSwitch>, // Window waiting out the switch debounce
+    sticky_tag: Option<String>, // Given to each new session; set over the status socket
+    held_session: Option<HeldSession>, // Interrupted by an AFK session that hasn't lasted min_afk_secs yet
+}
+
+/// An active session held back while the AFK session after it might still be a flap, along with
+/// the window it was detected on, so it can carry on if the AFK session turns out too short
+struct HeldSession {
+    session: Session,
+    app: String,
+    window: Option<String>,
 }
 
 impl Daemon {
-    pub fn new(database: Database) -> Self {
+    pub fn new(mut database: Database, config: AppConfig) -> Self {
+        crate::daemon::title_trim::set_title_trim_rules(&config.title_trim_rules);
+        database.set_influx_exporter(crate::daemon::influx::InfluxExporter::from_config(&config));
         let monitor = AppMonitor::new();
         let last_input = Arc::new(Mutex::new(Local::now()));
 
         // Start input monitoring thread
-        Self::start_input_monitoring(Arc::clone(&last_input));
+        Self::start_input_monitoring(Arc::clone(&last_input), config.ignores_mouse_move());
+        let dnd_active = Arc::new(AtomicBool::new(false));
+        if config.dnd_enabled() {
+            Self::start_dnd_monitoring(Arc::clone(&dnd_active));
+        }
+        let screen_locked = Arc::new(AtomicBool::new(false));
+        if config.lock_is_afk {
+            crate::daemon::screen_lock::spawn_watcher(Arc::clone(&screen_locked), Arc::clone(&last_input));
+        }
 
         Self {
             database,
             monitor,
             current_app: "unknown".to_string(),
             current_window: None,
+            current_fullscreen: false,
+            dnd_active,
+            current_dnd: false,
+            screen_locked,
+            afk_state: AfkHysteresis::default(),
             current_session: None,
             last_input,
+            config,
+            notified_limits: HashSet::new(),
+            notified_app_limits: HashMap::new(),
+            status: None,
+            pending_switch: None,
+            sticky_tag: None,
+            held_session: None,
         }
     }
 
     // Input monitoring using rdev
-    fn start_input_monitoring(last_input: Arc<Mutex<DateTime<Local>>>) {
+    fn start_input_monitoring(last_input: Arc<Mutex<DateTime<Local>>>, ignore_mouse_move: bool) {
         std::thread::spawn(move || {
             let callback = move |event: rdev::Event| {
-                match event.event_type {
-                    EventType::KeyPress(_) | EventType::KeyRelease(_) | EventType::ButtonPress(_) | EventType::ButtonRelease(_) | EventType::MouseMove { .. } => {
-                        log::debug!("Input event detected: {:?}", event.event_type);
-                        *last_input.lock().unwrap() = Local::now();
-                    }
-                    _ => {}
+                if crate::daemon::afk::counts_as_input(&event.event_type, ignore_mouse_move) {
+                    log::debug!("Input event detected: {:?}", event.event_type);
+                    *last_input.lock().unwrap() = Local::now();
                 }
             };
             if let Err(error) = listen(callback) {
@@ -56,6 +159,26 @@ impl Daemon {
         });
     }
 
+    // Poll Do-Not-Disturb; where the state can't be read it stays off and tracking is unaffected
+    fn start_dnd_monitoring(dnd_active: Arc<AtomicBool>) {
+        tokio::spawn(async move {
+            let mut reported_unavailable = false;
+            loop {
+                match notifications::dnd_active().await {
+                    Ok(active) => dnd_active.store(active, Ordering::Relaxed),
+                    Err(e) => {
+                        if !reported_unavailable {
+                            log::info!("Do-Not-Disturb state unavailable: {}", e);
+                            reported_unavailable = true;
+                        }
+                        dnd_active.store(false, Ordering::Relaxed);
+                    }
+                }
+                time::sleep(Duration::from_secs(10)).await;
+            }
+        });
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         log::info!("Starting background daemon for window tracking...");
 
@@ -67,14 +190,35 @@ impl Daemon {
         // Start tracking initial app
         self.start_tracking().await?;
 
+        // Optional status socket for other clients (off unless ipc_socket_path is set)
+        if let Some(path) = self.config.ipc_socket_path.clone() {
+            let status = Arc::new(Mutex::new(DaemonStatus::default()));
+            match ipc::spawn_server(path.into(), Arc::clone(&status)) {
+                Ok(()) => self.status = Some(status),
+                Err(e) => log::warn!("Failed to start status socket: {}", e),
+            }
+        }
+        let mut last_status_refresh = tokio::time::Instant::now();
+        let status_refresh_interval = Duration::from_secs(5);
+
         // Auto-save interval: 1 hour
         let mut last_save = tokio::time::Instant::now();
         let save_interval = Duration::from_secs(3600);
 
         let mut last_afk_check = tokio::time::Instant::now();
         let afk_check_interval = Duration::from_secs(1); // Check AFK status every second
-        let afk_threshold = Duration::from_secs(300); // 5 minutes of idle = AFK
-        let idle_threshold = Duration::from_secs(600); // 10 minutes = IDLE (no input during AFK)
+        let mut last_config_reload = tokio::time::Instant::now();
+        let config_reload_interval = Duration::from_secs(5); // Pick up changes made in the TUI settings screen
+        let mut last_limit_check = tokio::time::Instant::now();
+        let limit_check_interval = Duration::from_secs(60); // Category limits only need minute precision
+        let mut poll_backoff = PollBackoff::new();
+        let mut last_detection: Option<(String, Option<String>)> = None;
+        let mut on_battery = false;
+        let mut last_power_check: Option<tokio::time::Instant> = None;
+        let power_check_interval = Duration::from_secs(60);
+        let mut last_heartbeat: Option<tokio::time::Instant> = None;
+        let heartbeat_interval = Duration::from_secs(30); // Lets the TUI tell when the daemon has stopped
+        let mut heartbeat_warned = false;
 
         loop {
             // Check for shutdown signal
@@ -83,10 +227,36 @@ impl Daemon {
                 break;
             }
 
+            if last_heartbeat.is_none_or(|at| at.elapsed() >= heartbeat_interval) {
+                // Databases the TUI hasn't migrated lack the table; tracking works without it
+                match self.database.touch_daemon_heartbeat().await {
+                    Err(e) if !heartbeat_warned => {
+                        log::warn!("Failed to write daemon heartbeat: {}", e);
+                        heartbeat_warned = true;
+                    }
+                    _ => {}
+                }
+                last_heartbeat = Some(tokio::time::Instant::now());
+            }
+
+            if last_config_reload.elapsed() >= config_reload_interval {
+                let config = AppConfig::load();
+                if config.title_trim_rules != self.config.title_trim_rules {
+                    crate::daemon::title_trim::set_title_trim_rules(&config.title_trim_rules);
+                }
+                self.config = config;
+                last_config_reload = tokio::time::Instant::now();
+            }
+            // Passive categories (e.g. video playback) use a longer threshold before the session goes AFK
+            let current_category = self.current_session.as_ref().and_then(|session| session.category.as_deref());
+            let afk_threshold = Duration::from_secs(self.config.afk_threshold_for(current_category));
+
             // Check for AFK status every second
             if last_afk_check.elapsed() >= afk_check_interval {
                 let idle_duration = Local::now().signed_duration_since(*self.last_input.lock().unwrap());
-                let is_currently_afk = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+                let is_idle = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+                // A locked screen is AFK straight away; idle time has to stay past (or back under) the threshold
+                let is_currently_afk = self.afk_state.observe(is_idle, self.config.afk_hysteresis_checks) || self.screen_locked.load(Ordering::Relaxed);
 
                 // If we have a current session, check if AFK state changed
                 if let Some(ref mut session) = self.current_session {
@@ -98,30 +268,51 @@ impl Daemon {
                         let mut old_session = self.current_session.take().unwrap();
                         old_session.duration = Local::now().signed_duration_since(old_session.start_time).num_seconds();
 
-                        // If this is an AFK session being ended, mark as IDLE if it lasted 10+ minutes
-                        if was_afk && old_session.duration >= idle_threshold.as_secs() as i64 {
+                        // If this is an AFK session being ended, mark as IDLE if it lasted past the idle threshold
+                        if was_afk && old_session.duration >= self.config.idle_threshold_secs as i64 {
                             old_session.is_idle = Some(true);
                             log::info!("AFK session marked as IDLE: {} for {:.1} minutes",
                                       old_session.app_name, old_session.duration as f64 / 60.0);
                         }
 
-                        if let Err(e) = self.database.apply_renames_and_categories(&mut old_session).await {
-                            log::warn!("Failed to apply renames and categories on AFK change: {}", e);
-                        }
+                        // AFK that ends before min_afk_secs is dropped, and the session it interrupted carries on
+                        let afk_flap = was_afk && self.held_session.is_some();
+                        // Entering AFK with min_afk_secs set: hold the session until the AFK session has lasted long enough
+                        let hold = is_currently_afk && self.config.min_afk_secs() > 0;
 
-                        if let Err(e) = self.database.insert_session(&old_session).await {
-                            log::error!("Failed to save session on AFK state change: {}", e);
-                        } else {
-                            log::info!("Session saved on AFK state change: {} -> is_afk={}", old_session.app_name, is_currently_afk);
+                        if afk_flap {
+                            log::debug!("Dropped AFK session of {}s, below min_afk_secs", old_session.duration);
+                        } else if !hold {
+                            if let Err(e) = self.database.apply_renames_and_categories(&mut old_session).await {
+                                log::warn!("Failed to apply renames and categories on AFK change: {}", e);
+                            }
+
+                            if let Err(e) = self.database.insert_session(&old_session).await {
+                                log::error!("Failed to save session on AFK state change: {}", e);
+                            } else {
+                                log::info!("Session saved on AFK state change: {} -> is_afk={}", old_session.app_name, is_currently_afk);
+                            }
                         }
 
                         // Start new session with updated AFK state
-                        if is_currently_afk {
+                        if afk_flap {
+                            if let Some(held) = self.held_session.take() {
+                                log::info!("Resumed {} after a short AFK", held.session.app_name);
+                                self.current_session = Some(held.session);
+                                self.current_app = held.app;
+                                self.current_window = held.window;
+                            }
+                        } else if is_currently_afk {
+                            let interrupted = (self.current_app.clone(), self.current_window.clone());
                             // Starting AFK session
                             self.switch_app("AFK".to_string(), Some("Away from keyboard".to_string())).await?;
                             if let Some(ref mut new_session) = self.current_session {
                                 new_session.is_afk = Some(true);
                             }
+                            if hold {
+                                let (app, window) = interrupted;
+                                self.held_session = Some(HeldSession { session: old_session, app, window });
+                            }
                         } else {
                             // Returning from AFK - get the actual active app
                             if let Ok((active_app, active_window)) = self.monitor.get_active_window_info_async().await {
@@ -134,17 +325,59 @@ impl Daemon {
                     }
                 }
 
+                // The AFK session has outlasted min_afk_secs, so it's real: record the session it interrupted
+                let afk_secs = self.current_session.as_ref()
+                    .filter(|session| session.is_afk.unwrap_or(false))
+                    .map(|session| Local::now().signed_duration_since(session.start_time).num_seconds());
+                if afk_secs.is_some_and(|secs| !self.config.is_afk_flap(secs)) {
+                    self.save_held_session().await;
+                }
+
                 last_afk_check = tokio::time::Instant::now();
             }
 
-            // Check for app or window change (but not if we're AFK)
-            if let Ok((active_app, active_window)) = self.monitor.get_active_window_info_async().await {
-                let idle_duration = Local::now().signed_duration_since(*self.last_input.lock().unwrap());
-                let is_currently_afk = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+            self.sync_sticky_tag();
 
-                // Only track app changes if not AFK
-                if !is_currently_afk && (active_app != self.current_app || active_window != self.current_window) {
-                    self.switch_app(active_app.clone(), active_window.clone()).await?;
+            // Check for app or window change (but not if we're AFK)
+            let mut detection_changed = false;
+            // Some setups report the focused window with an empty app name; the current session carries on
+            let detected = self.monitor.get_active_window_info_async().await.ok().filter(|(active_app, _)| {
+                let blank = active_app.trim().is_empty();
+                if blank {
+                    log::debug!("Active window has no app name, keeping {}", self.current_app);
+                }
+                !blank
+            });
+            // Do-not-track windows (a login greeter, the screensaver) record nothing, not even AFK time
+            let detected = match detected {
+                Some((active_app, active_window)) if self.config.is_do_not_track(&active_app, active_window.as_deref()) => {
+                    self.stop_for_do_not_track(&active_app).await;
+                    None
+                }
+                other => other,
+            };
+            if let Some((active_app, active_window)) = detected {
+                let detection = (active_app.clone(), active_window.clone());
+                detection_changed = last_detection.as_ref() != Some(&detection);
+                last_detection = Some(detection);
+                let is_currently_afk = self.afk_state.is_afk() || self.screen_locked.load(Ordering::Relaxed);
+
+                // Only track app changes if not AFK, and only once the new window has stopped flickering
+                // Going in or out of fullscreen counts as a change when fullscreen windows get their own category
+                let fullscreen_changed = self.config.fullscreen_category.is_some() && self.monitor.is_fullscreen() != self.current_fullscreen;
+                // Likewise turning Do-Not-Disturb on or off, when it sets a category or tag
+                let dnd_changed = self.config.dnd_enabled() && self.dnd_active.load(Ordering::Relaxed) != self.current_dnd;
+                let window_changed = active_app != self.current_app || active_window != self.current_window || fullscreen_changed || dnd_changed;
+                let debounce = self.switch_debounce();
+                let switch_at = if !is_currently_afk && window_changed {
+                    PendingSwitch::observe(&mut self.pending_switch, &active_app, &active_window, Local::now(), debounce)
+                } else {
+                    // Focus came back before the debounce elapsed; that time stays with the current session
+                    self.pending_switch = None;
+                    None
+                };
+                if let Some(switch_at) = switch_at {
+                    self.switch_app_at(active_app.clone(), active_window.clone(), switch_at).await?;
                     self.current_app = active_app;
                     self.current_window = active_window;
 
@@ -155,6 +388,39 @@ impl Daemon {
                 }
             }
 
+            // Detection stuck on the last window (e.g. an unresponsive GNOME extension) would otherwise
+            // grow one session for as long as the daemon runs
+            if let Some(session) = self.current_session.as_ref().filter(|session| self.config.session_exceeds_max(session, Local::now())) {
+                log::warn!(
+                    "{} tracked for {}s without a window change; starting a fresh session in case window detection is stuck",
+                    session.app_name,
+                    Local::now().signed_duration_since(session.start_time).num_seconds()
+                );
+                let tag = session.tag.clone();
+                // A pending switch would end the fresh session before it started
+                self.pending_switch = None;
+                self.switch_app(self.current_app.clone(), self.current_window.clone()).await?;
+                if let Some(ref mut session) = self.current_session {
+                    session.is_afk = Some(false);
+                    session.tag = tag;
+                }
+            }
+
+            if last_limit_check.elapsed() >= limit_check_interval {
+                if let Some(session) = &self.current_session {
+                    let duration = Local::now().signed_duration_since(session.start_time).num_seconds();
+                    log::info!("Still tracking: {} for {}s", session.app_name, duration);
+                }
+                self.check_category_limits().await;
+                self.check_app_limits().await;
+                last_limit_check = tokio::time::Instant::now();
+            }
+
+            if self.status.is_some() && last_status_refresh.elapsed() >= status_refresh_interval {
+                self.refresh_status().await;
+                last_status_refresh = tokio::time::Instant::now();
+            }
+
             // Auto save every hour
             if last_save.elapsed() >= save_interval {
                 if let Some(session) = &mut self.current_session {
@@ -171,11 +437,25 @@ impl Daemon {
                 }
             }
 
-            // Poll every 100ms for real-time tracking
-            time::sleep(Duration::from_millis(100)).await;
+            if self.config.adaptive_polling && last_power_check.is_none_or(|checked| checked.elapsed() >= power_check_interval) {
+                on_battery = crate::daemon::power::on_battery().await.unwrap_or_else(|e| {
+                    log::debug!("Power source unknown: {}", e);
+                    false
+                });
+                last_power_check = Some(tokio::time::Instant::now());
+            }
+
+            // Poll every 100ms for real-time tracking, backing off to 1s while the window stays the same or on battery
+            let poll_interval = if self.config.adaptive_polling {
+                poll_backoff.next(detection_changed, on_battery)
+            } else {
+                PollBackoff::FAST
+            };
+            time::sleep(poll_interval).await;
         }
 
         // Save current session on exit
+        self.save_held_session().await;
         if let Some(mut session) = self.current_session.take() {
             session.duration = Local::now().signed_duration_since(session.start_time).num_seconds();
             if let Err(e) = self.database.apply_renames_and_categories(&mut session).await {
@@ -188,6 +468,12 @@ impl Daemon {
             }
         }
 
+        crate::daemon::tracker::kwin::unload_script().await;
+
+        if let Some(path) = self.status.as_ref().and(self.config.ipc_socket_path.as_ref()) {
+            std::fs::remove_file(path).unwrap_or_else(|e| log::warn!("Failed to remove status socket {}: {}", path, e));
+        }
+
         Ok(())
     }
 
@@ -205,62 +491,332 @@ impl Daemon {
         };
 
         let start_time = Local::now();
-        let (category_name, _) = Self::categorize_app(&app_name);
+        let dnd_active = self.dnd_active.load(Ordering::Relaxed);
 
         let mut session = Self::create_session_with_parsing(
             app_name.clone(),
             window_name.clone(),
             start_time,
-            category_name.to_string(),
-        );
+        ).await;
+        session.category = Some(self.new_session_category(&session, dnd_active));
 
         if let Err(e) = self.database.apply_renames_and_categories(&mut session).await {
             log::warn!("Failed to apply renames and categories: {}", e);
         }
+        if session.app_name != "AFK" {
+            // A sticky tag wins over the Do-Not-Disturb tag
+            session.tag = self.sticky_tag.clone().or(self.config.dnd_tag.clone().filter(|_| dnd_active));
+        }
+
+        // A quick restart (crash, update) carries on the last session rather than splitting it in two
+        let resumed = match self.database.reopen_last_session_if_recent(&session, self.config.resume_window_secs).await {
+            Ok(previous) => previous,
+            Err(e) => {
+                log::warn!("Failed to check the last session for resuming: {}", e);
+                None
+            }
+        };
+        if let Some(previous) = &resumed {
+            session.start_time = previous.start_time;
+            session.tag = session.tag.take().or(previous.tag.clone());
+            session.resumed_id = previous.id;
+        }
 
         self.current_session = Some(session);
         self.current_window = window_name;
-        log::info!("Started tracking: {}", app_name);
+        self.current_fullscreen = self.monitor.is_fullscreen();
+        self.current_dnd = dnd_active;
+        if let Some(previous) = resumed {
+            log::info!("Resumed tracking: {} (started {})", app_name, previous.start_time.format("%H:%M:%S"));
+        } else {
+            log::info!("Started tracking: {}", app_name);
+        }
         Ok(())
     }
 
+    fn switch_debounce(&self) -> chrono::Duration {
+        chrono::Duration::milliseconds(self.config.switch_debounce_ms() as i64)
+    }
+
     async fn switch_app(&mut self, new_app: String, window_name: Option<String>) -> Result<()> {
-        // End current session
-        if let Some(mut session) = self.current_session.take() {
-            session.duration = Local::now().signed_duration_since(session.start_time).num_seconds();
+        self.switch_app_at(new_app, window_name, Local::now()).await
+    }
 
-            if let Err(e) = self.database.insert_session(&session).await {
-                log::error!("Failed to save session: {}", e);
-            } else {
-                log::info!("Saved session: {} for {}s", session.app_name, session.duration);
-            }
+    /// End the current session at `switch_at` and start the new one from there,
+    /// so time spent waiting out the debounce goes to the window that was actually focused
+    async fn switch_app_at(&mut self, new_app: String, window_name: Option<String>, switch_at: DateTime<Local>) -> Result<()> {
+        if new_app.trim().is_empty() {
+            log::debug!("Not switching to an empty app name, keeping {}", self.current_app);
+            return Ok(());
         }
 
+        // Whatever ends an AFK session other than the return from it, the interrupted session is kept
+        self.save_held_session().await;
+        self.end_current_session(switch_at).await;
+
         // Start new session
-        let start_time = Local::now();
-        let (category_name, _) = Self::categorize_app(&new_app);
+        let start_time = switch_at;
+        let dnd_active = self.dnd_active.load(Ordering::Relaxed);
 
         let mut session = Self::create_session_with_parsing(
             new_app.clone(),
             window_name.clone(),
             start_time,
-            category_name.to_string(),
-        );
+        ).await;
+        session.category = Some(self.new_session_category(&session, dnd_active));
 
         if let Err(e) = self.database.apply_renames_and_categories(&mut session).await {
             log::warn!("Failed to apply renames and categories: {}", e);
         }
+        if session.app_name != "AFK" {
+            // A sticky tag wins over the Do-Not-Disturb tag
+            session.tag = self.sticky_tag.clone().or(self.config.dnd_tag.clone().filter(|_| dnd_active));
+        }
 
+        // Same name the dashboard shows for it, renames included
+        let shown_name = session.effective_display_name().map(|(name, _, _)| format!(" ({})", name)).unwrap_or_default();
         self.current_session = Some(session);
         self.current_app = new_app.clone();
         self.current_window = window_name;
-        log::info!("Switched to: {}", new_app);
+        self.current_fullscreen = self.monitor.is_fullscreen();
+        self.current_dnd = dnd_active;
+        log::info!("Switched to: {}{}", new_app, shown_name);
         Ok(())
     }
 
+    /// A do-not-track window is focused: the running session ends now and nothing is tracked until
+    /// another window is, so it's neither recorded nor counted
+    async fn stop_for_do_not_track(&mut self, app_name: &str) {
+        self.pending_switch = None;
+        if self.current_session.is_none() {
+            return;
+        }
+        log::info!("{} is on the do-not-track list, pausing tracking", app_name);
+        self.save_held_session().await;
+        self.end_current_session(Local::now()).await;
+        // Whatever gets focused next, even the app from before, starts a new session
+        self.current_app = String::new();
+        self.current_window = None;
+    }
+
+    /// Save the running session ending at `end_at`, or merge or drop it when it's too short
+    async fn end_current_session(&mut self, end_at: DateTime<Local>) {
+        if let Some(mut session) = self.current_session.take() {
+            session.duration = end_at.signed_duration_since(session.start_time).num_seconds().max(0);
+
+            // Sessions below the configured minimum are merged or dropped instead of getting their own row
+            match self.config.short_session_action_for(&session) {
+                Some(ShortSessionAction::Merge) => match self.database.merge_into_previous_session(&session).await {
+                    Ok(true) => log::info!("Merged short session: {} ({}s) into previous", session.app_name, session.duration),
+                    Ok(false) => log::debug!("No previous session to merge {} into, dropped", session.app_name),
+                    Err(e) => log::error!("Failed to merge short session: {}", e),
+                },
+                Some(ShortSessionAction::Discard) => {
+                    log::debug!("Discarded short session: {} for {}s", session.app_name, session.duration);
+                }
+                None => {
+                    if let Err(e) = self.database.insert_session(&session).await {
+                        log::error!("Failed to save session: {}", e);
+                    } else {
+                        log::info!("Saved session: {} for {}s", session.app_name, session.duration);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Record the session held back when AFK started, with the duration it had then
+    async fn save_held_session(&mut self) {
+        let Some(HeldSession { mut session, .. }) = self.held_session.take() else {
+            return;
+        };
+        if let Err(e) = self.database.apply_renames_and_categories(&mut session).await {
+            log::warn!("Failed to apply renames and categories on AFK change: {}", e);
+        }
+        if let Err(e) = self.database.insert_session(&session).await {
+            log::error!("Failed to save session on AFK state change: {}", e);
+        } else {
+            log::info!("Session saved on AFK state change: {} -> is_afk=true", session.app_name);
+        }
+    }
+
+    /// Pick up a sticky tag set over the status socket; it also tags the running session
+    fn sync_sticky_tag(&mut self) {
+        let Some(status) = &self.status else {
+            return;
+        };
+        let tag = status.lock().unwrap().sticky_tag.clone();
+        if tag == self.sticky_tag {
+            return;
+        }
+        log::info!("Sticky tag: {}", tag.as_deref().unwrap_or("(cleared)"));
+        if let Some(session) = self.current_session.as_mut().filter(|session| session.app_name != "AFK") {
+            session.tag = tag.clone();
+        }
+        self.sticky_tag = tag;
+    }
+
+    /// Update the snapshot served on the status socket
+    async fn refresh_status(&mut self) {
+        let Some(status) = self.status.clone() else {
+            return;
+        };
+
+        let mut today_totals = match self.database.get_daily_category_usage().await {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Failed to load daily category usage for status: {}", e);
+                return;
+            }
+        };
+        today_totals.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+
+        let mut status = status.lock().unwrap();
+        status.current_app = self.current_app.clone();
+        status.current_window = self.current_window.clone();
+        status.today_totals = today_totals;
+        match &self.current_session {
+            Some(session) => {
+                status.session_secs = Local::now().signed_duration_since(session.start_time).num_seconds();
+                status.is_afk = session.is_afk.unwrap_or(false);
+            }
+            None => {
+                status.session_secs = 0;
+                status.is_afk = false;
+            }
+        }
+    }
+
+    /// Notify once per day for each category whose non-AFK time today has crossed its configured limit
+    async fn check_category_limits(&mut self) {
+        if !self.config.category_limits_enabled || self.config.category_limits_minutes.is_empty() {
+            return;
+        }
+
+        let mut usage: HashMap<String, i64> = match self.database.get_daily_category_usage().await {
+            Ok(rows) => rows.into_iter().collect(),
+            Err(e) => {
+                log::warn!("Failed to load daily category usage for limits: {}", e);
+                return;
+            }
+        };
+
+        // Include the in-progress session, which is not in the database yet
+        let live_session = self.current_session.as_ref().filter(|session| !session.is_afk.unwrap_or(false));
+        if let Some((session, category)) = live_session.and_then(|session| session.category.as_ref().map(|category| (session, category))) {
+            let elapsed = Local::now().signed_duration_since(session.start_time).num_seconds();
+            *usage.entry(category.clone()).or_insert(0) += elapsed;
+        }
+
+        let today = Local::now().date_naive();
+        self.notified_limits.retain(|(_, date)| *date == today);
+
+        for (category, limit_minutes) in &self.config.category_limits_minutes {
+            let spent = usage.get(category).copied().unwrap_or(0);
+            if spent < (*limit_minutes as i64) * 60 || self.notified_limits.contains(&(category.clone(), today)) {
+                continue;
+            }
+
+            let title = format!("{} limit reached", category);
+            let body = format!("You've spent {}h {}m on {} today (limit {}m)", spent / 3600, (spent % 3600) / 60, category, limit_minutes);
+            match notifications::send_desktop_notification(&title, &body, false) {
+                Ok(()) => log::info!("Category limit notification sent: {}", body),
+                Err(e) => log::warn!("Failed to send category limit notification: {}", e),
+            }
+            // Mark as notified even on failure so a broken notifier doesn't retry every minute
+            self.notified_limits.insert((category.clone(), today));
+        }
+    }
+
+    /// Urgent notifications for apps past their `app_limits_minutes`, once or repeatedly per
+    /// `app_limit_action`, plus a marker row the TUI shows as a banner
+    async fn check_app_limits(&mut self) {
+        if self.config.app_limits_minutes.is_empty() {
+            return;
+        }
+
+        let mut usage = match self.database.get_daily_usage().await {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Failed to load daily app usage for limits: {}", e);
+                return;
+            }
+        };
+
+        // Include the in-progress session, which is not in the database yet
+        if let Some(session) = self.current_session.as_ref().filter(|session| !session.is_afk.unwrap_or(false)) {
+            let elapsed = Local::now().signed_duration_since(session.start_time).num_seconds();
+            usage.push((session.app_name.clone(), elapsed));
+        }
+
+        let now = Local::now();
+        let today = now.date_naive();
+        self.notified_app_limits.retain(|(_, date), _| *date == today);
+
+        for (app, limit_minutes) in &self.config.app_limits_minutes {
+            // App names differ in case between platforms (firefox, Firefox)
+            let spent: i64 = usage.iter().filter(|(name, _)| name.eq_ignore_ascii_case(app)).map(|(_, secs)| secs).sum();
+            if spent < (*limit_minutes as i64) * 60 {
+                continue;
+            }
+
+            let recorded = if self.config.app_limit_banner {
+                self.database.upsert_app_limit_alert(app, today, spent, *limit_minutes).await
+            } else {
+                Ok(())
+            };
+            if let Err(e) = recorded {
+                log::warn!("Failed to record app limit alert: {}", e);
+            }
+
+            let key = (app.clone(), today);
+            if !self.config.app_limit_action.notification_due(self.notified_app_limits.get(&key).copied(), now) {
+                continue;
+            }
+            let title = format!("⛔ {} limit reached", app);
+            let body = format!("You've used {} for {}h {}m today (limit {}m)", app, spent / 3600, (spent % 3600) / 60, limit_minutes);
+            match notifications::send_desktop_notification(&title, &body, true) {
+                Ok(()) => log::info!("App limit notification sent: {}", body),
+                Err(e) => log::warn!("Failed to send app limit notification: {}", e),
+            }
+            // Counted as sent even on failure so a broken notifier doesn't retry every minute
+            self.notified_app_limits.insert(key, now);
+        }
+    }
+
+    /// A `directory_categories` rule for the parsed directory or project, else the app and window rules,
+    /// then the fullscreen and Do-Not-Disturb overrides
+    fn new_session_category(&self, session: &Session, dnd_active: bool) -> String {
+        let category_name = match self.config.directory_category(session) {
+            Some(category) => category.to_string(),
+            None => Self::categorize_window(&session.app_name, session.window_name.as_deref()).0.to_string(),
+        };
+        let category_name = self.config.category_for_window(&session.app_name, category_name, self.monitor.is_fullscreen());
+        self.config.category_for_dnd(&session.app_name, category_name, dnd_active)
+    }
+
+    fn categorize_window(app: &str, window_name: Option<&str>) -> (&'static str, ()) {
+        if window_name.is_some_and(Self::is_meeting_window) {
+            ("📅 Meetings", ())
+        } else {
+            Self::categorize_app(app)
+        }
+    }
+
+    // Zoom/Meet/Teams call windows, matched by title since the app name alone can't tell a call from chat
+    fn is_meeting_window(window_name: &str) -> bool {
+        let title_lower = window_name.to_lowercase();
+        title_lower.contains("zoom meeting") ||
+        title_lower.starts_with("meet - ") || title_lower.contains("google meet") ||
+        (title_lower.contains("teams") && title_lower.contains("meeting")) ||
+        title_lower.contains("jitsi meet") || title_lower.contains("webex meeting")
+    }
+
     fn categorize_app(app: &str) -> (&'static str, ()) {
         let app_lower = app.to_lowercase();
-        if app_lower.contains("code") || app_lower.contains("vim") || app_lower.contains("nvim") ||
+        if app == "AFK" {
+            ("💤 Away", ())
+        } else if app_lower.contains("code") || app_lower.contains("vim") || app_lower.contains("nvim") ||
            app_lower.contains("terminal") || app_lower.contains("alacritty") || app_lower.contains("kitty") ||
            app_lower.contains("rust") || app_lower.contains("cargo") || app_lower.contains("editor") ||
            app_lower.contains("vscode") || app_lower.contains("vscodium") || app_lower.contains("gedit") ||
@@ -287,17 +843,20 @@ impl Daemon {
             ("📧 Email", ())
         } else if app_lower.contains("libreoffice") || app_lower.contains("soffice") {
             ("📄 Office", ())
+        } else if app_lower.contains("obsidian") || app_lower.contains("logseq") || app_lower.contains("notion") {
+            ("🗒️ Notes", ())
         } else {
             ("📦 Other", ())
         }
     }
 
-    fn create_session_with_parsing(
+    async fn create_session_with_parsing(
         app_name: String,
         window_name: Option<String>,
         start_time: chrono::DateTime<chrono::Local>,
-        category: String,
     ) -> Session {
+        // Media players record the playing track in place of the player window
+        let window_name = media::session_window(&app_name, window_name).await;
         let parsed = if let Some(ref win_name) = window_name {
             parser::parse_window_name(&app_name, win_name)
         } else {
@@ -312,7 +871,7 @@ impl Daemon {
             window_name,
             start_time,
             duration: 0,
-            category: Some(category),
+            category: None, // Set by new_session_category once parsed
             browser_url: parsed.browser_url,
             browser_page_title: parsed.browser_page_title,
             browser_notification_count: parsed.browser_notification_count,
@@ -342,6 +901,10 @@ impl Daemon {
             parsing_success: Some(parsed.parsing_success),
             is_afk: Some(false),
             is_idle: Some(false),  // Default to not idle for new sessions
+            tag: None,
+            hostname: crate::models::session::current_hostname(),
+            note: None,
+            resumed_id: None,
         }
     }
 }
@@ -351,6 +914,25 @@ mod tests {
     use super::*;
     use tokio::time::Duration;
 
+    #[test]
+    fn test_poll_backoff() {
+        let mut backoff = PollBackoff::new();
+        for _ in 0..PollBackoff::UNCHANGED_BEFORE_BACKOFF - 1 {
+            assert_eq!(backoff.next(false, false), PollBackoff::FAST);
+        }
+        assert_eq!(backoff.next(false, false), Duration::from_millis(200));
+        for _ in 0..5 {
+            backoff.next(false, false);
+        }
+        assert_eq!(backoff.next(false, false), PollBackoff::SLOW);
+
+        // A window switch polls fast again right away
+        assert_eq!(backoff.next(true, false), PollBackoff::FAST);
+        // On battery it backs off without waiting
+        assert_eq!(backoff.next(false, true), Duration::from_millis(200));
+        assert_eq!(backoff.next(true, true), PollBackoff::FAST);
+    }
+
     #[tokio::test]
     async fn test_sleep_gap_detection() {
         // This test verifies that sleep gap detection logic works correctly
@@ -370,6 +952,30 @@ mod tests {
         println!("Sleep gap detection logic test passed");
     }
 
+    #[test]
+    fn test_switch_debounce() {
+        let debounce = chrono::Duration::milliseconds(500);
+        let start = Local::now();
+        let mut pending = None;
+        let dialog = Some("Save As".to_string());
+
+        // A dialog that flickers in and out never becomes a session
+        assert_eq!(PendingSwitch::observe(&mut pending, "gimp", &dialog, start, debounce), None);
+        assert_eq!(PendingSwitch::observe(&mut pending, "gimp", &dialog, start + chrono::Duration::milliseconds(200), debounce), None);
+
+        // A different candidate restarts the wait
+        let editor = Some("main.rs".to_string());
+        let later = start + chrono::Duration::milliseconds(300);
+        assert_eq!(PendingSwitch::observe(&mut pending, "code", &editor, later, debounce), None);
+
+        // Once stable, the switch is dated to when the window first took focus
+        assert_eq!(PendingSwitch::observe(&mut pending, "code", &editor, later + debounce, debounce), Some(later));
+        assert_eq!(pending, None);
+
+        // No debounce switches immediately
+        assert_eq!(PendingSwitch::observe(&mut pending, "code", &editor, later, chrono::Duration::zero()), Some(later));
+    }
+
     #[tokio::test]
     async fn test_afk_session_creation_logic() {
         // Test that the logic for creating AFK sessions during sleep works
@@ -0,0 +1,82 @@
+//! AFK hysteresis: the idle check runs every second, and on Wayland `last_input` is partly guessed,
+//! so idle time near the threshold can cross it back and forth and split the session into many tiny
+//! ones. The AFK state only flips once the new reading has held for `afk_hysteresis_checks` checks in a row.
+//! Which input events count as activity in the first place is decided here too.
+
+/// Whether an input event resets the idle timer: key presses and mouse clicks always do,
+/// mouse movement unless `ignore_mouse_move` is set
+pub fn counts_as_input(event_type: &rdev::EventType, ignore_mouse_move: bool) -> bool {
+    use rdev::EventType;
+    match event_type {
+        EventType::KeyPress(_) | EventType::KeyRelease(_) | EventType::ButtonPress(_) | EventType::ButtonRelease(_) => true,
+        EventType::MouseMove { .. } => !ignore_mouse_move,
+        _ => false,
+    }
+}
+
+/// Settled AFK state, fed one raw idle reading per check
+#[derive(Debug, Default)]
+pub struct AfkHysteresis {
+    afk: bool,
+    streak: u32, // Consecutive readings that disagree with `afk`
+}
+
+impl AfkHysteresis {
+    /// Record whether the idle threshold is exceeded right now; returns the settled state
+    pub fn observe(&mut self, idle: bool, checks: u32) -> bool {
+        if idle == self.afk {
+            self.streak = 0;
+        } else {
+            self.streak += 1;
+            if self.streak >= checks.max(1) {
+                self.afk = idle;
+                self.streak = 0;
+            }
+        }
+        self.afk
+    }
+
+    pub fn is_afk(&self) -> bool {
+        self.afk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_afk_needs_consecutive_checks() {
+        let mut state = AfkHysteresis::default();
+        assert!(!state.observe(true, 3));
+        assert!(!state.observe(true, 3));
+        // A reading back under the threshold starts the count over
+        assert!(!state.observe(false, 3));
+        assert!(!state.observe(true, 3));
+        assert!(!state.observe(true, 3));
+        assert!(state.observe(true, 3));
+
+        // Coming back needs the same number of active readings
+        assert!(state.observe(false, 3));
+        assert!(state.observe(true, 3));
+        assert!(state.observe(false, 3));
+        assert!(state.observe(false, 3));
+        assert!(!state.observe(false, 3));
+
+        // 0 or 1 flips straight away, as before
+        assert!(state.observe(true, 0));
+        assert!(!state.observe(false, 1));
+    }
+
+    #[test]
+    fn test_counts_as_input() {
+        use rdev::{Button, EventType, Key};
+
+        let mouse_move = EventType::MouseMove { x: 10.0, y: 20.0 };
+        assert!(counts_as_input(&mouse_move, false));
+        assert!(!counts_as_input(&mouse_move, true));
+        assert!(counts_as_input(&EventType::KeyPress(Key::KeyA), true));
+        assert!(counts_as_input(&EventType::ButtonPress(Button::Left), true));
+        assert!(!counts_as_input(&EventType::Wheel { delta_x: 0, delta_y: 1 }, false));
+    }
+}
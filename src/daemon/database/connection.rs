@@ -1,35 +1,43 @@
 use anyhow::Result;
 use sqlx::postgres::PgPool;
 use sqlx::PgPool as Pool;
+use crate::daemon::influx::InfluxExporter;
 use crate::models::session::Session;
 
 pub struct Database {
     pool: Pool,
+    influx: Option<InfluxExporter>, // Copies saved sessions to InfluxDB when configured
 }
 
 impl Database {
     pub async fn new(database_url: &str) -> Result<Self> {
         let pool = PgPool::connect(database_url).await?;
-        Ok(Self { pool })
+        Ok(Self { pool, influx: None })
+    }
+
+    pub fn set_influx_exporter(&mut self, exporter: Option<InfluxExporter>) {
+        self.influx = exporter;
     }
 
 
 
-    pub async fn get_browser_page_title_rename(&self, title: &str) -> Result<Option<String>> {
+    /// `titles` are tried in order: the trimmed title, then the raw one older sessions were saved with
+    pub async fn get_browser_page_title_rename(&self, titles: &[String]) -> Result<Option<String>> {
         let renamed: Option<(String,)> = sqlx::query_as(
-            "SELECT browser_page_title_renamed FROM sessions WHERE browser_page_title = $1 AND browser_page_title_renamed IS NOT NULL LIMIT 1"
+            "SELECT browser_page_title_renamed FROM sessions WHERE browser_page_title = ANY($1) AND browser_page_title_renamed IS NOT NULL ORDER BY array_position($1, browser_page_title) LIMIT 1"
         )
-        .bind(title)
+        .bind(titles)
         .fetch_optional(&self.pool)
         .await?;
         Ok(renamed.map(|(r,)| r))
     }
 
-    pub async fn get_browser_page_title_category(&self, title: &str) -> Result<Option<String>> {
+    /// `titles` are tried in order, like `get_browser_page_title_rename`
+    pub async fn get_browser_page_title_category(&self, titles: &[String]) -> Result<Option<String>> {
         let category: Option<(String,)> = sqlx::query_as(
-            "SELECT browser_page_title_category FROM sessions WHERE browser_page_title = $1 AND browser_page_title_category IS NOT NULL LIMIT 1"
+            "SELECT browser_page_title_category FROM sessions WHERE browser_page_title = ANY($1) AND browser_page_title_category IS NOT NULL ORDER BY array_position($1, browser_page_title) LIMIT 1"
         )
-        .bind(title)
+        .bind(titles)
         .fetch_optional(&self.pool)
         .await?;
         Ok(category.map(|(c,)| c))
@@ -97,8 +105,10 @@ impl Database {
 
     pub async fn apply_renames_and_categories(&self, session: &mut Session) -> Result<()> {
         if let Some(title) = &session.browser_page_title {
-            session.browser_page_title_renamed = self.get_browser_page_title_rename(title).await?;
-            session.browser_page_title_category = self.get_browser_page_title_category(title).await?;
+            let raw_title = session.parsed_data.as_ref().and_then(|data| data["browser_raw_title"].as_str());
+            let titles: Vec<String> = std::iter::once(title.as_str()).chain(raw_title).map(str::to_string).collect();
+            session.browser_page_title_renamed = self.get_browser_page_title_rename(&titles).await?;
+            session.browser_page_title_category = self.get_browser_page_title_category(&titles).await?;
         }
         if let Some(dir) = &session.terminal_directory {
             session.terminal_directory_renamed = self.get_terminal_directory_rename(dir).await?;
@@ -115,7 +125,130 @@ impl Database {
         Ok(())
     }
 
+    /// Non-AFK seconds per category since local midnight
+    pub async fn get_daily_category_usage(&self) -> Result<Vec<(String, i64)>> {
+        let now = chrono::Local::now();
+        let today_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_local_timezone(chrono::Local).unwrap();
+
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT category, SUM(duration)::bigint as total_duration FROM sessions WHERE start_time >= $1 AND category IS NOT NULL AND is_afk IS NOT TRUE GROUP BY category"
+        )
+        .bind(today_start)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(category, total_duration)| (category, total_duration.unwrap_or(0))).collect())
+    }
+
+    /// Sum today's non-AFK duration per app, for the app limits
+    pub async fn get_daily_usage(&self) -> Result<Vec<(String, i64)>> {
+        let now = chrono::Local::now();
+        let today_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_local_timezone(chrono::Local).unwrap();
+
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT app_name, SUM(duration)::bigint as total_duration FROM sessions WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE GROUP BY app_name"
+        )
+        .bind(today_start)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(app_name, total_duration)| (app_name, total_duration.unwrap_or(0))).collect())
+    }
+
+    /// Record that an app is past its daily limit, for the TUI banner; the table comes from the TUI's migrations
+    pub async fn upsert_app_limit_alert(&self, app_name: &str, day: chrono::NaiveDate, spent_secs: i64, limit_minutes: u64) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO app_limit_alerts (app_name, day, spent_secs, limit_minutes) VALUES ($1, $2, $3, $4) ON CONFLICT (app_name, day) DO UPDATE SET spent_secs = EXCLUDED.spent_secs, limit_minutes = EXCLUDED.limit_minutes",
+        )
+        .bind(app_name)
+        .bind(day)
+        .bind(spent_secs)
+        .bind(limit_minutes as i64)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Add a short session's time to this machine's last non-AFK session before it.
+    /// Returns false when there is no such session to absorb it.
+    pub async fn merge_into_previous_session(&self, session: &Session) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions SET duration = duration + $1
+            WHERE id = (
+                SELECT id FROM sessions
+                WHERE start_time < $2 AND hostname IS NOT DISTINCT FROM $3
+                  AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+                ORDER BY start_time DESC LIMIT 1
+            )
+            "#,
+        )
+        .bind(session.duration)
+        .bind(session.start_time)
+        .bind(&session.hostname)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The most recent session on this machine, when `session`, just started, is it carrying on after a
+    /// restart (see `Session::resumes`). Its row stays until the combined session is saved over it (via
+    /// `resumed_id`), so a second crash before then loses nothing
+    pub async fn reopen_last_session_if_recent(&self, session: &Session, max_gap_secs: u64) -> Result<Option<Session>> {
+        let previous = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE hostname = $1
+            ORDER BY start_time + duration * INTERVAL '1 second' DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(&session.hostname)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(previous.filter(|previous| previous.id.is_some() && session.resumes(previous, max_gap_secs)))
+    }
+
+    /// Save a resumed session over the row it carries on, returning that row's id; None when the
+    /// session isn't resumed or the row is gone
+    async fn update_resumed_session(&self, session: &Session) -> Result<Option<i32>> {
+        let Some(id) = session.resumed_id else {
+            return Ok(None);
+        };
+        let result = sqlx::query("UPDATE sessions SET duration = $1, tag = $2 WHERE id = $3")
+            .bind(session.duration)
+            .bind(&session.tag)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok((result.rows_affected() > 0).then_some(id))
+    }
+
+    /// Record that this machine's daemon is running; the table comes from the TUI's migrations
+    pub async fn touch_daemon_heartbeat(&self) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO daemon_heartbeat (hostname, last_seen) VALUES ($1, NOW()) ON CONFLICT (hostname) DO UPDATE SET last_seen = EXCLUDED.last_seen",
+        )
+        .bind(crate::models::session::current_hostname().unwrap_or_default())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn insert_session(&self, session: &Session) -> Result<i32> {
+        if let Some(id) = self.update_resumed_session(session).await? {
+            return Ok(id);
+        }
         let id: (i32,) = sqlx::query_as(
             r#"
             INSERT INTO sessions (
@@ -187,6 +320,23 @@ impl Database {
         .bind(session.is_afk)
         .fetch_one(&self.pool)
         .await?;
+        // Written separately so sessions still save on databases the TUI hasn't migrated yet
+        if session.tag.is_some() || session.hostname.is_some() || session.note.is_some() || session.is_idle.is_some() {
+            let extras = sqlx::query("UPDATE sessions SET tag = $1, hostname = $2, note = $3, is_idle = $4 WHERE id = $5")
+                .bind(&session.tag)
+                .bind(&session.hostname)
+                .bind(&session.note)
+                .bind(session.is_idle)
+                .bind(id.0)
+                .execute(&self.pool)
+                .await;
+            if let Err(e) = extras {
+                log::warn!("Failed to save tag, hostname, note and idle flag for session {}: {}", id.0, e);
+            }
+        }
+        if let Some(influx) = &self.influx {
+            influx.export(session);
+        }
         Ok(id.0)
     }
 }
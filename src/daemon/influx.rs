@@ -0,0 +1,151 @@
+//! Optional InfluxDB export: every session the daemon saves is also written as a line-protocol
+//! point, for long-term dashboards in Grafana. Writes go through `curl` in a background task,
+//! so a slow or unreachable InfluxDB never holds up tracking or the PostgreSQL insert.
+
+use crate::config::settings::AppConfig;
+use crate::models::session::Session;
+use std::env;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+
+const MEASUREMENT: &str = "sessions";
+
+#[derive(Debug, Clone)]
+pub struct InfluxExporter {
+    write_url: String,
+    token: Option<String>,
+}
+
+impl InfluxExporter {
+    /// None unless both `influx_url` and `influx_bucket` are configured.
+    /// INFLUX_TOKEN in .env takes precedence over `influx_token` in config.toml
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let url = config.influx_url.as_deref().filter(|url| !url.is_empty())?;
+        let bucket = config.influx_bucket.as_deref().filter(|bucket| !bucket.is_empty())?;
+        let mut write_url = format!("{}/api/v2/write?bucket={}&precision=s", url.trim_end_matches('/'), query_escape(bucket));
+        if let Some(org) = config.influx_org.as_deref().filter(|org| !org.is_empty()) {
+            write_url.push_str(&format!("&org={}", query_escape(org)));
+        }
+        let token = env::var("INFLUX_TOKEN").ok().or_else(|| config.influx_token.clone());
+        Some(Self { write_url, token })
+    }
+
+    /// Write the session in the background; failures are logged and otherwise ignored
+    pub fn export(&self, session: &Session) {
+        let exporter = self.clone();
+        let point = line_protocol(session);
+        tokio::spawn(async move {
+            if let Err(e) = exporter.write(&point).await {
+                log::warn!("Failed to export session to InfluxDB: {}", e);
+            }
+        });
+    }
+
+    // The token and point go to curl on stdin as a config file, keeping the token out of the process list
+    async fn write(&self, point: &str) -> anyhow::Result<()> {
+        let mut config = format!("url = \"{}\"\n", curl_quote(&self.write_url));
+        if let Some(token) = &self.token {
+            config.push_str(&format!("header = \"Authorization: Token {}\"\n", curl_quote(token)));
+        }
+        config.push_str("header = \"Content-Type: text/plain; charset=utf-8\"\n");
+        config.push_str(&format!("data-binary = \"{}\"\n", curl_quote(point)));
+
+        let mut child = tokio::process::Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--max-time", "10", "--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(config.as_bytes()).await?;
+        }
+        let output = child.wait_with_output().await?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("curl: {}", String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(())
+    }
+}
+
+/// One point per session, timestamped at its start, so saving the same session again
+/// (the hourly auto-save, then the final save) overwrites the point instead of adding one
+pub fn line_protocol(session: &Session) -> String {
+    let mut point = format!("{},app={}", MEASUREMENT, escape_tag(&session.app_name));
+    if let Some(category) = session.category.as_deref().filter(|category| !category.is_empty()) {
+        point.push_str(&format!(",category={}", escape_tag(category)));
+    }
+    let project = [&session.ide_project_name, &session.terminal_project_name]
+        .into_iter()
+        .flatten()
+        .find(|project| !project.is_empty());
+    if let Some(project) = project {
+        point.push_str(&format!(",project={}", escape_tag(project)));
+    }
+    point.push_str(&format!(
+        " duration={}i,afk={} {}",
+        session.duration,
+        session.is_afk.unwrap_or(false),
+        session.start_time.timestamp()
+    ));
+    point
+}
+
+/// Tag keys and values escape commas, equals signs and spaces; newlines would end the point
+fn escape_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+        .replace(['\n', '\r'], "\\ ")
+}
+
+/// Percent-encodes everything but unreserved characters, for a query string value
+fn query_escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn curl_quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_protocol() {
+        let session = Session {
+            category: Some("💻 Development".to_string()),
+            ide_project_name: Some("neura hustle, tracker".to_string()),
+            is_afk: Some(false),
+            ..Session::for_test("code", chrono::TimeZone::timestamp_opt(&chrono::Local, 1741597200, 0).unwrap(), 1500)
+        };
+        assert_eq!(
+            line_protocol(&session),
+            "sessions,app=code,category=💻\\ Development,project=neura\\ hustle\\,\\ tracker duration=1500i,afk=false 1741597200"
+        );
+    }
+
+    #[test]
+    fn test_exporter_needs_url_and_bucket() {
+        let mut config = AppConfig { influx_url: Some("http://localhost:8086/".to_string()), ..AppConfig::default() };
+        assert!(InfluxExporter::from_config(&config).is_none());
+
+        config.influx_bucket = Some("hustle".to_string());
+        config.influx_org = Some("home".to_string());
+        let exporter = InfluxExporter::from_config(&config).unwrap();
+        assert_eq!(exporter.write_url, "http://localhost:8086/api/v2/write?bucket=hustle&precision=s&org=home");
+
+        config.influx_bucket = Some("time tracking".to_string());
+        config.influx_org = Some("R&D".to_string());
+        let exporter = InfluxExporter::from_config(&config).unwrap();
+        assert_eq!(exporter.write_url, "http://localhost:8086/api/v2/write?bucket=time%20tracking&precision=s&org=R%26D");
+    }
+}
@@ -0,0 +1,209 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of what the daemon is tracking, served to clients over the status socket
+#[derive(Debug, Clone, Default)]
+pub struct DaemonStatus {
+    pub current_app: String,
+    pub current_window: Option<String>,
+    pub session_secs: i64,
+    pub is_afk: bool,
+    /// Today's non-AFK totals per category, in seconds
+    pub today_totals: Vec<(String, i64)>,
+    /// Tag given to every new session until cleared; set by clients with `tag <label>`
+    pub sticky_tag: Option<String>,
+}
+
+impl DaemonStatus {
+    /// Line protocol reply to `status`: tab-separated `key\tvalue` lines terminated by `end`
+    fn to_lines(&self) -> String {
+        let mut reply = format!(
+            "app\t{}\nwindow\t{}\nsession\t{}\nafk\t{}\n",
+            self.current_app,
+            self.current_window.as_deref().unwrap_or(""),
+            self.session_secs,
+            self.is_afk
+        );
+        if let Some(tag) = &self.sticky_tag {
+            reply.push_str(&format!("tag\t{}\n", tag));
+        }
+        for (category, secs) in &self.today_totals {
+            reply.push_str(&format!("total\t{}\t{}\n", category, secs));
+        }
+        reply.push_str("end\n");
+        reply
+    }
+}
+
+/// Reply to one request line; supports `ping`, `status` and `tag [label]` (no label clears the tag)
+fn handle_request(request: &str, status: &Mutex<DaemonStatus>) -> String {
+    match request.trim() {
+        "ping" => "pong\n".to_string(),
+        "status" => status.lock().unwrap().to_lines(),
+        "tag" => {
+            status.lock().unwrap().sticky_tag = None;
+            "ok\n".to_string()
+        }
+        other => match other.strip_prefix("tag ") {
+            Some(tag) => {
+                let tag = tag.trim();
+                status.lock().unwrap().sticky_tag = (!tag.is_empty()).then(|| tag.to_string());
+                "ok\n".to_string()
+            }
+            None => format!("error\tunknown command: {}\n", other),
+        },
+    }
+}
+
+/// Bind the status socket and serve requests in the background until the daemon exits
+#[cfg(unix)]
+pub fn spawn_server(path: PathBuf, status: Arc<Mutex<DaemonStatus>>) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    // A socket left behind by a crashed daemon would make bind fail
+    if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    let listener = UnixListener::bind(&path)?;
+    log::info!("Status socket listening on {}", path.display());
+
+    tokio::spawn(async move {
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    log::warn!("Status socket accept failed: {}", e);
+                    continue;
+                }
+            };
+
+            let status = Arc::clone(&status);
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = BufReader::new(reader).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let reply = handle_request(&line, &status);
+                    if writer.write_all(reply.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn spawn_server(_path: PathBuf, _status: Arc<Mutex<DaemonStatus>>) -> Result<()> {
+    Err(anyhow::anyhow!("Status socket requires Unix domain sockets, which this platform does not support"))
+}
+
+/// Ask a running daemon for its status and format it for the terminal
+#[cfg(unix)]
+pub async fn query_status(path: &Path) -> Result<String> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    let stream = UnixStream::connect(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Could not connect to daemon at {}: {}", path.display(), e))?;
+    let (reader, mut writer) = stream.into_split();
+    writer.write_all(b"status\n").await?;
+
+    let mut output = String::new();
+    let mut totals_header = false;
+    let mut lines = BufReader::new(reader).lines();
+    while let Some(line) = lines.next_line().await? {
+        let fields: Vec<&str> = line.split('\t').collect();
+        match fields.as_slice() {
+            ["end"] => break,
+            ["app", app] => output.push_str(&format!("Current app:  {}\n", app)),
+            ["window", window] if !window.is_empty() => output.push_str(&format!("Window:       {}\n", window)),
+            ["session", secs] => {
+                let secs: i64 = secs.parse().unwrap_or(0);
+                output.push_str(&format!("Session:      {}m {}s\n", secs / 60, secs % 60));
+            }
+            ["afk", afk] if *afk == "true" => output.push_str("Status:       AFK\n"),
+            ["tag", tag] => output.push_str(&format!("Tag:          {}\n", tag)),
+            ["total", category, secs] => {
+                if !totals_header {
+                    output.push_str("Today:\n");
+                    totals_header = true;
+                }
+                let secs: i64 = secs.parse().unwrap_or(0);
+                output.push_str(&format!("  {:<20} {}h {}m\n", category, secs / 3600, (secs % 3600) / 60));
+            }
+            ["error", message] => return Err(anyhow::anyhow!("Daemon error: {}", message)),
+            _ => {}
+        }
+    }
+    Ok(output)
+}
+
+#[cfg(not(unix))]
+pub async fn query_status(_path: &Path) -> Result<String> {
+    Err(anyhow::anyhow!("Status socket requires Unix domain sockets, which this platform does not support"))
+}
+
+/// Set (or with None, clear) the running daemon's sticky tag
+#[cfg(unix)]
+pub async fn send_tag(path: &Path, tag: Option<&str>) -> Result<()> {
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixStream;
+
+    let stream = UnixStream::connect(path)
+        .await
+        .map_err(|e| anyhow::anyhow!("Could not connect to daemon at {}: {}", path.display(), e))?;
+    let (reader, mut writer) = stream.into_split();
+    let request = match tag {
+        Some(tag) => format!("tag {}\n", tag.replace('\n', " ")),
+        None => "tag\n".to_string(),
+    };
+    writer.write_all(request.as_bytes()).await?;
+
+    let reply = BufReader::new(reader).lines().next_line().await?.unwrap_or_default();
+    match reply.split_once('\t') {
+        Some(("error", message)) => Err(anyhow::anyhow!("Daemon error: {}", message)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(not(unix))]
+pub async fn send_tag(_path: &Path, _tag: Option<&str>) -> Result<()> {
+    Err(anyhow::anyhow!("Status socket requires Unix domain sockets, which this platform does not support"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_status_socket_round_trip() {
+        let path = std::env::temp_dir().join(format!("hustle-status-test-{}.sock", std::process::id()));
+        let status = Arc::new(Mutex::new(DaemonStatus {
+            current_app: "alacritty".to_string(),
+            current_window: Some("nvim main.rs".to_string()),
+            session_secs: 125,
+            is_afk: false,
+            today_totals: vec![("💻 Development".to_string(), 5400)],
+            sticky_tag: None,
+        }));
+
+        spawn_server(path.clone(), Arc::clone(&status)).unwrap();
+        send_tag(&path, Some("PROJ-123")).await.unwrap();
+        assert_eq!(status.lock().unwrap().sticky_tag.as_deref(), Some("PROJ-123"));
+        let output = query_status(&path).await.unwrap();
+        send_tag(&path, None).await.unwrap();
+        assert_eq!(status.lock().unwrap().sticky_tag, None);
+        std::fs::remove_file(&path).ok();
+
+        assert!(output.contains("Tag:          PROJ-123"));
+        assert!(output.contains("Current app:  alacritty"));
+        assert!(output.contains("Session:      2m 5s"));
+        assert!(output.contains("💻 Development"));
+        assert!(output.contains("1h 30m"));
+    }
+}
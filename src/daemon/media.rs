@@ -0,0 +1,154 @@
+//! Now-playing lookup, so media player sessions record the track instead of a bare player window
+
+use anyhow::Result;
+
+/// Players whose sessions are worth a now-playing lookup
+const MEDIA_PLAYERS: &[&str] = &[
+    "spotify", "vlc", "rhythmbox", "audacious", "clementine", "strawberry", "elisa",
+    "lollypop", "mpv", "celluloid", "totem", "music",
+];
+
+/// Whether the app is a known media player
+pub fn is_media_player(app_name: &str) -> bool {
+    let app_lower = app_name.to_lowercase();
+    MEDIA_PLAYERS.iter().any(|player| app_lower == *player || app_lower.ends_with(&format!(".{}", player)))
+}
+
+/// "Artist - Title" for the current track, or just the title when no artist is reported
+pub fn track_label(artists: &[String], title: &str) -> Option<String> {
+    let title = title.trim();
+    if title.is_empty() {
+        return None;
+    }
+    let artists: Vec<&str> = artists.iter().map(|a| a.trim()).filter(|a| !a.is_empty()).collect();
+    if artists.is_empty() {
+        Some(title.to_string())
+    } else {
+        Some(format!("{} - {}", artists.join(", "), title))
+    }
+}
+
+/// The window name to record for a new session: the playing track for media players, otherwise
+/// the detected window
+pub async fn session_window(app_name: &str, window_name: Option<String>) -> Option<String> {
+    if !is_media_player(app_name) {
+        return window_name;
+    }
+    match now_playing(app_name).await {
+        Ok(Some(track)) => Some(track),
+        Ok(None) => window_name,
+        Err(e) => {
+            log::debug!("Now-playing lookup for {} failed: {}", app_name, e);
+            window_name
+        }
+    }
+}
+
+/// The playing track from the MPRIS players on the session bus, preferring the one matching the app
+#[cfg(target_os = "linux")]
+pub async fn now_playing(app_name: &str) -> Result<Option<String>> {
+    use std::collections::HashMap;
+    use zbus::zvariant::{OwnedValue, Value};
+
+    let connection = zbus::Connection::session().await?;
+    let response = connection.call_method(
+        Some("org.freedesktop.DBus"),
+        "/org/freedesktop/DBus",
+        Some("org.freedesktop.DBus"),
+        "ListNames",
+        &(),
+    ).await?;
+    let names: Vec<String> = response.body().deserialize()?;
+
+    let app_lower = app_name.to_lowercase();
+    let mut players: Vec<String> = names.into_iter().filter(|name| name.starts_with("org.mpris.MediaPlayer2.")).collect();
+    players.sort_by_key(|name| !name.to_lowercase().contains(&app_lower));
+
+    for player in players {
+        let status = match player_property(&connection, &player, "PlaybackStatus").await {
+            Ok(status) => status,
+            Err(e) => {
+                log::debug!("PlaybackStatus for {} failed: {}", player, e);
+                continue;
+            }
+        };
+        if String::try_from(status).ok().as_deref() != Some("Playing") {
+            continue;
+        }
+
+        let metadata = player_property(&connection, &player, "Metadata").await?;
+        let metadata: HashMap<String, OwnedValue> = HashMap::try_from(metadata)?;
+        let field = |key: &str| metadata.get(key).and_then(|value| value.try_clone().ok()).map(Value::from);
+        let title = field("xesam:title").and_then(|value| value.downcast::<String>().ok()).unwrap_or_default();
+        let artists = field("xesam:artist").and_then(|value| value.downcast::<Vec<String>>().ok()).unwrap_or_default();
+        if let Some(track) = track_label(&artists, &title) {
+            return Ok(Some(track));
+        }
+    }
+    Ok(None)
+}
+
+/// One property of an MPRIS player's org.mpris.MediaPlayer2.Player interface
+#[cfg(target_os = "linux")]
+async fn player_property(connection: &zbus::Connection, player: &str, property: &str) -> Result<zbus::zvariant::OwnedValue> {
+    let response = connection.call_method(
+        Some(player),
+        "/org/mpris/MediaPlayer2",
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.mpris.MediaPlayer2.Player", property),
+    ).await?;
+    Ok(response.body().deserialize()?)
+}
+
+/// The playing track from Spotify or Music via AppleScript. Other players have no scripting
+/// interface for it, and telling an app that isn't running to do anything launches it
+#[cfg(target_os = "macos")]
+pub async fn now_playing(app_name: &str) -> Result<Option<String>> {
+    let app_lower = app_name.to_lowercase();
+    let application = if app_lower.contains("spotify") {
+        "Spotify"
+    } else if app_lower == "music" || app_lower.ends_with(".music") {
+        "Music"
+    } else {
+        return Ok(None);
+    };
+    let script = format!(
+        "tell application \"{}\" to if player state is playing then artist of current track & \"\\n\" & name of current track",
+        application
+    );
+    let output = tokio::process::Command::new("osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("osascript failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.trim().splitn(2, '\n');
+    let artist = lines.next().unwrap_or_default().to_string();
+    let title = lines.next().unwrap_or_default();
+    Ok(track_label(&[artist], title))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub async fn now_playing(_app_name: &str) -> Result<Option<String>> {
+    Err(anyhow::anyhow!("Now-playing lookup isn't supported on this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_label() {
+        assert_eq!(track_label(&["Daft Punk".to_string()], "One More Time"), Some("Daft Punk - One More Time".to_string()));
+        assert_eq!(track_label(&["A".to_string(), "B".to_string()], "Duet"), Some("A, B - Duet".to_string()));
+        assert_eq!(track_label(&[], "Podcast Episode 12"), Some("Podcast Episode 12".to_string()));
+        assert_eq!(track_label(&[" ".to_string()], "  "), None);
+        assert!(is_media_player("Spotify"));
+        assert!(is_media_player("org.gnome.Totem"));
+        assert!(!is_media_player("firefox"));
+    }
+}
@@ -1,3 +1,11 @@
 pub mod active_window;
+pub mod afk;
 pub mod database;
+pub mod influx;
+pub mod ipc;
+pub mod media;
+pub mod notifications;
+pub mod power;
+pub mod screen_lock;
+pub mod title_trim;
 pub mod tracker;
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use std::process::Command;
+
+/// Show a desktop notification using the platform's native mechanism. Urgent ones stay on screen
+/// until dismissed on Linux and play a sound on macOS
+pub fn send_desktop_notification(title: &str, body: &str, urgent: bool) -> Result<()> {
+    #[cfg(target_os = "linux")]
+    let output = Command::new("notify-send")
+        .arg("--app-name=Neura Hustle Tracker")
+        .arg(if urgent { "--urgency=critical" } else { "--urgency=normal" })
+        .arg(title)
+        .arg(body)
+        .output()?;
+
+    #[cfg(target_os = "macos")]
+    let output = {
+        let escape = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            "display notification \"{}\" with title \"Neura Hustle Tracker\" subtitle \"{}\"{}",
+            escape(body),
+            escape(title),
+            if urgent { " sound name \"Sosumi\"" } else { "" }
+        );
+        Command::new("osascript").arg("-e").arg(script).output()?
+    };
+
+    #[cfg(target_os = "windows")]
+    let output = {
+        // Toast via the WinRT notification API, reachable from PowerShell without extra modules
+        let escape = |s: &str| s.replace('\'', "''").replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        let script = format!(
+            r#"
+            [Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
+            [Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
+            $xml = New-Object Windows.Data.Xml.Dom.XmlDocument
+            $xml.LoadXml('<toast{}><visual><binding template="ToastGeneric"><text>{}</text><text>{}</text></binding></visual></toast>')
+            $toast = New-Object Windows.UI.Notifications.ToastNotification $xml
+            [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier('Neura Hustle Tracker').Show($toast)
+            "#,
+            // Reminders stay on screen until dismissed
+            if urgent { " scenario=\"reminder\"" } else { "" },
+            escape(title),
+            escape(body)
+        );
+        Command::new("powershell").arg("-NoProfile").arg("-Command").arg(script).output()?
+    };
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow::anyhow!("Notification command failed: {}", error.trim()));
+    }
+    Ok(())
+}
+
+/// Whether Do-Not-Disturb is on: the freedesktop Notifications "Inhibited" property (KDE Plasma),
+/// falling back to GNOME's show-banners setting
+#[cfg(target_os = "linux")]
+pub async fn dnd_active() -> Result<bool> {
+    let connection = zbus::Connection::session().await?;
+    match connection.call_method(
+        Some("org.freedesktop.Notifications"),
+        "/org/freedesktop/Notifications",
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.freedesktop.Notifications", "Inhibited"),
+    ).await {
+        Ok(response) => {
+            let inhibited: zbus::zvariant::OwnedValue = response.body().deserialize()?;
+            Ok(bool::try_from(inhibited)?)
+        }
+        Err(e) => {
+            log::debug!("Notifications Inhibited property failed: {}", e);
+            // GNOME stores DND as show-banners = false
+            let output = tokio::process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+                .output()
+                .await?;
+            if !output.status.success() {
+                return Err(anyhow::anyhow!("No Do-Not-Disturb state found over D-Bus or GSettings"));
+            }
+            Ok(String::from_utf8_lossy(&output.stdout).trim() == "false")
+        }
+    }
+}
+
+/// Whether Do-Not-Disturb is on, from the Notification Center preference
+#[cfg(target_os = "macos")]
+pub async fn dnd_active() -> Result<bool> {
+    let output = tokio::process::Command::new("defaults")
+        .args(["-currentHost", "read", "com.apple.notificationcenterui", "doNotDisturb"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("doNotDisturb preference not set"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim() == "1")
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub async fn dnd_active() -> Result<bool> {
+    Err(anyhow::anyhow!("Do-Not-Disturb detection isn't supported on this platform"))
+}
@@ -0,0 +1,38 @@
+//! Power source detection, so the daemon can poll less often on battery
+
+use anyhow::Result;
+
+/// Whether the machine is running on battery: a discharging battery under /sys/class/power_supply
+/// with no AC adapter online
+#[cfg(target_os = "linux")]
+pub async fn on_battery() -> Result<bool> {
+    let mut discharging = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply")? {
+        let path = entry?.path();
+        let read = |name: &str| std::fs::read_to_string(path.join(name)).map(|value| value.trim().to_string()).unwrap_or_default();
+        match read("type").as_str() {
+            "Mains" if read("online") == "1" => return Ok(false),
+            "Battery" if read("status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    Ok(discharging)
+}
+
+/// Whether the machine is running on battery, from `pmset -g batt` ("Now drawing from 'Battery Power'")
+#[cfg(target_os = "macos")]
+pub async fn on_battery() -> Result<bool> {
+    let output = tokio::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .await?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!("pmset failed"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).contains("'Battery Power'"))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub async fn on_battery() -> Result<bool> {
+    Err(anyhow::anyhow!("Power source detection isn't supported on this platform"))
+}
@@ -0,0 +1,124 @@
+//! Screen lock as immediate AFK (`lock_is_afk`): logind's Lock/Unlock signals and the screensaver's
+//! ActiveChanged signal flip a shared flag that the AFK check reads, so locking the screen ends the
+//! session right away instead of after the idle threshold. Linux only; elsewhere the flag stays unset.
+
+use chrono::{DateTime, Local};
+use std::sync::atomic::AtomicBool;
+#[cfg(target_os = "linux")]
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+
+/// Watch for lock and unlock in the background
+#[cfg(target_os = "linux")]
+pub fn spawn_watcher(locked: Arc<AtomicBool>, last_input: Arc<Mutex<DateTime<Local>>>) {
+    let (logind_locked, logind_input) = (Arc::clone(&locked), Arc::clone(&last_input));
+    tokio::spawn(async move {
+        if let Err(e) = watch_logind(&logind_locked, &logind_input).await {
+            log::info!("logind lock signals unavailable: {}", e);
+        }
+    });
+    tokio::spawn(async move {
+        if let Err(e) = watch_screensaver(&locked, &last_input).await {
+            log::info!("ScreenSaver signals unavailable: {}", e);
+        }
+    });
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn spawn_watcher(_locked: Arc<AtomicBool>, _last_input: Arc<Mutex<DateTime<Local>>>) {
+    log::info!("Screen lock detection isn't supported on this platform; idle detection still applies");
+}
+
+// Unlocking counts as input, since someone just typed their password
+#[cfg(target_os = "linux")]
+fn set_locked(locked: &AtomicBool, last_input: &Mutex<DateTime<Local>>, is_locked: bool) {
+    if locked.swap(is_locked, Ordering::Relaxed) == is_locked {
+        return;
+    }
+    if !is_locked {
+        *last_input.lock().unwrap() = Local::now();
+    }
+    log::info!("Screen {}", if is_locked { "locked" } else { "unlocked" });
+}
+
+// Lock/Unlock on this login session's object, from the system bus
+#[cfg(target_os = "linux")]
+async fn watch_logind(locked: &AtomicBool, last_input: &Mutex<DateTime<Local>>) -> anyhow::Result<()> {
+    use futures_util::StreamExt;
+
+    let connection = zbus::Connection::system().await?;
+    let response = connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1",
+        Some("org.freedesktop.login1.Manager"),
+        "GetSession",
+        &("auto",),
+    ).await?;
+    let session_path: zbus::zvariant::OwnedObjectPath = response.body().deserialize()?;
+
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .sender("org.freedesktop.login1")?
+        .interface("org.freedesktop.login1.Session")?
+        .path(session_path)?
+        .build();
+    let mut signals = zbus::MessageStream::for_match_rule(rule, &connection, None).await?;
+    while let Some(message) = signals.next().await {
+        match message?.header().member().map(|member| member.as_str()) {
+            Some("Lock") => set_locked(locked, last_input, true),
+            Some("Unlock") => set_locked(locked, last_input, false),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+// ActiveChanged from GNOME (org.gnome.ScreenSaver) or KDE Plasma (org.freedesktop.ScreenSaver), on the session bus
+#[cfg(target_os = "linux")]
+async fn watch_screensaver(locked: &AtomicBool, last_input: &Mutex<DateTime<Local>>) -> anyhow::Result<()> {
+    use futures_util::StreamExt;
+
+    let connection = zbus::Connection::session().await?;
+    let rule = zbus::MatchRule::builder()
+        .msg_type(zbus::message::Type::Signal)
+        .member("ActiveChanged")?
+        .build();
+    let mut signals = zbus::MessageStream::for_match_rule(rule, &connection, None).await?;
+    while let Some(message) = signals.next().await {
+        let message = message?;
+        let from_screensaver = message
+            .header()
+            .interface()
+            .is_some_and(|interface| interface.as_str().ends_with(".ScreenSaver"));
+        if from_screensaver {
+            let active: bool = message.body().deserialize()?;
+            set_locked(locked, last_input, active);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_counts_as_input() {
+        let locked = AtomicBool::new(false);
+        let long_ago = Local::now() - chrono::Duration::hours(1);
+        let last_input = Mutex::new(long_ago);
+
+        set_locked(&locked, &last_input, true);
+        assert!(locked.load(Ordering::Relaxed));
+        assert_eq!(*last_input.lock().unwrap(), long_ago);
+
+        set_locked(&locked, &last_input, false);
+        assert!(!locked.load(Ordering::Relaxed));
+        assert!(*last_input.lock().unwrap() > long_ago);
+
+        // A repeated unlock (logind and the screensaver both report it) changes nothing
+        *last_input.lock().unwrap() = long_ago;
+        set_locked(&locked, &last_input, false);
+        assert_eq!(*last_input.lock().unwrap(), long_ago);
+    }
+}
@@ -0,0 +1,107 @@
+//! Browser page title trimming, shared by the TUI and daemon parsers so both store the same title
+//! for a page: volatile parts (unread counts, site suffixes) would otherwise give one page many titles.
+
+use crate::config::settings::TitleTrimRule;
+use regex::Regex;
+use std::sync::RwLock;
+
+/// `title` trimmed with the built-in rules and the active `title_trim_rules`
+pub fn trim(title: &str, service: Option<&str>) -> String {
+    trim_page_title(title, service, &TITLE_TRIM_RULES.read().unwrap())
+}
+
+/// Compiled `title_trim_rules`
+static TITLE_TRIM_RULES: RwLock<Vec<(Regex, String)>> = RwLock::new(Vec::new());
+
+/// Replaces the active rules; called at startup and whenever the config is reloaded or saved
+pub fn set_title_trim_rules(rules: &[TitleTrimRule]) {
+    *TITLE_TRIM_RULES.write().unwrap() = compile_trim_rules(rules);
+}
+
+/// Invalid patterns are skipped with a warning rather than failing startup
+fn compile_trim_rules(rules: &[TitleTrimRule]) -> Vec<(Regex, String)> {
+    rules
+        .iter()
+        .filter_map(|rule| match Regex::new(&rule.pattern) {
+            Ok(regex) => Some((regex, rule.replacement.clone())),
+            Err(e) => {
+                log::warn!("Ignoring title trim rule '{}': {}", rule.pattern, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// Page title with the browser name, a trailing " - Service" segment naming the detected service and
+/// unread counts like "(47)" removed, then the user's rules applied. Never trims a title down to nothing
+fn trim_page_title(title: &str, service: Option<&str>, rules: &[(Regex, String)]) -> String {
+    const BROWSER_SUFFIXES: [&str; 6] = ["Google Chrome", "Chromium", "Brave", "Mozilla Firefox", "Firefox", "Microsoft Edge"];
+    const SEPARATORS: [&str; 4] = [" - ", " — ", " | ", " · "];
+
+    let mut trimmed = title.trim();
+    if let Some((rest, _)) = trimmed.rsplit_once(" - ").filter(|(_, last)| BROWSER_SUFFIXES.contains(&last.trim())) {
+        trimmed = rest.trim_end();
+    }
+    if let Some(service) = service {
+        let names: Vec<String> = service.split('/').map(str::to_lowercase).collect();
+        let site_suffix = SEPARATORS.iter().find_map(|separator| {
+            let (rest, last) = trimmed.rsplit_once(separator)?;
+            let last = last.trim().to_lowercase();
+            names.iter().any(|name| last.contains(name.as_str())).then_some(rest.trim_end())
+        });
+        if let Some(rest) = site_suffix {
+            trimmed = rest;
+        }
+    }
+    trimmed = strip_count(trimmed);
+
+    let mut result = trimmed.to_string();
+    for (regex, replacement) in rules {
+        result = regex.replace_all(&result, replacement.as_str()).into_owned();
+    }
+    let result = result.trim();
+    if result.is_empty() { title.trim().to_string() } else { result.to_string() }
+}
+
+// "(2) Inbox (47)" -> "Inbox"; four digits are more likely a year than an unread count
+fn strip_count(title: &str) -> &str {
+    let is_count = |text: &str| {
+        let digits = text.strip_prefix('(').and_then(|text| text.strip_suffix(')')).unwrap_or("");
+        (1..=3).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_digit())
+    };
+    let mut title = title.trim();
+    if let Some((_, rest)) = title.split_once(' ').filter(|(first, _)| is_count(first)) {
+        title = rest.trim_start();
+    }
+    if let Some((rest, _)) = title.rsplit_once(' ').filter(|(_, last)| is_count(last)) {
+        title = rest.trim_end();
+    }
+    title
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_page_title() {
+        assert_eq!(trim_page_title("(2) Inbox (47) - Gmail", Some("Gmail"), &[]), "Inbox");
+        assert_eq!(trim_page_title("Pull requests · rust-lang/rust · GitHub", Some("GitHub"), &[]), "Pull requests · rust-lang/rust");
+        assert_eq!(trim_page_title("Home / X", Some("Twitter/X"), &[]), "Home / X");
+        assert_eq!(trim_page_title("Home | X", Some("Twitter/X"), &[]), "Home");
+        // Without a detected service the last segment could be the page itself, so it stays
+        assert_eq!(trim_page_title("Rust - Wikipedia", None, &[]), "Rust - Wikipedia");
+        assert_eq!(trim_page_title("Release notes (2024)", None, &[]), "Release notes (2024)");
+
+        let rules = compile_trim_rules(&[
+            TitleTrimRule { pattern: r" \| Jira$".to_string(), replacement: String::new() },
+            TitleTrimRule { pattern: r"^\[[A-Z]+-\d+\] ".to_string(), replacement: "ticket: ".to_string() },
+            TitleTrimRule { pattern: "(unclosed".to_string(), replacement: String::new() },
+        ]);
+        assert_eq!(rules.len(), 2);
+        assert_eq!(trim_page_title("[PROJ-12] Fix login | Jira", None, &rules), "ticket: Fix login");
+        // A rule that removes everything leaves the title untouched
+        let everything = compile_trim_rules(&[TitleTrimRule { pattern: ".*".to_string(), replacement: String::new() }]);
+        assert_eq!(trim_page_title("Dashboard", None, &everything), "Dashboard");
+    }
+}
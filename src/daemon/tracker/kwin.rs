@@ -0,0 +1,213 @@
+//! Active window lookup on KDE Plasma Wayland, where the GNOME 'Window Calls' extension doesn't exist.
+//! KWin scripts can't return values to the caller, so a small script, loaded once, reports the active
+//! window back to a D-Bus object registered on our own connection whenever it changes. `kdotool` is the fallback.
+//! Shared by the daemon and the TUI; each process loads its own script, named after its pid.
+
+use anyhow::Result;
+use std::env;
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::watch;
+
+const SCRIPT_PREFIX: &str = "neura-hustle-active-window";
+const REPORT_PATH: &str = "/com/neura/HustleTracker/KWin";
+const REPORT_INTERFACE: &str = "com.neura.HustleTracker.KWin";
+
+/// Plasma sets KDE_SESSION_VERSION; XDG_CURRENT_DESKTOP may list several desktops ("KDE" or "KDE:plasma")
+pub fn is_kde_plasma() -> bool {
+    env::var("KDE_SESSION_VERSION").is_ok()
+        || env::var("XDG_CURRENT_DESKTOP")
+            .map(|desktop| desktop.split(':').any(|part| part.eq_ignore_ascii_case("KDE")))
+            .unwrap_or(false)
+}
+
+/// Active window as `(wm_class, title, fullscreen)`, the same shape as the GNOME path.
+/// kdotool can't tell whether a window is fullscreen, so that path reports false
+pub async fn get_active_window() -> Result<(String, String, bool)> {
+    match get_active_window_scripting().await {
+        Ok(window) => Ok(window),
+        Err(scripting_err) => {
+            log::debug!("KWin scripting failed ({}), trying kdotool", scripting_err);
+            get_active_window_kdotool().map(|(wm_class, title)| (wm_class, title, false)).map_err(|kdotool_err| {
+                anyhow::anyhow!("KWin scripting failed: {}; kdotool failed: {}", scripting_err, kdotool_err)
+            })
+        }
+    }
+}
+
+/// Last window the loaded KWin script reported; None until its first report
+type Report = Option<(String, String, bool)>;
+
+struct Reporter {
+    sender: watch::Sender<Report>,
+}
+
+#[zbus::interface(name = "com.neura.HustleTracker.KWin")]
+impl Reporter {
+    fn report(&self, wm_class: String, title: String, fullscreen: bool) {
+        self.sender.send_replace(Some((wm_class, title, fullscreen)));
+    }
+}
+
+/// The script stays loaded and reports over this connection, which has to stay open for it
+struct LoadedScript {
+    connection: zbus::Connection,
+    latest: watch::Receiver<Report>,
+}
+
+/// Plugin name of this process's script, so processes never unload each other's
+fn script_name() -> String {
+    format!("{}-{}", SCRIPT_PREFIX, std::process::id())
+}
+
+async fn is_script_loaded(connection: &zbus::Connection) -> Result<bool> {
+    let reply = connection
+        .call_method(Some("org.kde.KWin"), "/Scripting", Some("org.kde.kwin.Scripting"), "isScriptLoaded", &(script_name(),))
+        .await?;
+    Ok(reply.body().deserialize()?)
+}
+
+async fn unload(connection: &zbus::Connection) {
+    connection
+        .call_method(Some("org.kde.KWin"), "/Scripting", Some("org.kde.kwin.Scripting"), "unloadScript", &(script_name(),))
+        .await
+        .ok();
+}
+
+/// Unloads this process's script on exit; KWin would otherwise keep it until the next login
+pub async fn unload_script() {
+    let script = LOADED_SCRIPT.lock().unwrap().take();
+    if let Some(script) = script {
+        unload(&script.connection).await;
+    }
+}
+
+/// Loaded on the first poll; later polls only read the latest report
+static LOADED_SCRIPT: Mutex<Option<LoadedScript>> = Mutex::new(None);
+
+// Reports the active window once, then whenever another window is activated or the active one changes
+// its title or goes fullscreen. Plasma 6 renamed activeClient/clientActivated to activeWindow/windowActivated
+fn script_source(service: &str) -> String {
+    format!(
+        r#"let current = null;
+function report() {{
+    const w = current;
+    callDBus("{service}", "{REPORT_PATH}", "{REPORT_INTERFACE}", "report", w ? String(w.resourceClass) : "", w ? String(w.caption) : "", w ? Boolean(w.fullScreen) : false);
+}}
+function activated(w) {{
+    // The previous window may already be closed
+    try {{
+        current.captionChanged.disconnect(report);
+        current.fullScreenChanged.disconnect(report);
+    }} catch (e) {{}}
+    current = w;
+    if (current) {{
+        current.captionChanged.connect(report);
+        current.fullScreenChanged.connect(report);
+    }}
+    report();
+}}
+(workspace.windowActivated || workspace.clientActivated).connect(activated);
+activated(workspace.activeWindow || workspace.activeClient);
+"#
+    )
+}
+
+async fn get_active_window_scripting() -> Result<(String, String, bool)> {
+    let loaded = LOADED_SCRIPT
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|script| (script.connection.clone(), script.latest.clone()));
+    // Reports stop when KWin restarts or the script gets unloaded, so check it is still there before trusting them
+    let latest = match loaded {
+        Some((connection, latest)) if is_script_loaded(&connection).await.unwrap_or(false) => latest,
+        loaded => {
+            if loaded.is_some() {
+                log::info!("KWin script is no longer loaded, loading it again");
+            }
+            let script = load_script().await?;
+            let latest = script.latest.clone();
+            *LOADED_SCRIPT.lock().unwrap() = Some(script);
+            latest
+        }
+    };
+
+    let (wm_class, title, fullscreen) = latest
+        .borrow()
+        .clone()
+        .ok_or_else(|| anyhow::anyhow!("KWin script has not reported the active window"))?;
+    if wm_class.is_empty() {
+        return Err(anyhow::anyhow!("No active window"));
+    }
+    Ok((wm_class, title, fullscreen))
+}
+
+/// Loads and starts the reporting script, waiting for its first report
+async fn load_script() -> Result<LoadedScript> {
+    let connection = zbus::Connection::session().await?;
+    let service = connection
+        .unique_name()
+        .ok_or_else(|| anyhow::anyhow!("D-Bus connection has no unique name"))?
+        .to_string();
+
+    let (sender, mut latest) = watch::channel(None);
+    connection.object_server().at(REPORT_PATH, Reporter { sender }).await?;
+
+    let name = script_name();
+    let script_path = env::temp_dir().join(format!("{}.js", name));
+    std::fs::write(&script_path, script_source(&service))?;
+
+    // Our own script may still be loaded, reporting to a connection that was replaced
+    unload(&connection).await;
+
+    let result = async {
+        let reply = connection
+            .call_method(
+                Some("org.kde.KWin"),
+                "/Scripting",
+                Some("org.kde.kwin.Scripting"),
+                "loadScript",
+                &(script_path.to_string_lossy().as_ref(), name.as_str()),
+            )
+            .await?;
+        let script_id: i32 = reply.body().deserialize()?;
+
+        // Plasma 6 exposes loaded scripts under /Scripting/Script<id>, Plasma 5 under /<id>
+        let plasma6_path = format!("/Scripting/Script{}", script_id);
+        if connection
+            .call_method(Some("org.kde.KWin"), plasma6_path.as_str(), Some("org.kde.kwin.Script"), "run", &())
+            .await
+            .is_err()
+        {
+            let plasma5_path = format!("/{}", script_id);
+            connection
+                .call_method(Some("org.kde.KWin"), plasma5_path.as_str(), Some("org.kde.kwin.Script"), "run", &())
+                .await?;
+        }
+
+        tokio::time::timeout(Duration::from_millis(500), latest.changed())
+            .await
+            .map_err(|_| anyhow::anyhow!("KWin script did not report the active window"))?
+            .map_err(|_| anyhow::anyhow!("KWin reporter closed"))
+    }
+    .await;
+    std::fs::remove_file(&script_path).ok();
+
+    if let Err(e) = result {
+        unload(&connection).await;
+        return Err(e);
+    }
+    Ok(LoadedScript { connection, latest })
+}
+
+fn get_active_window_kdotool() -> Result<(String, String)> {
+    let query = |command: &str| -> Result<String> {
+        let output = std::process::Command::new("kdotool").arg("getactivewindow").arg(command).output()?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("kdotool {} failed: {}", command, String::from_utf8_lossy(&output.stderr).trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    Ok((query("getwindowclassname")?, query("getwindowname")?))
+}
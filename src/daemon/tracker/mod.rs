@@ -1,3 +1,4 @@
+pub mod kwin;
 pub mod monitor;
 pub mod parser;
 pub mod windows_inspection;
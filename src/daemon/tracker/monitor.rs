@@ -1,11 +1,16 @@
 use active_win_pos_rs::get_active_window;
 use anyhow::Result;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(target_os = "windows")]
 use super::windows_inspection;
 #[cfg(target_os = "macos")]
 use super::macos_inspection;
 
+/// Trailing ID segments that don't name the app ("com.spotify.Client", "org.telegram.desktop");
+/// shared by every app name normalizer so they agree
+pub const GENERIC_ID_SEGMENTS: &[&str] = &["client", "desktop", "app"];
+
 #[derive(serde::Deserialize, Debug)]
 struct WindowInfo {
     #[serde(default)]
@@ -14,6 +19,8 @@ struct WindowInfo {
     title: String,
     #[serde(default)]
     focus: bool,
+    #[serde(default)]
+    fullscreen: bool,
 }
 
 #[cfg(target_os = "linux")]
@@ -34,6 +41,8 @@ struct EditorInfo {
 
 pub struct AppMonitor {
     use_wayland: bool,
+    /// Whether the window from the last lookup was fullscreen
+    fullscreen: AtomicBool,
 }
 
 impl Default for AppMonitor {
@@ -72,7 +81,7 @@ impl AppMonitor {
         #[cfg(target_os = "windows")]
         log::info!("Using Win32 APIs for window tracking");
 
-        Self { use_wayland }
+        Self { use_wayland, fullscreen: AtomicBool::new(false) }
     }
 
 
@@ -110,12 +119,8 @@ impl AppMonitor {
                 if cmd.contains("tmux") {
                     info.has_tmux = true;
                     // Try to get session name from cmdline
-                    for arg in cmdline.split('\0').skip(1) {
-                        if arg.starts_with("-t") || arg.starts_with("-s") {
-                            if let Some(session) = arg.split('=').nth(1).or_else(|| arg.split(' ').nth(1)) {
-                                info.tmux_session = Some(session.to_string());
-                            }
-                        }
+                    if let Some(session) = Self::tmux_session_from_cmdline(&cmdline) {
+                        info.tmux_session = Some(session);
                     }
                 }
 
@@ -144,14 +149,15 @@ impl AppMonitor {
         }
 
         // If tmux detected, try to get the current window name
-        if info.has_tmux {
-            let session_arg = if let Some(ref session) = info.tmux_session {
-                format!("-t {}", session)
-            } else {
-                "".to_string()
-            };
-            let cmd = format!("tmux list-windows {} -F \"#{{window_name}}:#{{window_active}}\"", session_arg);
-            if let Ok(output) = std::process::Command::new("sh").arg("-c").arg(&cmd).output() {
+        if info.has_tmux && Self::tmux_installed() {
+            // Pass args directly so the session name is never interpreted by a shell
+            let mut command = std::process::Command::new("tmux");
+            command.arg("list-windows");
+            if let Some(ref session) = info.tmux_session {
+                command.arg("-t").arg(session);
+            }
+            command.arg("-F").arg("#{window_name}:#{window_active}");
+            if let Ok(output) = command.output() {
                 if output.status.success() {
                     let stdout = String::from_utf8_lossy(&output.stdout);
                     for line in stdout.lines() {
@@ -171,6 +177,31 @@ impl AppMonitor {
         Some(info)
     }
 
+    /// Whether a tmux binary is on PATH, looked up once so terminals without tmux never spawn it
+    #[cfg(target_os = "linux")]
+    fn tmux_installed() -> bool {
+        static TMUX_INSTALLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *TMUX_INSTALLED.get_or_init(|| {
+            env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join("tmux").is_file()))
+        })
+    }
+
+    /// Extract the tmux session name from a NUL-separated /proc cmdline.
+    /// tmux receives `-t`/`-s` and the name as separate argv entries, but `-tname` is accepted too.
+    #[cfg(target_os = "linux")]
+    fn tmux_session_from_cmdline(cmdline: &str) -> Option<String> {
+        let mut args = cmdline.split('\0').skip(1).filter(|arg| !arg.is_empty());
+        while let Some(arg) = args.next() {
+            if arg == "-t" || arg == "-s" {
+                return args.next().map(|session| session.to_string());
+            }
+            if let Some(attached) = arg.strip_prefix("-t").or_else(|| arg.strip_prefix("-s")) {
+                return Some(attached.trim_start_matches('=').to_string());
+            }
+        }
+        None
+    }
+
     #[cfg(target_os = "linux")]
     fn get_child_processes(pid: u64) -> Vec<u64> {
         let mut children = Vec::new();
@@ -244,9 +275,34 @@ impl AppMonitor {
         Ok((class, title))
     }
 
-    async fn get_active_window_wayland() -> Result<(String, String)> {
+    /// Whether the last detected window was fullscreen. Only the GNOME 'Window Calls' extension and
+    /// KWin report it, so it is always false on X11, macOS and Windows
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen.load(Ordering::Relaxed)
+    }
+
+    async fn get_active_window_wayland() -> Result<(String, String, bool)> {
+        // KDE Plasma has no GNOME Shell; ask KWin instead
+        if super::kwin::is_kde_plasma() {
+            return super::kwin::get_active_window().await;
+        }
+
         let connection = zbus::Connection::session().await?;
 
+        // 'Window Calls Extended' is a separate extension with its own interface; either one works
+        match Self::get_active_window_window_calls(&connection).await {
+            Ok(window) => Ok(window),
+            Err(window_calls_err) => {
+                log::debug!("Window Calls failed ({}), trying Window Calls Extended", window_calls_err);
+                Self::get_active_window_window_calls_extended(&connection).await.map_err(|extended_err| {
+                    anyhow::anyhow!("Window Calls: {}; Window Calls Extended: {}", window_calls_err, extended_err)
+                })
+            }
+        }
+    }
+
+    /// The focused window from the 'Window Calls' extension's JSON window list
+    async fn get_active_window_window_calls(connection: &zbus::Connection) -> Result<(String, String, bool)> {
         let response = connection.call_method(
             Some("org.gnome.Shell"),
             "/org/gnome/Shell/Extensions/Windows",
@@ -264,11 +320,34 @@ impl AppMonitor {
             .find(|w| w.focus)
             .ok_or(anyhow::anyhow!("No focused window found"))?;
 
-        Ok((focused_window.wm_class.clone(), focused_window.title.clone()))
+        Ok((focused_window.wm_class.clone(), focused_window.title.clone(), focused_window.fullscreen))
+    }
+
+    /// The focused window from 'Window Calls Extended', which answers one call per property.
+    /// It doesn't report fullscreen state, so that is always false
+    async fn get_active_window_window_calls_extended(connection: &zbus::Connection) -> Result<(String, String, bool)> {
+        let mut focused = Vec::new();
+        for method in ["FocusClass", "FocusTitle"] {
+            let response = connection.call_method(
+                Some("org.gnome.Shell"),
+                "/org/gnome/Shell/Extensions/WindowsExt",
+                Some("org.gnome.Shell.Extensions.WindowsExt"),
+                method,
+                &(),
+            ).await?;
+            focused.push(response.body().deserialize::<String>()?);
+        }
+        let title = focused.pop().unwrap_or_default();
+        let wm_class = focused.pop().unwrap_or_default();
+        if wm_class.is_empty() {
+            return Err(anyhow::anyhow!("No focused window found"));
+        }
+        Ok((wm_class, title, false))
     }
 
     // Get both app and window info in a single call
     pub async fn get_active_window_info_async(&self) -> Result<(String, Option<String>)> {
+        self.fullscreen.store(false, Ordering::Relaxed);
         // Try active-win-pos-rs first (works for X11 and some Wayland compositors)
         match get_active_window() {
             Ok(active_window) => {
@@ -328,7 +407,8 @@ impl AppMonitor {
                 if self.use_wayland {
                     // Try GNOME extension for Wayland
                     match Self::get_active_window_wayland().await {
-                        Ok((wm_class, title)) => {
+                        Ok((wm_class, title, fullscreen)) => {
+                            self.fullscreen.store(fullscreen, Ordering::Relaxed);
                             let app_name = self.fix_app_name(wm_class);
                             return Ok((app_name, Some(title)));
                         }
@@ -337,8 +417,9 @@ impl AppMonitor {
                 } else {
                     // Try GNOME extension for Wayland
                     match Self::get_active_window_wayland().await {
-                        Ok((wm_class, mut title)) => {
+                        Ok((wm_class, mut title, fullscreen)) => {
                             log::info!("Wayland fallback title: '{}'", title);
+                            self.fullscreen.store(fullscreen, Ordering::Relaxed);
                             let app_name = self.fix_app_name(wm_class);
                             // Extract directory from prompt if it looks like a shell prompt
                             if Self::is_terminal_app(&app_name) && title.contains("@") && title.contains(": ") {
@@ -443,6 +524,20 @@ impl AppMonitor {
                         return appName & "|" & windowTitle & "|" & pid
                     end try
                 end tell
+            else if appName is "Arc" then
+                tell application "Arc"
+                    try
+                        set windowTitle to title of active tab of front window
+                        return appName & "|" & windowTitle & "|" & pid
+                    end try
+                end tell
+            else if appName contains "Safari Technology Preview" then
+                tell application "Safari Technology Preview"
+                    try
+                        set windowTitle to name of front document
+                        return appName & "|" & windowTitle & "|" & pid
+                    end try
+                end tell
             else if appName contains "Safari" then
                 tell application "Safari"
                     try
@@ -631,7 +726,13 @@ impl AppMonitor {
         #[cfg(target_os = "linux")]
         let normalized = {
             if app_lower.contains('.') {
-                app_lower.split('.').last().unwrap_or(&app_lower).to_string()
+                // Flatpak IDs often end in a generic word ("com.spotify.Client", "org.telegram.desktop")
+                app_lower
+                    .split('.')
+                    .rev()
+                    .find(|part| !GENERIC_ID_SEGMENTS.contains(part))
+                    .unwrap_or(&app_lower)
+                    .to_string()
             } else if app_lower.contains('_') {
                 app_lower.split('_').next().unwrap_or(&app_lower).to_string()
             } else {
@@ -648,6 +749,11 @@ impl AppMonitor {
             return "chrome".to_string();
         } else if normalized.contains("firefox") {
             return "firefox".to_string();
+        } else if normalized.contains("safari") {
+            // Covers Safari Technology Preview so both builds aggregate together
+            return "safari".to_string();
+        } else if normalized == "arc" {
+            return "arc".to_string();
         } else if normalized.contains("code") || normalized.contains("vscode") || normalized.contains("vscodium") {
             return "vscode".to_string();
         } else if normalized.contains("slack") {
@@ -718,4 +824,20 @@ mod tests {
             }
         }
     }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_tmux_session_from_cmdline() {
+        // /proc/<pid>/cmdline is NUL-separated with a trailing NUL
+        let cmdline = "tmux\0attach-session\0-t\0work\0";
+        assert_eq!(AppMonitor::tmux_session_from_cmdline(cmdline), Some("work".to_string()));
+
+        let cmdline = "tmux\0new-session\0-d\0-s\0dev-server\0";
+        assert_eq!(AppMonitor::tmux_session_from_cmdline(cmdline), Some("dev-server".to_string()));
+
+        let cmdline = "tmux\0attach\0-tnotes\0";
+        assert_eq!(AppMonitor::tmux_session_from_cmdline(cmdline), Some("notes".to_string()));
+
+        assert_eq!(AppMonitor::tmux_session_from_cmdline("tmux\0"), None);
+    }
 }
@@ -1,11 +1,18 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// Layout version of the `parsed_data` JSON; keep in sync with the TUI parser, which upgrades older blobs
+pub const PARSED_DATA_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParsedSessionData {
+    pub schema_version: u32,
+
     // Browser tracking
     pub browser_url: Option<String>,
     pub browser_page_title: Option<String>,
     pub browser_notification_count: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser_raw_title: Option<String>, // Page title before trimming, when trimming changed it
 
     // Terminal tracking
     pub terminal_username: Option<String>,
@@ -27,12 +34,39 @@ pub struct ParsedSessionData {
     // IDE tracking
     pub ide_project_name: Option<String>,
     pub ide_file_open: Option<String>,
-    pub ide_workspace: Option<String>,
+    pub ide_workspace: Option<String>, // Also holds the browser profile for Chromium-based browsers
 
     // Metadata
     pub parsing_success: bool,
 }
 
+impl Default for ParsedSessionData {
+    fn default() -> Self {
+        Self {
+            schema_version: PARSED_DATA_SCHEMA_VERSION,
+            browser_url: None,
+            browser_page_title: None,
+            browser_notification_count: None,
+            browser_raw_title: None,
+            terminal_username: None,
+            terminal_hostname: None,
+            terminal_directory: None,
+            terminal_project_name: None,
+            editor_filename: None,
+            editor_filepath: None,
+            editor_project_path: None,
+            editor_language: None,
+            tmux_window_name: None,
+            tmux_pane_count: None,
+            terminal_multiplexer: None,
+            ide_project_name: None,
+            ide_file_open: None,
+            ide_workspace: None,
+            parsing_success: false,
+        }
+    }
+}
+
 impl ParsedSessionData {
     pub fn new() -> Self {
         Self {
@@ -52,7 +86,9 @@ pub fn parse_window_name(app_name: &str, window_name: &str) -> ParsedSessionData
     if is_browser(&app_lower) {
         parse_browser(window_name, &mut parsed);
     } else if is_terminal(&app_lower) {
-        parse_terminal(window_name, &mut parsed);
+        parse_terminal(&app_lower, window_name, &mut parsed);
+    } else if is_notes(&app_lower) {
+        parse_notes(&app_lower, window_name, &mut parsed);
     } else if is_editor(&app_lower) {
         parse_editor(window_name, &mut parsed);
     } else if is_file_manager(&app_lower) {
@@ -69,6 +105,7 @@ fn is_browser(app_name: &str) -> bool {
     || app_name.contains("chromium")
     || app_name.contains("brave")
     || app_name.contains("safari")
+    || app_name == "arc"
     || app_name.contains("edge")
 }
 
@@ -80,6 +117,19 @@ fn is_terminal(app_name: &str) -> bool {
     || app_name.contains("kitty")
     || app_name.contains("wezterm")
     || app_name.contains("konsole")
+    || is_warp(app_name)
+}
+
+/// "warp" on macOS; Linux's "dev.warp.Warp" window class normalizes to the same name
+fn is_warp(app_name: &str) -> bool {
+    app_name == "warp" || app_name == "dev.warp.warp"
+}
+
+/// Check if app is a note-taking app
+fn is_notes(app_name: &str) -> bool {
+    app_name.contains("obsidian")
+    || app_name.contains("logseq")
+    || app_name.contains("notion")
 }
 
 /// Check if app is an editor
@@ -108,6 +158,10 @@ fn is_file_manager(app_name: &str) -> bool {
 /// Parse browser window title
 /// Pattern: "(notification_count) Page Title — Browser Name" or "Page Title — Browser Name"
 fn parse_browser(window_name: &str, parsed: &mut ParsedSessionData) {
+    // Chromium-based browsers append the profile after their name: "Inbox - Google Chrome - Work"
+    let (window_name, profile) = split_browser_profile(window_name);
+    parsed.ide_workspace = profile;
+
     // Extract notification count if present
     if let Some(start) = window_name.find('(') {
         if let Some(end) = window_name.find(')') {
@@ -138,10 +192,29 @@ fn parse_browser(window_name: &str, parsed: &mut ParsedSessionData) {
         title
     };
 
-    parsed.browser_page_title = Some(clean_title.to_string());
-
     // Detect service from page title
     parsed.browser_url = detect_service(clean_title);
+
+    // Volatile parts (unread counts, site suffixes) would give the same page many distinct titles
+    let trimmed = crate::daemon::title_trim::trim(clean_title, parsed.browser_url.as_deref());
+    if trimmed != clean_title {
+        parsed.browser_raw_title = Some(clean_title.to_string());
+    }
+    parsed.browser_page_title = Some(trimmed);
+}
+
+/// Split a trailing profile segment off a browser title, returning the title without it
+fn split_browser_profile(window_name: &str) -> (&str, Option<String>) {
+    const BROWSER_NAMES: [&str; 3] = ["Google Chrome", "Chromium", "Brave"];
+
+    if let Some((rest, profile)) = window_name.rsplit_once(" - ") {
+        let profile = profile.trim();
+        let after_browser_name = BROWSER_NAMES.iter().any(|name| rest.ends_with(&format!(" - {}", name)) || rest == *name);
+        if after_browser_name && !profile.is_empty() && !BROWSER_NAMES.contains(&profile) {
+            return (rest, Some(profile.to_string()));
+        }
+    }
+    (window_name, None)
 }
 
 /// Detect web service from page title
@@ -223,7 +296,15 @@ fn detect_service(title: &str) -> Option<String> {
 
 /// Parse terminal window title
 /// Handles multiple patterns: "username@hostname: /directory/path", tmux variants, and platform differences
-fn parse_terminal(window_name: &str, parsed: &mut ParsedSessionData) {
+fn parse_terminal(app_name: &str, window_name: &str, parsed: &mut ParsedSessionData) {
+    if is_warp(app_name) && parse_warp(window_name, parsed) {
+        return;
+    }
+
+    // WezTerm prefixes the tab position when several tabs are open: "[2/3] ~/code/app".
+    // On Linux WezTerm is normalized to "terminal", so strip it for every terminal.
+    let window_name = strip_tab_position(window_name);
+
     // First, check for tmux patterns and extract tmux information
     let (cleaned_title, tmux_info) = extract_tmux_info(window_name);
 
@@ -261,6 +342,55 @@ fn parse_terminal(window_name: &str, parsed: &mut ParsedSessionData) {
             parsed.terminal_project_name = extract_project_name(&directory);
         }
     }
+
+    // WezTerm pane titles are often just the running program ("nvim", "cargo test")
+    if app_name.contains("wezterm") && parsed.tmux_window_name.is_none() {
+        parsed.terminal_multiplexer = Some("wezterm".to_string());
+        if parsed.terminal_directory.is_none() && parsed.terminal_username.is_none() && !cleaned_title.trim().is_empty() {
+            parsed.tmux_window_name = Some(cleaned_title.trim().to_string());
+        }
+    }
+}
+
+/// WezTerm's "[2/3] title" tab position prefix, removed
+fn strip_tab_position(title: &str) -> &str {
+    title
+        .strip_prefix('[')
+        .and_then(|rest| rest.split_once("] "))
+        .filter(|(position, _)| {
+            position
+                .split_once('/')
+                .is_some_and(|(index, count)| index.parse::<u32>().is_ok() && count.parse::<u32>().is_ok())
+        })
+        .map_or(title, |(_, after)| after)
+}
+
+/// Warp titles are "folder — command" while something runs, or the working directory at the prompt.
+/// Returns false for other formats (e.g. "user@host: ~/dir" over SSH) so the generic parsing handles them.
+fn parse_warp(title: &str, parsed: &mut ParsedSessionData) -> bool {
+    let (folder, command) = match title.split_once(" — ") {
+        Some((folder, command)) => (folder.trim(), Some(command.trim())),
+        None => (title.trim(), None),
+    };
+    let is_path = folder.starts_with('/') || folder.starts_with('~');
+    if folder.is_empty() || folder.contains('@') || (command.is_none() && !is_path) {
+        return false;
+    }
+
+    if is_path {
+        let directory = expand_tilde(folder);
+        parsed.terminal_project_name = extract_project_name(&directory);
+        parsed.terminal_directory = Some(directory);
+    } else {
+        // Warp shows only the last path component while a command runs
+        parsed.terminal_directory = Some(folder.to_string());
+        parsed.terminal_project_name = Some(folder.to_string());
+    }
+    if let Some(command) = command.filter(|command| !command.is_empty()) {
+        parsed.tmux_window_name = Some(command.to_string());
+    }
+    parsed.terminal_multiplexer = Some("warp".to_string());
+    true
 }
 
 /// Extract tmux information from terminal title
@@ -445,6 +575,10 @@ fn extract_project_name(path: &str) -> Option<String> {
 /// Parse editor window title
 /// Pattern: "filename (path) - Editor Name" or "path/filename - Editor Name"
 fn parse_editor(window_name: &str, parsed: &mut ParsedSessionData) {
+    if parse_remote_editor(window_name, parsed) {
+        return;
+    }
+
     // Try pattern: "filename (path) - Editor"
     if let Some(paren_start) = window_name.find('(') {
         let filename = window_name[..paren_start].trim();
@@ -482,6 +616,67 @@ fn parse_editor(window_name: &str, parsed: &mut ParsedSessionData) {
     }
 }
 
+/// Host named by a remote editing marker at the end of a title segment: "project [SSH: devbox]"
+/// (also "[WSL: Ubuntu]", "[Dev Container: api]") gives "devbox"; code-server's "project (Remote)"
+/// gives "remote". Returns the segment without the marker
+fn split_remote_marker(segment: &str) -> Option<(&str, String)> {
+    if let Some(rest) = segment.strip_suffix("(Remote)") {
+        return Some((rest.trim_end(), "remote".to_string()));
+    }
+    let open = segment.strip_suffix(']')?.rfind(" [")?;
+    let (_, host) = segment[open + 2..segment.len() - 1].split_once(": ")?;
+    Some((segment[..open].trim_end(), host.trim().to_string()))
+}
+
+/// VS Code Remote and code-server titles: "file.rs — project [SSH: devbox] — Visual Studio Code".
+/// The files live on the remote machine, so the project comes from the marked segment and no
+/// path is resolved against the local HOME. Returns false when the title has no remote marker
+fn parse_remote_editor(window_name: &str, parsed: &mut ParsedSessionData) -> bool {
+    let separator = if window_name.contains(" — ") { " — " } else { " - " };
+    let segments: Vec<&str> = window_name.split(separator).map(|segment| segment.trim()).collect();
+    let Some((index, (project, host))) = segments.iter().enumerate().find_map(|(i, segment)| split_remote_marker(segment).map(|marked| (i, marked))) else {
+        return false;
+    };
+
+    // Only a file name comes before the workspace; nothing does when no file is open
+    if index > 0 {
+        let filename = segments[0];
+        parsed.editor_filename = Some(filename.to_string());
+        parsed.editor_language = detect_language(filename);
+    }
+    parsed.editor_project_path = Some(project.to_string());
+    parsed.ide_workspace = Some(format!("{}:{}", host, project));
+    true
+}
+
+/// Parse note app window title
+/// Pattern: "note — vault — Obsidian v1.5.3", "page - graph - Logseq" or "page - Notion"
+fn parse_notes(app_name: &str, window_name: &str, parsed: &mut ParsedSessionData) {
+    let separator = if window_name.contains(" — ") { " — " } else { " - " };
+    let mut parts: Vec<&str> = window_name.split(separator).map(|part| part.trim()).filter(|part| !part.is_empty()).collect();
+
+    // Drop the trailing app name segment
+    if parts.last().is_some_and(|last| is_notes(&last.to_lowercase())) {
+        parts.pop();
+    }
+
+    let (note, vault) = match parts.len() {
+        0 => return,
+        1 => (parts[0].to_string(), None),
+        // Note names may themselves contain the separator, so the vault is always the last segment
+        n => (parts[..n - 1].join(separator), Some(parts[n - 1].to_string())),
+    };
+
+    parsed.editor_filename = Some(note);
+    parsed.editor_project_path = vault.clone();
+    parsed.ide_workspace = vault;
+
+    // Obsidian and Logseq store pages as Markdown files
+    if !app_name.contains("notion") {
+        parsed.editor_language = Some("Markdown".to_string());
+    }
+}
+
 /// Detect programming language from file extension
 fn detect_language(filename: &str) -> Option<String> {
     if let Some(ext_pos) = filename.rfind('.') {
@@ -538,6 +733,13 @@ fn parse_file_manager(window_name: &str, parsed: &mut ParsedSessionData) {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parsed_data_schema_version() {
+        let parsed = parse_window_name("firefox", "GitHub - Mozilla Firefox");
+        let json = serde_json::to_value(&parsed).unwrap();
+        assert_eq!(json["schema_version"], PARSED_DATA_SCHEMA_VERSION);
+    }
+
     #[test]
     fn test_parse_browser() {
         let parsed = parse_window_name(
@@ -550,6 +752,27 @@ mod tests {
         assert_eq!(parsed.browser_url, Some("WhatsApp".to_string()));
     }
 
+    #[test]
+    fn test_parse_browser_profile() {
+        let parsed = parse_window_name(
+            "google-chrome",
+            "Inbox (3) - Gmail - Google Chrome - Work"
+        );
+        assert_eq!(parsed.ide_workspace, Some("Work".to_string()));
+        // Unread count, browser name and the " - Gmail" suffix are trimmed off the stored title
+        assert_eq!(parsed.browser_page_title, Some("Inbox".to_string()));
+        assert_eq!(parsed.browser_raw_title, Some("Inbox (3) - Gmail - Google Chrome".to_string()));
+        assert_eq!(parsed.browser_url, Some("Gmail".to_string()));
+
+        // Without a profile suffix the title is parsed as before
+        let parsed = parse_window_name(
+            "google-chrome",
+            "Inbox (3) - Gmail - Google Chrome"
+        );
+        assert_eq!(parsed.ide_workspace, None);
+        assert_eq!(parsed.browser_page_title, Some("Inbox".to_string()));
+    }
+
     #[test]
     fn test_parse_terminal() {
         let parsed = parse_window_name(
@@ -563,6 +786,38 @@ mod tests {
         assert_eq!(parsed.terminal_project_name, Some("neura-hustle-tracker".to_string()));
     }
 
+    #[test]
+    fn test_parse_warp_title() {
+        let parsed = parse_window_name("warp", "neura-hustle-tracker — cargo test");
+        assert_eq!(parsed.terminal_directory, Some("neura-hustle-tracker".to_string()));
+        assert_eq!(parsed.terminal_project_name, Some("neura-hustle-tracker".to_string()));
+        assert_eq!(parsed.tmux_window_name, Some("cargo test".to_string()));
+        assert_eq!(parsed.terminal_multiplexer, Some("warp".to_string()));
+
+        // At the prompt Warp shows the working directory
+        let parsed = parse_window_name("warp", "/srv/apps/shop");
+        assert_eq!(parsed.terminal_project_name, Some("shop".to_string()));
+        assert_eq!(parsed.tmux_window_name, None);
+    }
+
+    #[test]
+    fn test_parse_wezterm_title() {
+        let parsed = parse_window_name("wezterm", "[2/3] /srv/apps/shop");
+        assert_eq!(parsed.terminal_directory, Some("/srv/apps/shop".to_string()));
+        assert_eq!(parsed.terminal_project_name, Some("shop".to_string()));
+        assert_eq!(parsed.terminal_multiplexer, Some("wezterm".to_string()));
+
+        // A pane running a program
+        let parsed = parse_window_name("wezterm", "[1/2] nvim");
+        assert_eq!(parsed.tmux_window_name, Some("nvim".to_string()));
+        assert_eq!(parsed.terminal_directory, None);
+
+        // Normalized to "terminal" on Linux: the tab position is still stripped
+        let parsed = parse_window_name("terminal", "[1/2] deploy@web-01: /srv/apps/shop");
+        assert_eq!(parsed.terminal_username, Some("deploy".to_string()));
+        assert_eq!(parsed.terminal_project_name, Some("shop".to_string()));
+    }
+
     #[test]
     fn test_parse_editor() {
         let parsed = parse_window_name(
@@ -575,6 +830,48 @@ mod tests {
         assert_eq!(parsed.editor_language, Some("Markdown".to_string()));
     }
 
+    #[test]
+    fn test_parse_remote_editor() {
+        let parsed = parse_window_name("code", "file.rs — project [SSH: devbox] — Visual Studio Code");
+        assert_eq!(parsed.editor_filename, Some("file.rs".to_string()));
+        assert_eq!(parsed.editor_language, Some("Rust".to_string()));
+        assert_eq!(parsed.editor_project_path, Some("project".to_string()));
+        assert_eq!(parsed.ide_workspace, Some("devbox:project".to_string()));
+        // The path is on the remote machine, so nothing is taken as a local path
+        assert_eq!(parsed.editor_filepath, None);
+
+        let parsed = parse_window_name("code", "project [WSL: Ubuntu] — Visual Studio Code");
+        assert_eq!(parsed.editor_filename, None);
+        assert_eq!(parsed.ide_workspace, Some("Ubuntu:project".to_string()));
+
+        let parsed = parse_window_name("code-server", "main.py - api (Remote) - code-server");
+        assert_eq!(parsed.editor_filename, Some("main.py".to_string()));
+        assert_eq!(parsed.ide_workspace, Some("remote:api".to_string()));
+    }
+
+    #[test]
+    fn test_parse_obsidian() {
+        let parsed = parse_window_name(
+            "obsidian",
+            "Weekly Review — Second Brain — Obsidian v1.5.3"
+        );
+
+        assert_eq!(parsed.editor_filename, Some("Weekly Review".to_string()));
+        assert_eq!(parsed.ide_workspace, Some("Second Brain".to_string()));
+        assert_eq!(parsed.editor_language, Some("Markdown".to_string()));
+    }
+
+    #[test]
+    fn test_parse_logseq() {
+        let parsed = parse_window_name(
+            "logseq",
+            "Oct 18th, 2026 - work-graph - Logseq"
+        );
+
+        assert_eq!(parsed.editor_filename, Some("Oct 18th, 2026".to_string()));
+        assert_eq!(parsed.ide_workspace, Some("work-graph".to_string()));
+    }
+
     #[test]
     fn test_detect_language() {
         assert_eq!(detect_language("main.rs"), Some("Rust".to_string()));
@@ -5,22 +5,63 @@ mod models;
 use anyhow::Result;
 use crate::daemon::active_window::daemon::Daemon;
 use crate::daemon::database::connection::Database;
-use crate::config::settings::Settings;
+use crate::config::settings::{AppConfig, Settings};
 use dotenvy::dotenv;
 use std::env;
 use std::fs::OpenOptions;
+use clap::{Arg, Command};
 
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let matches = Command::new("Neura Hustle Tracker Daemon")
+        .version("0.3.2")
+        .about("Background window tracking for Neura Hustle Tracker")
+        .arg(
+            Arg::new("query")
+                .long("query")
+                .help("Print the status of the running daemon from its status socket and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tag")
+                .long("tag")
+                .value_name("LABEL")
+                .help("Tag the running daemon's sessions with LABEL until cleared with an empty LABEL, then exit"),
+        )
+        .get_matches();
+
     // Load .env file
     dotenv().ok();
 
-    // Check if debug logging is enabled via .env
+    // Load runtime settings (config.toml is created with defaults on first run)
+    let app_config = AppConfig::load();
+
+    // Query mode: ask the running daemon over ipc_socket_path instead of starting a new one
+    let tag = matches.get_one::<String>("tag");
+    if matches.get_flag("query") || tag.is_some() {
+        let Some(path) = &app_config.ipc_socket_path else {
+            eprintln!("❌ Status socket is disabled. Set ipc_socket_path in config.toml and restart the daemon.");
+            std::process::exit(1);
+        };
+        let path = std::path::Path::new(path);
+        if let Some(tag) = tag {
+            let tag = tag.trim();
+            crate::daemon::ipc::send_tag(path, (!tag.is_empty()).then_some(tag)).await?;
+            println!("{}", if tag.is_empty() { "Tag cleared".to_string() } else { format!("Tagging sessions with {}", tag) });
+            return Ok(());
+        }
+        let status = crate::daemon::ipc::query_status(path).await?;
+        print!("{}", status);
+        return Ok(());
+    }
+
+    // Check if debug logging is enabled via .env or the settings screen
     let debug_enabled = env::var("DEBUG_LOGS_ENABLED")
         .ok()
         .and_then(|v| v.parse::<bool>().ok())
-        .unwrap_or(false);
+        .unwrap_or(false)
+        || app_config.debug_logs_enabled;
 
     if debug_enabled {
         // Enable debug logging to daemon.log file
@@ -39,7 +80,7 @@ async fn main() -> Result<()> {
 
         log::info!("=== DEBUG LOGGING ENABLED ===");
         log::info!("Writing logs to daemon.log");
-        log::info!("To disable: Remove DEBUG_LOGS_ENABLED from .env or set to false");
+        log::info!("To disable: Remove DEBUG_LOGS_ENABLED from .env or turn it off in settings [s]");
     } else {
         // No logging for regular users
         env_logger::Builder::from_env(
@@ -50,7 +91,7 @@ async fn main() -> Result<()> {
     }
 
     log::info!("Starting Neura Hustle Tracker Daemon");
-    let settings = Settings::new()?;
+    let settings = Settings::new(None)?;
     log::info!("Connecting to database...");
     log::info!("Database URL: {}", settings.database_url);
     log::info!("Environment variables loaded: POSTGRES_USERNAME={}, POSTGRES_PASSWORD=***", 
@@ -77,7 +118,7 @@ async fn main() -> Result<()> {
 
     log::info!("Tables created. Starting daemon...");
 
-    let mut daemon = Daemon::new(database);
+    let mut daemon = Daemon::new(database, app_config);
     daemon.run().await?;
 
     Ok(())
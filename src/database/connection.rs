@@ -1,10 +1,53 @@
 use anyhow::Result;
 use sqlx::postgres::PgPool;
 use sqlx::PgPool as Pool;
+use crate::config::settings::WeekStart;
 use crate::models::session::Session;
+use crate::ui::tracking::ViewMode;
+use std::collections::HashSet;
+
+/// Categories assigned by the built-in rules; anything else was created by the user
+const DEFAULT_CATEGORIES: [&str; 11] = [
+    "💻 Development",
+    "🌐 Browsing",
+    "💬 Communication",
+    "🎵 Media",
+    "📁 Files",
+    "📧 Email",
+    "📄 Office",
+    "📅 Meetings",
+    "🗒️ Notes",
+    "📦 Other",
+    "💤 Away",
+];
+
+/// Whether a category comes from the built-in rules rather than the user
+pub fn is_default_category(category: &str) -> bool {
+    DEFAULT_CATEGORIES.contains(&category)
+}
+
+/// Columns holding a category: the app's own plus one per sub-entry type
+const CATEGORY_COLUMNS: [&str; 5] = [
+    "category",
+    "browser_page_title_category",
+    "terminal_directory_category",
+    "editor_filename_category",
+    "tmux_window_name_category",
+];
+
+/// Rows per INSERT in `insert_sessions_batch`; 36 columns each stays under Postgres' 65535 parameters
+const INSERT_BATCH_ROWS: usize = 1000;
+
+/// Names `get_app_usage_with_afk` gives AFK and idle time
+pub const AFK_PSEUDO_APP: &str = "AFK";
+pub const IDLE_PSEUDO_APP: &str = "Idle";
+
+/// A session's project: its IDE project, else its terminal project (empty names count as none)
+const PROJECT_SQL: &str = "COALESCE(NULLIF(ide_project_name, ''), NULLIF(terminal_project_name, ''))";
 
 pub struct Database {
     pool: Pool,
+    week_start: WeekStart,
 }
 
 impl Database {
@@ -16,13 +59,102 @@ impl Database {
             .run(&pool)
             .await?;
 
-        Ok(Self { pool })
+        Ok(Self { pool, week_start: WeekStart::Rolling })
+    }
+
+    /// Connect without running migrations, for viewing a database (--db-url) without changing it
+    pub async fn new_read_only(database_url: &str) -> Result<Self> {
+        let pool = PgPool::connect(database_url).await?;
+        Ok(Self { pool, week_start: WeekStart::Rolling })
+    }
+
+    /// Create the database named in the URL if the server doesn't have it yet.
+    /// Returns true when it was created, false when it already existed.
+    pub async fn create_if_missing(database_url: &str) -> Result<bool> {
+        use sqlx::migrate::MigrateDatabase;
+
+        if sqlx::Postgres::database_exists(database_url).await? {
+            return Ok(false);
+        }
+        sqlx::Postgres::create_database(database_url).await?;
+        Ok(true)
     }
 
+    /// Refresh planner statistics so the start_time/app_name indexes are used on large tables
+    pub async fn analyze(&self) -> Result<()> {
+        sqlx::query("ANALYZE sessions").execute(&self.pool).await?;
+        Ok(())
+    }
 
 
+    /// Add a short session's time to this machine's last non-AFK session before it.
+    /// Returns false when there is no such session to absorb it.
+    pub async fn merge_into_previous_session(&self, session: &Session) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE sessions SET duration = duration + $1
+            WHERE id = (
+                SELECT id FROM sessions
+                WHERE start_time < $2 AND hostname IS NOT DISTINCT FROM $3
+                  AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+                ORDER BY start_time DESC LIMIT 1
+            )
+            "#,
+        )
+        .bind(session.duration)
+        .bind(session.start_time)
+        .bind(&session.hostname)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn insert_session(&self, session: &Session) -> Result<i32> {
-        let id: (i32,) = sqlx::query_as(
+        if let Some(id) = self.update_resumed_session(session).await? {
+            return Ok(id);
+        }
+        Self::insert_session_with(&self.pool, session).await
+    }
+
+    /// Save a resumed session over the row it carries on, returning that row's id; None when the
+    /// session isn't resumed or the row is gone
+    async fn update_resumed_session(&self, session: &Session) -> Result<Option<i32>> {
+        let Some(id) = session.resumed_id else {
+            return Ok(None);
+        };
+        let result = sqlx::query("UPDATE sessions SET duration = $1, tag = $2 WHERE id = $3")
+            .bind(session.duration)
+            .bind(&session.tag)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok((result.rows_affected() > 0).then_some(id))
+    }
+
+    /// Insert on any executor, so callers can include it in a transaction
+    async fn insert_session_with<'e>(executor: impl sqlx::PgExecutor<'e>, session: &Session) -> Result<i32> {
+        let mut query = Self::session_insert_query(std::slice::from_ref(session));
+        query.push(" RETURNING id");
+        let id: (i32,) = query.build_query_as().fetch_one(executor).await?;
+        Ok(id.0)
+    }
+
+    /// Insert many sessions in one transaction with multi-row INSERTs, for imports over a remote
+    /// connection where a round trip per row is slow. Returns the number of rows inserted
+    pub async fn insert_sessions_batch(&self, sessions: &[Session]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut inserted = 0;
+        for chunk in sessions.chunks(INSERT_BATCH_ROWS) {
+            inserted += Self::session_insert_query(chunk).build().execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+        Ok(inserted)
+    }
+
+    /// `INSERT INTO sessions (...) VALUES (...), ...` for `sessions`; shared by the single and batch
+    /// inserts so their columns can't drift apart
+    fn session_insert_query(sessions: &[Session]) -> sqlx::QueryBuilder<'_, sqlx::Postgres> {
+        let mut query = sqlx::QueryBuilder::new(
             r#"
             INSERT INTO sessions (
                 app_name, window_name, start_time, duration, category,
@@ -35,67 +167,198 @@ impl Database {
                 tmux_window_name, tmux_pane_count, terminal_multiplexer,
                 tmux_window_name_renamed, tmux_window_name_category,
                 ide_project_name, ide_file_open, ide_workspace,
-                parsed_data, parsing_success, is_afk, is_idle
-            ) VALUES (
-                $1, $2, $3, $4, $5,
-                $6, $7, $8,
-                $9, $10,
-                $11, $12, $13, $14,
-                $15, $16,
-                $17, $18, $19, $20,
-                $21, $22,
-                $23, $24, $25,
-                $26, $27,
-                $28, $29, $30,
-                $31, $32,
-                $33, $34
-            ) RETURNING id
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            ) "#,
+        );
+        query.push_values(sessions, |mut row, session| {
+            row.push_bind(&session.app_name)
+                .push_bind(&session.window_name)
+                .push_bind(session.start_time)
+                .push_bind(session.duration)
+                .push_bind(&session.category)
+                // Browser
+                .push_bind(&session.browser_url)
+                .push_bind(&session.browser_page_title)
+                .push_bind(session.browser_notification_count)
+                .push_bind(&session.browser_page_title_renamed)
+                .push_bind(&session.browser_page_title_category)
+                // Terminal
+                .push_bind(&session.terminal_username)
+                .push_bind(&session.terminal_hostname)
+                .push_bind(&session.terminal_directory)
+                .push_bind(&session.terminal_project_name)
+                .push_bind(&session.terminal_directory_renamed)
+                .push_bind(&session.terminal_directory_category)
+                // Editor
+                .push_bind(&session.editor_filename)
+                .push_bind(&session.editor_filepath)
+                .push_bind(&session.editor_project_path)
+                .push_bind(&session.editor_language)
+                .push_bind(&session.editor_filename_renamed)
+                .push_bind(&session.editor_filename_category)
+                // Multiplexer
+                .push_bind(&session.tmux_window_name)
+                .push_bind(session.tmux_pane_count)
+                .push_bind(&session.terminal_multiplexer)
+                .push_bind(&session.tmux_window_name_renamed)
+                .push_bind(&session.tmux_window_name_category)
+                // IDE
+                .push_bind(&session.ide_project_name)
+                .push_bind(&session.ide_file_open)
+                .push_bind(&session.ide_workspace)
+                // Metadata
+                .push_bind(&session.parsed_data)
+                .push_bind(session.parsing_success)
+                // AFK tracking
+                .push_bind(session.is_afk)
+                .push_bind(session.is_idle)
+                .push_bind(&session.tag)
+                .push_bind(&session.hostname)
+                .push_bind(&session.note);
+        });
+        query
+    }
+
+    pub async fn set_session_tag(&self, id: i32, tag: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE sessions SET tag = $1 WHERE id = $2")
+            .bind(tag)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Attach a note to a saved session; None removes it
+    pub async fn set_session_note(&self, id: i32, note: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE sessions SET note = $1 WHERE id = $2")
+            .bind(note)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Split a session at `at`: the original ends there and a copy (parsed fields included) covers the rest.
+    /// With `new_app`, the second part is renamed and takes that app's known category (the original's if unknown).
+    /// Returns the id of the new session.
+    pub async fn split_session(&self, id: i32, at: chrono::DateTime<chrono::Local>, new_app: Option<String>) -> Result<i32> {
+        let original = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE id = $1
             "#,
         )
-        .bind(&session.app_name)
-        .bind(&session.window_name)
-        .bind(session.start_time)
-        .bind(session.duration)
-        .bind(&session.category)
-        // Browser
-        .bind(&session.browser_url)
-        .bind(&session.browser_page_title)
-        .bind(session.browser_notification_count)
-        .bind(&session.browser_page_title_renamed)
-        .bind(&session.browser_page_title_category)
-        // Terminal
-        .bind(&session.terminal_username)
-        .bind(&session.terminal_hostname)
-        .bind(&session.terminal_directory)
-        .bind(&session.terminal_project_name)
-        .bind(&session.terminal_directory_renamed)
-        .bind(&session.terminal_directory_category)
-        // Editor
-        .bind(&session.editor_filename)
-        .bind(&session.editor_filepath)
-        .bind(&session.editor_project_path)
-        .bind(&session.editor_language)
-        .bind(&session.editor_filename_renamed)
-        .bind(&session.editor_filename_category)
-        // Multiplexer
-        .bind(&session.tmux_window_name)
-        .bind(session.tmux_pane_count)
-        .bind(&session.terminal_multiplexer)
-        .bind(&session.tmux_window_name_renamed)
-        .bind(&session.tmux_window_name_category)
-        // IDE
-        .bind(&session.ide_project_name)
-        .bind(&session.ide_file_open)
-        .bind(&session.ide_workspace)
-        // Metadata
-        .bind(&session.parsed_data)
-        .bind(session.parsing_success)
-        // AFK tracking
-        .bind(session.is_afk)
-        .bind(session.is_idle)
-        .fetch_one(&self.pool)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Session {} not found", id))?;
+
+        let end = original.start_time + chrono::Duration::seconds(original.duration);
+        if at <= original.start_time || at >= end {
+            return Err(anyhow::anyhow!(
+                "Split time {} is outside the session ({} - {})",
+                at.format("%H:%M:%S"),
+                original.start_time.format("%H:%M:%S"),
+                end.format("%H:%M:%S")
+            ));
+        }
+
+        let mut second = original.clone();
+        second.id = None;
+        second.start_time = at;
+        second.duration = (end - at).num_seconds();
+        if let Some(app_name) = new_app.filter(|name| !name.trim().is_empty()) {
+            second.category = self.get_app_category_by_name(&app_name).await?.or(second.category);
+            second.app_name = app_name;
+        }
+
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE sessions SET duration = $1 WHERE id = $2")
+            .bind((at - original.start_time).num_seconds())
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        let new_id = Self::insert_session_with(&mut *tx, &second).await?;
+        tx.commit().await?;
+        Ok(new_id)
+    }
+
+    /// The most recent session on this machine, when `session`, just started, is it carrying on after a
+    /// restart (see `Session::resumes`). Its row stays until the combined session is saved over it (via
+    /// `resumed_id`), so a second crash before then loses nothing
+    pub async fn reopen_last_session_if_recent(&self, session: &Session, max_gap_secs: u64) -> Result<Option<Session>> {
+        // The session that ended last, leaving out `session`'s own row (saved when it started)
+        let previous = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE id IS DISTINCT FROM $1 AND hostname = $2
+            ORDER BY start_time + duration * INTERVAL '1 second' DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(session.id)
+        .bind(&session.hostname)
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(id.0)
+        Ok(previous.filter(|previous| previous.id.is_some() && session.resumes(previous, max_gap_secs)))
+    }
+
+    /// When this machine's daemon last checked in, or None if it never has
+    pub async fn get_daemon_heartbeat(&self) -> Result<Option<chrono::DateTime<chrono::Local>>> {
+        let row: Option<(chrono::DateTime<chrono::Local>,)> = sqlx::query_as("SELECT last_seen FROM daemon_heartbeat WHERE hostname = $1")
+            .bind(crate::models::session::current_hostname().unwrap_or_default())
+            .fetch_optional(&self.pool)
+            .await?;
+        Ok(row.map(|(last_seen,)| last_seen))
+    }
+
+    /// Apps the daemon found past their daily limit today, as (app, seconds used, limit in minutes)
+    pub async fn get_app_limit_alerts(&self) -> Result<Vec<(String, i64, i64)>> {
+        let rows: Vec<(String, i64, i64)> = sqlx::query_as(
+            "SELECT app_name, spent_secs, limit_minutes FROM app_limit_alerts WHERE day = $1 ORDER BY app_name",
+        )
+        .bind(chrono::Local::now().date_naive())
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// `(start_time, app_name, hostname)` of the stored sessions starting at one of `start_times`.
+    /// Ids differ between databases, so imports recognize sessions they already have by these
+    pub async fn existing_session_keys(
+        &self,
+        start_times: &[chrono::DateTime<chrono::Local>],
+    ) -> Result<HashSet<(chrono::DateTime<chrono::Local>, String, Option<String>)>> {
+        let rows: Vec<(chrono::DateTime<chrono::Local>, String, Option<String>)> =
+            sqlx::query_as("SELECT start_time, app_name, hostname FROM sessions WHERE start_time = ANY($1)")
+                .bind(start_times)
+                .fetch_all(&self.pool)
+                .await?;
+        Ok(rows.into_iter().collect())
     }
 
     pub async fn get_recent_sessions(&self, limit: i64) -> Result<Vec<Session>> {
@@ -112,7 +375,7 @@ impl Database {
                 tmux_window_name, tmux_pane_count, terminal_multiplexer,
                 tmux_window_name_renamed, tmux_window_name_category,
                 ide_project_name, ide_file_open, ide_workspace,
-                parsed_data, parsing_success, is_afk, is_idle
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
             FROM sessions
             ORDER BY start_time DESC
             LIMIT $1
@@ -124,15 +387,183 @@ impl Database {
         Ok(sessions)
     }
 
-    pub async fn get_app_usage(&self) -> Result<Vec<(String, i64)>> {
+    /// Per-app totals over the same window as the dashboard view (today, last 7 days or last 30 days)
+    pub async fn get_app_usage_for_range(&self, range: ViewMode) -> Result<Vec<(String, i64)>> {
         let rows: Vec<(String, i64)> = sqlx::query_as(
-            "SELECT app_name, SUM(duration)::BIGINT as total_duration FROM sessions WHERE is_afk IS NOT TRUE AND is_idle IS NOT TRUE GROUP BY app_name ORDER BY total_duration DESC",
+            "SELECT app_name, SUM(duration)::BIGINT as total_duration FROM sessions WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE GROUP BY app_name ORDER BY total_duration DESC",
+        )
+        .bind(self.range_start(&range))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Like `get_app_usage_for_range`, with AFK and idle time included as the `AFK_PSEUDO_APP` and
+    /// `IDLE_PSEUDO_APP` rows instead of being left out
+    pub async fn get_app_usage_with_afk(&self, range: ViewMode) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            r#"
+            SELECT CASE WHEN is_idle THEN $3 WHEN is_afk THEN $2 ELSE app_name END AS name, SUM(duration)::BIGINT as total_duration
+            FROM sessions WHERE start_time >= $1
+            GROUP BY name ORDER BY total_duration DESC
+            "#,
+        )
+        .bind(self.range_start(&range))
+        .bind(AFK_PSEUDO_APP)
+        .bind(IDLE_PSEUDO_APP)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Non-AFK time per app within one category for the range, largest first. Sessions without a
+    /// category are counted as "📦 Other"
+    pub async fn get_app_usage_for_category(&self, range: ViewMode, category: &str) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT app_name, SUM(duration)::BIGINT as total_duration FROM sessions WHERE start_time >= $1 AND COALESCE(NULLIF(category, ''), '📦 Other') = $2 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE GROUP BY app_name ORDER BY total_duration DESC",
+        )
+        .bind(self.range_start(&range))
+        .bind(category)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// One page of a view mode's sessions, newest first, for scrolling long histories without loading the whole range.
+    /// Pages continue after the `(start_time, id)` of the last loaded session, so rows saved meanwhile don't shift them
+    pub async fn get_sessions_paged(&self, range: ViewMode, after: Option<(chrono::DateTime<chrono::Local>, i32)>, limit: i64) -> Result<Vec<Session>> {
+        let rows = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE start_time >= $1
+              AND ($2::timestamptz IS NULL OR (start_time, id) < ($2, $3))
+            ORDER BY start_time DESC, id DESC
+            LIMIT $4
+            "#,
+        )
+        .bind(self.range_start(&range))
+        .bind(after.map(|(start_time, _)| start_time))
+        .bind(after.map(|(_, id)| id))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Sessions in the range whose app, window or page title, URL, directory, project, file, tag or
+    /// note matches `term` (case-insensitive, anywhere in the text), newest first, with their total seconds.
+    /// AFK and idle sessions are left out
+    pub async fn search_usage(&self, term: &str, range: ViewMode) -> Result<(i64, Vec<Session>)> {
+        let pattern = crate::ui::commands::search_pattern(term)?;
+        let rows = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE start_time >= $1
+              AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+              AND (
+                app_name ILIKE $2 OR window_name ILIKE $2
+                OR browser_page_title ILIKE $2 OR browser_page_title_renamed ILIKE $2 OR browser_url ILIKE $2
+                OR terminal_directory ILIKE $2 OR terminal_project_name ILIKE $2 OR tmux_window_name ILIKE $2
+                OR editor_filename ILIKE $2 OR editor_filepath ILIKE $2 OR editor_project_path ILIKE $2
+                OR ide_project_name ILIKE $2 OR ide_file_open ILIKE $2 OR ide_workspace ILIKE $2
+                OR tag ILIKE $2 OR note ILIKE $2
+              )
+            ORDER BY start_time DESC, id DESC
+            "#,
         )
+        .bind(self.range_start(&range))
+        .bind(pattern)
+        .fetch_all(&self.pool)
+        .await?;
+        let total_secs = rows.iter().map(|session| session.duration).sum();
+        Ok((total_secs, rows))
+    }
+
+    /// Sessions starting in [start, end), newest first; the history popup uses it to show a single day
+    pub async fn get_sessions_between(&self, start: chrono::DateTime<chrono::Local>, end: chrono::DateTime<chrono::Local>) -> Result<Vec<Session>> {
+        let rows = sqlx::query_as::<_, Session>(
+            r#"
+            SELECT
+                id, app_name, window_name, start_time, duration, category,
+                browser_url, browser_page_title, browser_notification_count,
+                browser_page_title_renamed, browser_page_title_category,
+                terminal_username, terminal_hostname, terminal_directory, terminal_project_name,
+                terminal_directory_renamed, terminal_directory_category,
+                editor_filename, editor_filepath, editor_project_path, editor_language,
+                editor_filename_renamed, editor_filename_category,
+                tmux_window_name, tmux_pane_count, terminal_multiplexer,
+                tmux_window_name_renamed, tmux_window_name_category,
+                ide_project_name, ide_file_open, ide_workspace,
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
+            FROM sessions
+            WHERE start_time >= $1 AND start_time < $2
+            ORDER BY start_time DESC, id DESC
+            "#,
+        )
+        .bind(start)
+        .bind(end)
         .fetch_all(&self.pool)
         .await?;
         Ok(rows)
     }
 
+    /// Sessions of one local calendar day, newest first
+    pub async fn get_sessions_on(&self, date: chrono::NaiveDate) -> Result<Vec<Session>> {
+        let start = Self::local_midnight(date);
+        let end = date.succ_opt().map_or(start + chrono::Duration::days(1), Self::local_midnight);
+        self.get_sessions_between(start, end).await
+    }
+
+    /// Local midnight at the start of a view mode's range, matching get_daily/weekly/monthly_sessions
+    fn range_start(&self, range: &ViewMode) -> chrono::DateTime<chrono::Local> {
+        let today = chrono::Local::now().date_naive();
+        let today_start = Self::local_midnight(today);
+        match range {
+            ViewMode::Daily => today_start,
+            ViewMode::Weekly => Self::local_midnight(crate::ui::metrics::week_start_date(today, self.week_start)),
+            ViewMode::Monthly => today_start - chrono::Duration::days(29),
+        }
+    }
+
+    fn local_midnight(date: chrono::NaiveDate) -> chrono::DateTime<chrono::Local> {
+        date.and_hms_opt(0, 0, 0).unwrap().and_local_timezone(chrono::Local).unwrap()
+    }
+
+    /// Local time zone for `start_time AT TIME ZONE`, so days and hours in the past follow daylight
+    /// saving changes. Falls back to today's fixed offset, written POSIX style (east of UTC is negative)
+    fn local_time_zone() -> String {
+        iana_time_zone::get_timezone().unwrap_or_else(|e| {
+            log::warn!("Could not detect the local time zone, using the current UTC offset: {}", e);
+            let offset_secs = -chrono::Local::now().offset().local_minus_utc();
+            let sign = if offset_secs < 0 { '-' } else { '+' };
+            format!("UTC{}{:02}:{:02}", sign, offset_secs.abs() / 3600, offset_secs.abs() % 3600 / 60)
+        })
+    }
+
     pub async fn rename_app_with_category(&self, old_name: &str, new_name: &str, category: &str) -> Result<()> {
         sqlx::query("UPDATE sessions SET app_name = $1, category = $2 WHERE app_name = $3")
             .bind(new_name)
@@ -143,6 +574,41 @@ impl Database {
         Ok(())
     }
 
+    /// Rename a category in every column that holds one, carrying its sub-categories along
+    /// ("💻 Development/Rust" follows "💻 Development"). Returns how many rows changed
+    pub async fn rename_category(&self, old: &str, new: &str) -> Result<u64> {
+        // Same test as metrics::parent_category: the text before the '/' is the parent
+        let is_child = |column: &str| format!("(starts_with({c}, $1) AND left(ltrim(substr({c}, length($1) + 1)), 1) = '/')", c = column);
+        let assignments: Vec<String> = CATEGORY_COLUMNS
+            .iter()
+            .map(|column| {
+                format!(
+                    "{c} = CASE WHEN {c} = $1 THEN $2 WHEN {child} THEN $2 || substr({c}, length($1) + 1) ELSE {c} END",
+                    c = column,
+                    child = is_child(column)
+                )
+            })
+            .collect();
+        let matches: Vec<String> = CATEGORY_COLUMNS.iter().map(|column| format!("{} = $1 OR {}", column, is_child(column))).collect();
+        let result = sqlx::query(&format!("UPDATE sessions SET {} WHERE {}", assignments.join(", "), matches.join(" OR ")))
+            .bind(old)
+            .bind(new)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Number of sessions and total seconds recorded under an app name, shown before renaming it
+    pub async fn count_sessions_for_app(&self, app_name: &str) -> Result<(i64, i64)> {
+        let (count, total_seconds): (i64, Option<i64>) = sqlx::query_as(
+            "SELECT COUNT(*), SUM(duration)::bigint FROM sessions WHERE app_name = $1"
+        )
+        .bind(app_name)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok((count, total_seconds.unwrap_or(0)))
+    }
+
     pub async fn update_app_category(&self, app_name: &str, category: &str) -> Result<()> {
         sqlx::query("UPDATE sessions SET category = $1 WHERE app_name = $2")
             .bind(category)
@@ -152,8 +618,10 @@ impl Database {
         Ok(())
     }
 
+    /// Matches the stored (trimmed) title or the raw title it was trimmed from, so sessions saved
+    /// before and after title trimming are renamed together
     pub async fn rename_browser_page_title(&self, old_title: &str, new_title: &str) -> Result<()> {
-        sqlx::query("UPDATE sessions SET browser_page_title_renamed = $1 WHERE browser_page_title = $2")
+        sqlx::query("UPDATE sessions SET browser_page_title_renamed = $1 WHERE browser_page_title = $2 OR parsed_data->>'browser_raw_title' = $2")
             .bind(new_title)
             .bind(old_title)
             .execute(&self.pool)
@@ -161,8 +629,9 @@ impl Database {
         Ok(())
     }
 
+    /// Matches raw titles too, like `rename_browser_page_title`
     pub async fn categorize_browser_page_title(&self, title: &str, category: &str) -> Result<()> {
-        sqlx::query("UPDATE sessions SET browser_page_title_category = $1 WHERE browser_page_title = $2")
+        sqlx::query("UPDATE sessions SET browser_page_title_category = $1 WHERE browser_page_title = $2 OR parsed_data->>'browser_raw_title' = $2")
             .bind(category)
             .bind(title)
             .execute(&self.pool)
@@ -276,7 +745,7 @@ impl Database {
                 tmux_window_name, tmux_pane_count, terminal_multiplexer,
                 tmux_window_name_renamed, tmux_window_name_category,
                 ide_project_name, ide_file_open, ide_workspace,
-                parsed_data, parsing_success, is_afk, is_idle
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
             FROM sessions
             WHERE start_time >= $1
             ORDER BY start_time DESC
@@ -288,11 +757,18 @@ impl Database {
         Ok(rows)
     }
 
+    /// Which week the weekly queries cover; rolling (last 7 days) until set
+    pub fn set_week_start(&mut self, week_start: WeekStart) {
+        self.week_start = week_start;
+    }
+
     pub async fn get_weekly_sessions(&self) -> Result<Vec<Session>> {
-        // Get local midnight 7 days ago (start of week in local timezone)
-        let now = chrono::Local::now();
-        let today_start = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_local_timezone(chrono::Local).unwrap();
-        let week_start = today_start - chrono::Duration::days(6);
+        self.get_calendar_week_sessions(self.week_start).await
+    }
+
+    /// Sessions since local midnight on the first day of the current week, as defined by `week_start`
+    pub async fn get_calendar_week_sessions(&self, week_start: WeekStart) -> Result<Vec<Session>> {
+        let week_start = Self::local_midnight(crate::ui::metrics::week_start_date(chrono::Local::now().date_naive(), week_start));
 
         let rows = sqlx::query_as::<_, Session>(
             r#"
@@ -307,7 +783,7 @@ impl Database {
                 tmux_window_name, tmux_pane_count, terminal_multiplexer,
                 tmux_window_name_renamed, tmux_window_name_category,
                 ide_project_name, ide_file_open, ide_workspace,
-                parsed_data, parsing_success, is_afk, is_idle
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
             FROM sessions
             WHERE start_time >= $1
             ORDER BY start_time DESC
@@ -338,7 +814,7 @@ impl Database {
                 tmux_window_name, tmux_pane_count, terminal_multiplexer,
                 tmux_window_name_renamed, tmux_window_name_category,
                 ide_project_name, ide_file_open, ide_workspace,
-                parsed_data, parsing_success, is_afk, is_idle
+                parsed_data, parsing_success, is_afk, is_idle, tag, hostname, note
             FROM sessions
             WHERE start_time >= $1
             ORDER BY start_time DESC
@@ -349,9 +825,325 @@ impl Database {
         .await?;
         Ok(rows)
     }
+
+    /// Re-apply the categorization rules to every stored session, returning how many rows changed.
+    /// With `skip_custom`, apps that carry a user-created category are left untouched.
+    pub async fn recategorize_all<F>(&self, categorize_fn: F, skip_custom: bool) -> Result<u64>
+    where
+        F: Fn(&str, Option<&str>) -> String,
+    {
+        let custom_apps: HashSet<String> = if skip_custom {
+            let rows: Vec<(String,)> = sqlx::query_as(
+                "SELECT DISTINCT app_name FROM sessions WHERE category IS NOT NULL AND NOT (category = ANY($1))"
+            )
+            .bind(&DEFAULT_CATEGORIES[..])
+            .fetch_all(&self.pool)
+            .await?;
+            rows.into_iter().map(|(app_name,)| app_name).collect()
+        } else {
+            HashSet::new()
+        };
+
+        // Window titles matter too (e.g. meetings), so categorize each distinct app/window pair
+        let pairs: Vec<(String, Option<String>)> = sqlx::query_as("SELECT DISTINCT app_name, window_name FROM sessions")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut changed = 0;
+        for (app_name, window_name) in pairs {
+            if custom_apps.contains(&app_name) {
+                continue;
+            }
+
+            let category = categorize_fn(&app_name, window_name.as_deref());
+            let result = sqlx::query(
+                "UPDATE sessions SET category = $1 WHERE app_name = $2 AND window_name IS NOT DISTINCT FROM $3 AND category IS DISTINCT FROM $1"
+            )
+            .bind(&category)
+            .bind(&app_name)
+            .bind(&window_name)
+            .execute(&self.pool)
+            .await?;
+            changed += result.rows_affected();
+        }
+        Ok(changed)
+    }
+
+    /// Fill in the page category of browser sessions from their detected service (stored in browser_url),
+    /// for older rows that never got one. Rows with a category already, set by hand or by an
+    /// earlier run, are left alone, so running it again changes nothing. Returns rows updated
+    pub async fn backfill_browser_categories<F>(&self, category_for_service: F) -> Result<u64>
+    where
+        F: Fn(&str) -> Option<&'static str>,
+    {
+        let services: Vec<(String,)> = sqlx::query_as(
+            "SELECT DISTINCT browser_url FROM sessions WHERE browser_url IS NOT NULL AND browser_page_title_category IS NULL"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut changed = 0;
+        for (service,) in services {
+            let Some(category) = category_for_service(&service) else {
+                continue;
+            };
+            let result = sqlx::query(
+                "UPDATE sessions SET browser_page_title_category = $1 WHERE browser_url = $2 AND browser_page_title_category IS NULL"
+            )
+            .bind(category)
+            .bind(&service)
+            .execute(&self.pool)
+            .await?;
+            log::info!("Backfilled {} sessions on {} as {}", result.rows_affected(), service, category);
+            changed += result.rows_affected();
+        }
+        Ok(changed)
+    }
+
+    /// Sum non-AFK duration per stored category for the given range, largest first
+    pub async fn get_category_usage(&self, range: ViewMode) -> Result<Vec<(String, i64)>> {
+        let range_start = self.range_start(&range);
+
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT category, SUM(duration)::bigint as total_duration FROM sessions WHERE start_time >= $1 AND category IS NOT NULL AND is_afk IS NOT TRUE GROUP BY category ORDER BY total_duration DESC"
+        )
+        .bind(range_start)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(category, total_duration)| (category, total_duration.unwrap_or(0))).collect())
+    }
+
+    /// Count, length and idle share of the range's breaks (AFK sessions)
+    pub async fn get_afk_stats(&self, range: ViewMode) -> Result<crate::ui::metrics::AfkStats> {
+        let rows: Vec<(i64, bool)> = sqlx::query_as(
+            "SELECT duration, COALESCE(is_idle, FALSE) FROM sessions WHERE start_time >= $1 AND is_afk IS TRUE"
+        )
+        .bind(self.range_start(&range))
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(crate::ui::metrics::AfkStats::from_sessions(&rows))
+    }
+
+    /// Sum non-AFK duration per machine for the given range, largest first;
+    /// sessions recorded before hostnames were stored count as "unknown"
+    pub async fn get_usage_by_hostname(&self, range: ViewMode) -> Result<Vec<(String, i64)>> {
+        let range_start = self.range_start(&range);
+
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(
+            "SELECT COALESCE(hostname, 'unknown') AS machine, SUM(duration)::bigint as total_duration FROM sessions WHERE start_time >= $1 AND is_afk IS NOT TRUE GROUP BY machine ORDER BY total_duration DESC"
+        )
+        .bind(range_start)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(machine, total_duration)| (machine, total_duration.unwrap_or(0))).collect())
+    }
+
+    /// Start of the earliest recorded session, or None when nothing has been tracked yet
+    pub async fn get_first_session_time(&self) -> Result<Option<chrono::DateTime<chrono::Local>>> {
+        let first: (Option<chrono::DateTime<chrono::Local>>,) = sqlx::query_as("SELECT MIN(start_time) FROM sessions")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(first.0)
+    }
+
+    /// Unread counts from browser titles like "(3) Inbox - Gmail", per hour over the last `hours` hours.
+    /// Each service contributes its highest count in the hour; the points are the total across services.
+    pub async fn get_notification_trend(&self, hours: i64) -> Result<Vec<(chrono::DateTime<chrono::Local>, i64)>> {
+        let range_start = chrono::Local::now() - chrono::Duration::hours(hours);
+        let rows: Vec<(chrono::DateTime<chrono::Local>, i64)> = sqlx::query_as(
+            r#"
+            SELECT hour, SUM(unread)::bigint
+            FROM (
+                SELECT date_trunc('hour', start_time) AS hour, browser_url, MAX(browser_notification_count) AS unread
+                FROM sessions
+                WHERE start_time >= $1 AND browser_url IS NOT NULL AND browser_notification_count IS NOT NULL
+                GROUP BY hour, browser_url
+            ) per_service
+            GROUP BY hour
+            ORDER BY hour
+            "#,
+        )
+        .bind(range_start)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Distinct local days (most recent first) that have any non-AFK tracked time.
+    pub async fn get_active_days(&self, limit: i64) -> Result<Vec<chrono::NaiveDate>> {
+        // Local days, so day boundaries match the local-midnight daily queries
+        let time_zone = Self::local_time_zone();
+
+        let rows: Vec<(chrono::NaiveDate,)> = sqlx::query_as(
+            "SELECT DISTINCT (start_time AT TIME ZONE $1)::date AS day FROM sessions WHERE duration > 0 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE ORDER BY day DESC LIMIT $2"
+        )
+        .bind(&time_zone)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(day,)| day).collect())
+    }
+
+    /// Session starts per local hour of day over the last `days` days, a proxy for context switches.
+    /// AFK/idle sessions are excluded; with `coalesce_same_app`, a session of the same app as the
+    /// previous one (e.g. after returning from AFK) is not counted as a switch.
+    pub async fn get_switches_per_hour(&self, days: i64, coalesce_same_app: bool) -> Result<[i64; 24]> {
+        let range_start = self.range_start(&ViewMode::Daily) - chrono::Duration::days((days - 1).max(0));
+        let time_zone = Self::local_time_zone();
+
+        let rows: Vec<(i32, i64)> = sqlx::query_as(
+            r#"
+            WITH ordered AS (
+                SELECT start_time, app_name, LAG(app_name) OVER (ORDER BY start_time) AS previous_app
+                FROM sessions
+                WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+            )
+            SELECT EXTRACT(HOUR FROM start_time AT TIME ZONE $2)::int AS hour, COUNT(*)::bigint
+            FROM ordered
+            WHERE NOT $3 OR previous_app IS DISTINCT FROM app_name
+            GROUP BY hour
+            "#,
+        )
+        .bind(range_start)
+        .bind(&time_zone)
+        .bind(coalesce_same_app)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut per_hour = [0i64; 24];
+        for (hour, count) in rows {
+            if let Some(bucket) = per_hour.get_mut(hour as usize) {
+                *bucket = count;
+            }
+        }
+        Ok(per_hour)
+    }
+
+    /// Average non-AFK seconds per day for each weekday (Monday first) over the last `weeks` weeks, in local time.
+    /// Each weekday is divided by how many times it occurs in the range, so the current partial week counts correctly.
+    pub async fn get_weekday_averages(&self, weeks: i64) -> Result<[f64; 7]> {
+        let range_start = self.range_start(&ViewMode::Daily) - chrono::Duration::days((weeks * 7 - 1).max(0));
+        let time_zone = Self::local_time_zone();
+
+        let rows: Vec<(i32, Option<i64>)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(ISODOW FROM start_time AT TIME ZONE $2)::int AS weekday,
+                   SUM(duration)::bigint
+            FROM sessions
+            WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+            GROUP BY weekday
+            "#,
+        )
+        .bind(range_start)
+        .bind(&time_zone)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut totals = [0i64; 7];
+        for (weekday, total) in rows {
+            if let Some(bucket) = totals.get_mut((weekday - 1) as usize) {
+                *bucket = total.unwrap_or(0);
+            }
+        }
+        Ok(crate::ui::metrics::weekday_averages(totals, range_start.date_naive(), chrono::Local::now().date_naive()))
+    }
+
+    /// (ISO year, ISO week, non-AFK seconds) for each of the last `weeks` ISO weeks (this one included), oldest first,
+    /// in local time. Grouping by ISOYEAR rather than YEAR keeps late-December and early-January days in the right week
+    pub async fn get_weekly_totals(&self, weeks: i64) -> Result<Vec<(i32, i32, i64)>> {
+        let today = chrono::Local::now().date_naive();
+        let this_monday = today - chrono::Duration::days(chrono::Datelike::weekday(&today).num_days_from_monday() as i64);
+        let first_monday = this_monday - chrono::Duration::weeks((weeks - 1).max(0));
+        let time_zone = Self::local_time_zone();
+
+        let rows: Vec<(i32, i32, Option<i64>)> = sqlx::query_as(
+            r#"
+            SELECT EXTRACT(ISOYEAR FROM start_time AT TIME ZONE $2)::int AS iso_year,
+                   EXTRACT(WEEK FROM start_time AT TIME ZONE $2)::int AS iso_week,
+                   SUM(duration)::bigint
+            FROM sessions
+            WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+            GROUP BY iso_year, iso_week
+            "#,
+        )
+        .bind(Self::local_midnight(first_monday))
+        .bind(&time_zone)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rows: Vec<(i32, i32, i64)> = rows.into_iter().map(|(year, week, total)| (year, week, total.unwrap_or(0))).collect();
+        Ok(crate::ui::metrics::iso_week_totals(&rows, first_monday, today))
+    }
+
+    /// Non-AFK seconds per category for each of the last `days` days (today included), oldest first.
+    /// Days without activity are kept, empty, so a chart of them reads left to right without gaps
+    pub async fn get_category_usage_by_day(&self, days: i64) -> Result<Vec<(chrono::NaiveDate, Vec<(String, i64)>)>> {
+        let range_start = self.range_start(&ViewMode::Daily) - chrono::Duration::days((days - 1).max(0));
+        let time_zone = Self::local_time_zone();
+
+        let rows: Vec<(chrono::NaiveDate, String, Option<i64>)> = sqlx::query_as(
+            r#"
+            SELECT (start_time AT TIME ZONE $2)::date AS day, category, SUM(duration)::bigint
+            FROM sessions
+            WHERE start_time >= $1 AND category IS NOT NULL AND is_afk IS NOT TRUE
+            GROUP BY day, category
+            "#,
+        )
+        .bind(range_start)
+        .bind(&time_zone)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rows: Vec<(chrono::NaiveDate, String, i64)> = rows.into_iter().map(|(day, category, total)| (day, category, total.unwrap_or(0))).collect();
+        Ok(crate::ui::metrics::category_days(&rows, range_start.date_naive(), days))
+    }
+
+    /// Non-AFK seconds per project within a view mode's range, limited to the given categories.
+    /// The project is the IDE or terminal project when known, otherwise the app name.
+    pub async fn get_project_usage(&self, range: ViewMode, categories: &[String]) -> Result<Vec<(String, i64)>> {
+        let rows: Vec<(String, Option<i64>)> = sqlx::query_as(&format!(
+            r#"
+            SELECT COALESCE({PROJECT_SQL}, app_name) AS project,
+                   SUM(duration)::bigint AS total_duration
+            FROM sessions
+            WHERE start_time >= $1 AND category = ANY($2) AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+            GROUP BY project
+            ORDER BY total_duration DESC
+            "#
+        ))
+        .bind(self.range_start(&range))
+        .bind(categories)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|(project, total_duration)| (project, total_duration.unwrap_or(0))).collect())
+    }
+
+    /// Project with the most non-AFK time in the weekly range, falling back to the top app
+    /// when no session this week has a project
+    pub async fn get_busiest_project_this_week(&self) -> Result<Option<(String, i64)>> {
+        let row: Option<(String, Option<i64>)> = sqlx::query_as(&format!(
+            r#"
+            SELECT name, total_duration FROM (
+                SELECT COALESCE({PROJECT_SQL}, app_name) AS name,
+                       {PROJECT_SQL} IS NULL AS is_app,
+                       SUM(duration)::bigint AS total_duration
+                FROM sessions
+                WHERE start_time >= $1 AND is_afk IS NOT TRUE AND is_idle IS NOT TRUE
+                GROUP BY 1, 2
+            ) totals
+            ORDER BY is_app, total_duration DESC
+            LIMIT 1
+            "#
+        ))
+        .bind(self.range_start(&ViewMode::Weekly))
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(row.map(|(name, total_duration)| (name, total_duration.unwrap_or(0))))
+    }
+
     pub async fn get_custom_categories(&self) -> Result<Vec<String>> {
         let categories: Vec<(String,)> = sqlx::query_as(
-            "SELECT DISTINCT category FROM sessions WHERE category IS NOT NULL AND category NOT IN ($1, $2, $3, $4, $5, $6, $7, $8)"
+            "SELECT DISTINCT category FROM sessions WHERE category IS NOT NULL AND category NOT IN ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
         )
         .bind("💻 Development")
         .bind("🌐 Browsing")
@@ -360,6 +1152,8 @@ impl Database {
         .bind("📁 Files")
         .bind("📧 Email")
         .bind("📄 Office")
+        .bind("📅 Meetings")
+        .bind("🗒️ Notes")
         .bind("📦 Other")
         .fetch_all(&self.pool)
         .await?;
@@ -368,3 +1162,43 @@ impl Database {
 }
 
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Needs a scratch PostgreSQL database; skipped unless TEST_DATABASE_URL is set
+    #[tokio::test]
+    async fn test_merge_into_previous_session_stays_on_host() {
+        let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+            return;
+        };
+        let db = Database::new(&url).await.unwrap();
+        let hosts = [format!("merge-test-a-{}", std::process::id()), format!("merge-test-b-{}", std::process::id())];
+        let at = |secs: i64| chrono::Local.timestamp_opt(978307200 + secs, 0).unwrap();
+        let session = |host: &str, start: i64, afk: bool| Session {
+            hostname: Some(host.to_string()),
+            is_afk: Some(afk),
+            ..Session::for_test("code", at(start), 60)
+        };
+
+        let own = db.insert_session(&session(&hosts[0], 0, false)).await.unwrap();
+        let afk = db.insert_session(&session(&hosts[0], 60, true)).await.unwrap();
+        let other = db.insert_session(&session(&hosts[1], 120, false)).await.unwrap();
+
+        let short = Session { duration: 5, ..session(&hosts[0], 180, false) };
+        let merged = db.merge_into_previous_session(&short).await.unwrap();
+        let absorbed_elsewhere = db.merge_into_previous_session(&Session { hostname: Some("merge-test-none".to_string()), ..short }).await.unwrap();
+
+        let durations: Vec<(i32, i64)> = sqlx::query_as("SELECT id, duration FROM sessions WHERE id = ANY($1) ORDER BY id")
+            .bind([own, afk, other])
+            .fetch_all(&db.pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM sessions WHERE hostname = ANY($1)").bind(&hosts).execute(&db.pool).await.unwrap();
+
+        assert!(merged);
+        assert!(!absorbed_elsewhere);
+        assert_eq!(durations, vec![(own, 65), (afk, 60), (other, 60)]);
+    }
+}
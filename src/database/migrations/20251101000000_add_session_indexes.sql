@@ -0,0 +1,5 @@
+-- Every daily/weekly/monthly query filters on start_time
+CREATE INDEX IF NOT EXISTS idx_sessions_start_time ON sessions(start_time);
+
+-- Usage queries group by app_name
+CREATE INDEX IF NOT EXISTS idx_sessions_app_name ON sessions(app_name);
@@ -0,0 +1,4 @@
+-- Freeform label (project, ticket) set from the TUI; it can span sessions of different apps
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS tag TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_tag ON sessions(tag) WHERE tag IS NOT NULL;
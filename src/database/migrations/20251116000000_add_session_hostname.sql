@@ -0,0 +1,4 @@
+-- Machine that recorded the session, for several devices sharing one database; older rows stay NULL
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS hostname TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_hostname ON sessions(hostname) WHERE hostname IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Last time each machine's daemon was running, so the TUI can say when it has stopped
+CREATE TABLE IF NOT EXISTS daemon_heartbeat (
+    hostname TEXT PRIMARY KEY,
+    last_seen TIMESTAMPTZ NOT NULL
+);
@@ -0,0 +1,8 @@
+-- Apps the daemon found past their daily limit, so the TUI can show a banner for them
+CREATE TABLE IF NOT EXISTS app_limit_alerts (
+    app_name TEXT NOT NULL,
+    day DATE NOT NULL,
+    spent_secs BIGINT NOT NULL,
+    limit_minutes BIGINT NOT NULL,
+    PRIMARY KEY (app_name, day)
+);
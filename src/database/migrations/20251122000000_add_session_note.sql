@@ -0,0 +1,2 @@
+-- Freeform note on a single session ("fixed the auth bug"), written from the history popup
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS note TEXT;
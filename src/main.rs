@@ -1,16 +1,20 @@
 mod config;
+mod daemon;
 mod database;
 mod models;
 mod tracker;
 mod ui;
 
 use anyhow::Result;
-use crate::config::settings::Settings;
+use crate::config::settings::{AppConfig, Settings};
 use crate::database::connection::Database;
 use crate::ui::app::App;
+use crate::ui::tracking::ViewMode;
 use dotenvy::dotenv;
+use std::collections::BTreeMap;
 use std::env;
-use std::fs::OpenOptions;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use clap::{Arg, Command};
 
 #[tokio::main]
@@ -25,16 +29,149 @@ async fn main() -> Result<()> {
                 .help("Test D-Bus idle detection instead of running the full UI")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("doctor")
+                .long("doctor")
+                .help("Check session type, active window detection, required tools and idle detection, print a report and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("recategorize")
+                .long("recategorize")
+                .help("Re-run the categorization rules over all stored sessions and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("overwrite-custom")
+                .long("overwrite-custom")
+                .help("With --recategorize, also replace custom categories you created")
+                .requires("recategorize")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("backfill")
+                .long("backfill")
+                .help("Give older browser sessions a page category from their detected service (GitHub, Gmail...) and exit; categories you set are kept")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("export-db")
+                .long("export-db")
+                .value_name("FILE")
+                .help("Export every session to a newline-delimited JSON file and exit")
+                .conflicts_with("import-db"),
+        )
+        .arg(
+            Arg::new("import-db")
+                .long("import-db")
+                .value_name("FILE")
+                .help("Import sessions from a file written by --export-db, skipping sessions already in the database, and exit"),
+        )
+        .arg(
+            Arg::new("redact-titles")
+                .long("redact-titles")
+                .help("Start with window titles, file names and page titles hidden (toggle with [p]); app totals stay visible")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .help("Track as usual, but show a live log of detection events (switches, window titles, parser output, AFK) instead of the dashboard")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("view")
+                .long("view")
+                .value_name("VIEW")
+                .value_parser(["dashboard", "breakdown"])
+                .conflicts_with("follow")
+                .help("Open the TUI on this screen (default: dashboard)"),
+        )
+        .arg(
+            Arg::new("mode")
+                .long("mode")
+                .value_name("RANGE")
+                .value_parser(["daily", "weekly", "monthly"])
+                .help("Open the TUI on this range instead of the one used last time"),
+        )
+        .arg(
+            Arg::new("parse")
+                .long("parse")
+                .num_args(2)
+                .value_names(["APP", "TITLE"])
+                .help("Run the window title parser on one app/title pair, print the result as JSON, and exit (no database needed)")
+                .conflicts_with("parse-file"),
+        )
+        .arg(
+            Arg::new("parse-file")
+                .long("parse-file")
+                .value_name("FILE")
+                .help("Like --parse, for every `app<TAB>title` line in FILE"),
+        )
+        .arg(
+            Arg::new("init-db")
+                .long("init-db")
+                .help("Create the database from DATABASE_URL if it doesn't exist, run migrations, and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("invoice")
+                .long("invoice")
+                .value_name("RANGE")
+                .value_parser(["daily", "weekly", "monthly"])
+                .help("Print a per-project breakdown of billable time for the range and exit"),
+        )
+        .arg(
+            Arg::new("tmux-status")
+                .long("tmux-status")
+                .value_name("N")
+                .num_args(0..=1)
+                .default_missing_value("2")
+                .value_parser(clap::value_parser!(usize))
+                .help("Print today's top N categories (default 2) on one line for a tmux status bar and exit"),
+        )
+        .arg(
+            Arg::new("stats")
+                .long("stats")
+                .help("Print today's tracked and AFK time, focus score, category totals and top apps, then exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("timesheet")
+                .long("timesheet")
+                .num_args(1..=2)
+                .value_names(["DATE", "FILE"])
+                .help("Print one day's sessions, category totals and AFK time as a Markdown timesheet (or write it to FILE) and exit. DATE is YYYY-MM-DD, today or yesterday"),
+        )
+        .arg(
+            Arg::new("db-url")
+                .long("db-url")
+                .value_name("URL")
+                .help("Use this postgres:// database instead of DATABASE_URL from .env. The TUI then only views it: no session is tracked into it"),
+        )
+        .arg(
+            Arg::new("headless-track")
+                .long("headless-track")
+                .help("Track windows in the foreground without the TUI or a separate daemon, logging to stderr")
+                .action(clap::ArgAction::SetTrue),
+        )
         .get_matches();
 
     // Load .env file
     dotenv().ok();
 
-    // Check if debug logging is enabled via .env
+    // Load runtime settings (config.toml is created with defaults on first run)
+    let app_config = AppConfig::load();
+    crate::daemon::title_trim::set_title_trim_rules(&app_config.title_trim_rules);
+    crate::ui::render::set_time_format(crate::ui::metrics::resolve_time_format(app_config.time_format));
+    crate::ui::render::set_emoji(crate::ui::metrics::resolve_emoji(app_config.emoji));
+
+    // Check if debug logging is enabled via .env or the settings screen
     let debug_enabled = env::var("DEBUG_LOGS_ENABLED")
         .ok()
         .and_then(|v| v.parse::<bool>().ok())
-        .unwrap_or(false);
+        .unwrap_or(false)
+        || app_config.debug_logs_enabled;
 
     if debug_enabled {
         // Enable debug logging to app.log file
@@ -53,7 +190,14 @@ async fn main() -> Result<()> {
 
         log::info!("=== DEBUG LOGGING ENABLED ===");
         log::info!("Writing logs to app.log");
-        log::info!("To disable: Remove DEBUG_LOGS_ENABLED from .env or set to false");
+        log::info!("To disable: Remove DEBUG_LOGS_ENABLED from .env or turn it off in settings [s]");
+    } else if matches.get_flag("headless-track") {
+        // There's no dashboard to show activity, so report it on stderr
+        env_logger::Builder::from_env(
+            env_logger::Env::default()
+                .default_filter_or("neura_hustle_tracker=info")
+        )
+        .init();
     } else {
         // No logging for regular users
         env_logger::Builder::from_env(
@@ -65,18 +209,64 @@ async fn main() -> Result<()> {
 
     // Check if we're running idle test mode
     if matches.get_flag("test-idle") {
-        println!("Testing Wayland D-Bus idle detection...");
         test_idle_detection().await?;
         return Ok(());
     }
 
+    // Setup check mode: exits non-zero so scripts can tell a broken setup apart
+    if matches.get_flag("doctor") {
+        if !crate::tracker::doctor::run().await {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Parser development mode: runs before any database or .env setup
+    if let Some(mut values) = matches.get_many::<String>("parse") {
+        let (app, title) = (values.next().unwrap(), values.next().unwrap());
+        println!("{}", serde_json::to_string_pretty(&crate::ui::parser::parse_window_name(app, title))?);
+        return Ok(());
+    }
+    if let Some(path) = matches.get_one::<String>("parse-file") {
+        parse_file(path)?;
+        return Ok(());
+    }
+
     log::info!("Starting Neura Hustle Tracker");
-    let settings = Settings::new().unwrap();
+    let db_url = matches.get_one::<String>("db-url");
+    let settings = match Settings::new(db_url.map(String::as_str)) {
+        Ok(settings) => settings,
+        Err(e) if db_url.is_some() => {
+            eprintln!("❌ --db-url: {}", e);
+            std::process::exit(1);
+        }
+        Err(e) => return Err(e),
+    };
+
+    // Setup mode: normal startup still fails if the database is missing
+    if matches.get_flag("init-db") {
+        init_db(&settings.database_url).await;
+        return Ok(());
+    }
+
+    // Server/VM mode: the daemon's tracking loop, without raw mode or rendering
+    if matches.get_flag("headless-track") {
+        run_headless(&settings.database_url, app_config).await?;
+        return Ok(());
+    }
+
     log::info!("Connecting to database...");
     log::info!("Database URL: {}", settings.database_url);
     log::info!("Environment variables loaded: POSTGRES_USERNAME={}, POSTGRES_PASSWORD=***", 
                env::var("POSTGRES_USERNAME").unwrap_or_else(|_| "NOT_SET".to_string()));
-    let database = match Database::new(&settings.database_url).await {
+    // A database given with --db-url is only viewed, so it's left unmigrated unless a command writes to it
+    let writes = matches.get_flag("recategorize") || matches.get_flag("backfill") || matches.contains_id("import-db");
+    let connected = if db_url.is_some() && !writes {
+        Database::new_read_only(&settings.database_url).await
+    } else {
+        Database::new(&settings.database_url).await
+    };
+    let database = match connected {
         Ok(db) => {
             log::info!("Database connection successful");
             db
@@ -96,26 +286,299 @@ async fn main() -> Result<()> {
     };
     log::info!("Connected successfully. Creating tables...");
 
+    // Status bar mode: runs before ANALYZE since tmux calls it every few seconds
+    if let Some(count) = matches.get_one::<usize>("tmux-status") {
+        print_tmux_status(&database, &app_config, *count).await?;
+        return Ok(());
+    }
+
+    // Read-only summary, also before ANALYZE since it may run under `watch`
+    if matches.get_flag("stats") {
+        let sessions = database.get_daily_sessions().await?;
+        let categories = database.get_category_usage(ViewMode::Daily).await?;
+        let rounding_minutes = crate::ui::metrics::resolve_rounding_minutes(app_config.rounding_minutes);
+        print!("{}", crate::ui::reports::day_stats(&sessions, &categories, rounding_minutes));
+        return Ok(());
+    }
+
+    if app_config.analyze_on_startup && db_url.is_none() {
+        log::info!("Running ANALYZE on sessions...");
+        if let Err(e) = database.analyze().await {
+            log::warn!("Failed to analyze sessions table: {}", e);
+        }
+    }
+
+    // Maintenance mode: apply current categorization rules to existing sessions
+    if matches.get_flag("recategorize") {
+        let skip_custom = !matches.get_flag("overwrite-custom");
+        let changed = database
+            .recategorize_all(|app, window| App::categorize_window(app, window).0, skip_custom)
+            .await?;
+        println!("✅ Recategorized {} sessions", changed);
+        return Ok(());
+    }
+
+    // Maintenance mode: fill in browser categories missing from older sessions
+    if matches.get_flag("backfill") {
+        let changed = database.backfill_browser_categories(crate::ui::parser::service_category).await?;
+        log::info!("Backfilled browser categories on {} sessions", changed);
+        println!("✅ Backfilled browser categories on {} sessions", changed);
+        return Ok(());
+    }
+
+    // Backup/restore mode: portable JSONL dump independent of pg_dump
+    if let Some(path) = matches.get_one::<String>("export-db") {
+        let exported = export_db(&database, path).await?;
+        println!("✅ Exported {} sessions to {}", exported, path);
+        return Ok(());
+    }
+    if let Some(path) = matches.get_one::<String>("import-db") {
+        let (imported, skipped) = import_db(&database, path).await?;
+        println!("✅ Imported {} sessions from {} ({} already present)", imported, path, skipped);
+        return Ok(());
+    }
+
+    if let Some(range) = matches.get_one::<String>("invoice") {
+        print_invoice(&database, &app_config, range).await?;
+        return Ok(());
+    }
+
+    if let Some(mut values) = matches.get_many::<String>("timesheet") {
+        let date = values.next().unwrap();
+        write_timesheet(&database, date, values.next().map(String::as_str)).await?;
+        return Ok(());
+    }
+
     log::info!("Tables created. Starting application...");
 
-    let mut app = App::new(database);
+    let mut app = App::new(database, app_config);
+    app.redact_titles = matches.get_flag("redact-titles");
+    app.follow_logs = matches.get_flag("follow");
+    app.start_view_mode = matches.get_one::<String>("mode").map(String::as_str).map(view_mode_arg);
+    app.start_in_breakdown = matches.get_one::<String>("view").is_some_and(|view| view == "breakdown");
+    // Looking at someone else's data shouldn't add your own sessions to it
+    app.viewer_database = db_url.map(String::as_str).map(database_label);
     app.run().await?;
 
     Ok(())
 }
 
-async fn test_idle_detection() -> Result<()> {
-    println!("Testing Wayland D-Bus idle detection...");
+async fn init_db(database_url: &str) {
+    match Database::create_if_missing(database_url).await {
+        Ok(true) => println!("✅ Created database"),
+        Ok(false) => println!("✅ Database already exists"),
+        Err(e) => {
+            eprintln!("❌ Failed to create database. Please check:");
+            eprintln!("  - PostgreSQL server is running (make daemon-status)");
+            eprintln!("  - .env file has correct DATABASE_URL, with a user allowed to create databases");
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    }
+
+    // Connecting runs the migrations
+    match Database::new(database_url).await {
+        Ok(_) => println!("✅ Migrations applied, database is ready"),
+        Err(e) => {
+            eprintln!("❌ Failed to run migrations: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run_headless(database_url: &str, app_config: AppConfig) -> Result<()> {
+    // A running daemon would record every window a second time
+    let daemon_running = match &app_config.ipc_socket_path {
+        Some(path) => crate::daemon::ipc::query_status(std::path::Path::new(path)).await.is_ok(),
+        None => false,
+    };
+    if daemon_running {
+        eprintln!("❌ The daemon is already tracking. Stop it first (make daemon-stop) or use it instead of --headless-track.");
+        std::process::exit(1);
+    }
+
+    log::info!("Starting headless tracking (Ctrl+C to stop)");
+    let database = match crate::daemon::database::connection::Database::new(database_url).await {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("❌ Failed to connect to database. Please check:");
+            eprintln!("  - Database is running (make daemon-status)");
+            eprintln!("  - .env file has correct DATABASE_URL");
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut daemon = crate::daemon::active_window::daemon::Daemon::new(database, app_config);
+    daemon.run().await
+}
+
+// "host:port/database" for the status bar, leaving out the credentials
+fn database_label(database_url: &str) -> String {
+    match database_url.parse::<sqlx::postgres::PgConnectOptions>() {
+        Ok(options) => format!("{}:{}/{}", options.get_host(), options.get_port(), options.get_database().unwrap_or("postgres")),
+        Err(_) => "another database".to_string(),
+    }
+}
+
+fn parse_file(path: &str) -> Result<()> {
+    let reader = BufReader::new(File::open(path)?);
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((app, title)) = line.split_once('\t') else {
+            eprintln!("Line {}: expected `app<TAB>title`, skipping", line_number + 1);
+            continue;
+        };
+        println!("# {} | {}", app, title);
+        println!("{}", serde_json::to_string_pretty(&crate::ui::parser::parse_window_name(app, title))?);
+    }
+    Ok(())
+}
+
+async fn export_db(database: &Database, path: &str) -> Result<usize> {
+    let sessions = database.get_recent_sessions(i64::MAX).await?;
+    let mut writer = BufWriter::new(File::create(path)?);
+    for session in &sessions {
+        serde_json::to_writer(&mut writer, session)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(sessions.len())
+}
+
+/// Sessions read from the file before each batch insert
+const IMPORT_CHUNK_SESSIONS: usize = 5000;
+
+async fn import_db(database: &Database, path: &str) -> Result<(usize, usize)> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut pending = Vec::new();
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut session: crate::models::session::Session = serde_json::from_str(&line)
+            .map_err(|e| anyhow::anyhow!("Invalid session on line {}: {}", line_number + 1, e))?;
+        // Exports from older versions carry untagged parsed_data blobs
+        session.parsed_data = session.parsed_data.map(crate::ui::parser::migrate_parsed_data);
+        pending.push(session);
+
+        if pending.len() >= IMPORT_CHUNK_SESSIONS {
+            let (chunk_imported, chunk_skipped) = import_sessions(database, std::mem::take(&mut pending)).await?;
+            imported += chunk_imported;
+            skipped += chunk_skipped;
+        }
+    }
+    let (chunk_imported, chunk_skipped) = import_sessions(database, pending).await?;
+    Ok((imported + chunk_imported, skipped + chunk_skipped))
+}
+
+// Inserts the sessions the database doesn't have yet, matched on start time, app and hostname
+// (repeats within the file included); returns (imported, skipped)
+async fn import_sessions(database: &Database, sessions: Vec<crate::models::session::Session>) -> Result<(usize, usize)> {
+    let start_times: Vec<_> = sessions.iter().map(|session| session.start_time).collect();
+    let mut existing = database.existing_session_keys(&start_times).await?;
+    let total = sessions.len();
+    let new_sessions: Vec<_> = sessions
+        .into_iter()
+        .filter(|session| existing.insert((session.start_time, session.app_name.clone(), session.hostname.clone())))
+        .collect();
+    database.insert_sessions_batch(&new_sessions).await?;
+    Ok((new_sessions.len(), total - new_sessions.len()))
+}
+
+async fn print_tmux_status(database: &Database, config: &AppConfig, count: usize) -> Result<()> {
+    // Legacy category names share a display name, so merge them before picking the top ones
+    let mut totals: BTreeMap<String, i64> = BTreeMap::new();
+    for (category, secs) in database.get_category_usage(ViewMode::Daily).await? {
+        *totals.entry(App::category_from_string(&category).0).or_insert(0) += secs;
+    }
+    let mut categories: Vec<(String, i64)> = totals.into_iter().collect();
+    categories.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+
+    let rounding_minutes = crate::ui::metrics::resolve_rounding_minutes(config.rounding_minutes);
+    println!("{}", crate::ui::metrics::tmux_status(&categories, count, rounding_minutes));
+    Ok(())
+}
+
+// A daily/weekly/monthly argument; clap has already rejected anything else
+fn view_mode_arg(range: &str) -> ViewMode {
+    match range {
+        "daily" => ViewMode::Daily,
+        "monthly" => ViewMode::Monthly,
+        _ => ViewMode::Weekly,
+    }
+}
 
+async fn print_invoice(database: &Database, config: &AppConfig, range: &str) -> Result<()> {
+    let view_mode = view_mode_arg(range);
+    let projects = database.get_project_usage(view_mode, &config.billable_categories).await?;
+
+    println!("Billable time ({}) for: {}", range, config.billable_categories.join(", "));
+    if projects.is_empty() {
+        println!("No billable sessions in this range.");
+        return Ok(());
+    }
+
+    let currency = &config.currency_symbol;
+    let mut total_secs = 0;
+    for (project, secs) in &projects {
+        total_secs += secs;
+        let amount = *secs as f64 * config.hourly_rate / 3600.0;
+        println!("  {:<40} {:>4}h {:>2}m  {}{:.2}", project, secs / 3600, (secs % 3600) / 60, currency, amount);
+    }
+    let total_amount = total_secs as f64 * config.hourly_rate / 3600.0;
+    println!("  {:<40} {:>4}h {:>2}m  {}{:.2}", "Total", total_secs / 3600, (total_secs % 3600) / 60, currency, total_amount);
+    if config.hourly_rate <= 0.0 {
+        println!("Set hourly_rate in config.toml to see amounts.");
+    }
+    Ok(())
+}
+
+async fn write_timesheet(database: &Database, date: &str, path: Option<&str>) -> Result<()> {
+    let date = crate::ui::commands::parse_history_date(date, chrono::Local::now().date_naive())?;
+    let sessions = database.get_sessions_on(date).await?;
+    let markdown = crate::ui::reports::day_markdown(&sessions);
+    match path {
+        Some(path) => {
+            std::fs::write(path, markdown)?;
+            println!("✅ Wrote the {} timesheet to {}", date, path);
+        }
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
+
+async fn test_idle_detection() -> Result<()> {
     // Import the idle detection function from the app module
     use crate::ui::app::App;
 
-    match App::check_wayland_idle_time().await {
-        Ok(idle_time) => {
-            println!("✅ Success! Idle time: {} seconds", idle_time);
+    // The swayidle watcher starts on the first call and reads 0 until it reports, so sample a few times
+    let samples = if crate::tracker::wlroots_idle::is_wlroots() {
+        println!("Testing wlroots idle detection (swayidle, falling back to logind IdleHint)...");
+        println!("Leave the keyboard and mouse alone to see idle time count up");
+        4
+    } else {
+        println!("Testing Wayland D-Bus idle detection...");
+        1
+    };
+
+    for sample in 0..samples {
+        if sample > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
         }
-        Err(e) => {
-            println!("❌ Failed: {}", e);
+        match App::check_wayland_idle_time().await {
+            Ok(idle_time) => {
+                println!("✅ Success! Idle time: {} seconds", idle_time);
+            }
+            Err(e) => {
+                println!("❌ Failed: {}", e);
+            }
         }
     }
 
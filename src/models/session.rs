@@ -54,6 +54,226 @@ pub struct Session {
 
     // IDLE tracking: true if AFK for 10+ minutes with zero input
     pub is_idle: Option<bool>,
+
+    // Freeform label (project, ticket) set from the TUI
+    pub tag: Option<String>,
+
+    // Machine that recorded the session (None for sessions from before it was stored)
+    pub hostname: Option<String>,
+
+    // Freeform note on this one session, set from the history popup
+    pub note: Option<String>,
+
+    // Row of the session this one carries on after a quick restart; saving updates that row
+    // instead of inserting another. Not a column
+    #[sqlx(skip)]
+    #[serde(skip)]
+    pub resumed_id: Option<i32>,
+}
+
+impl Session {
+    /// Best name for what the session was about, below its app: a rename wins over the page title,
+    /// terminal project, file name or tmux window it replaces, then the window title. Comes with the
+    /// unique id that renames and sub-entry categories are keyed by, and that sub-entry's category.
+    /// None when the session has nothing more specific than its app
+    pub fn effective_display_name(&self) -> Option<(String, String, Option<String>)> {
+        if let Some(page_title) = &self.browser_page_title {
+            let name = self.browser_page_title_renamed.as_ref().unwrap_or(page_title).clone();
+            Some((name, format!("browser_page_title:{}", page_title), self.browser_page_title_category.clone()))
+        } else if let Some(dir) = &self.terminal_directory {
+            let name = self.terminal_directory_renamed.clone().unwrap_or_else(|| extract_project_name(dir).unwrap_or_else(|| dir.clone()));
+            Some((name, format!("terminal_directory:{}", dir), self.terminal_directory_category.clone()))
+        } else if let Some(filename) = &self.editor_filename {
+            let name = self.editor_filename_renamed.as_ref().unwrap_or(filename).clone();
+            Some((name, format!("editor_filename:{}", filename), self.editor_filename_category.clone()))
+        } else if let Some(tmux_window) = &self.tmux_window_name {
+            let name = self.tmux_window_name_renamed.as_ref().unwrap_or(tmux_window).clone();
+            Some((name, format!("tmux_window_name:{}", tmux_window), self.tmux_window_name_category.clone()))
+        } else {
+            self.window_name.as_ref().map(|window| (window.clone(), format!("window_name:{}", window), None))
+        }
+    }
+
+    /// Whether this freshly started session is `previous` carrying on after a quick restart: same app,
+    /// window and machine, neither AFK, and `previous` ended at most `max_gap_secs` before this started
+    pub fn resumes(&self, previous: &Session, max_gap_secs: u64) -> bool {
+        let previous_end = previous.start_time + chrono::Duration::seconds(previous.duration);
+        let gap = self.start_time.signed_duration_since(previous_end).num_seconds();
+        max_gap_secs > 0
+            && gap.unsigned_abs() <= max_gap_secs
+            && self.app_name == previous.app_name
+            && self.window_name == previous.window_name
+            && self.hostname == previous.hostname
+            && !self.is_afk.unwrap_or(false)
+            && !previous.is_afk.unwrap_or(false)
+    }
+}
+
+/// Extract project name from directory path with improved heuristics
+pub fn extract_project_name(path: &str) -> Option<String> {
+    // Handle home directory specially
+    if let Ok(home) = std::env::var("HOME") {
+        if path == home || path == "~" {
+            return Some("Home".to_string());
+        }
+        if path.starts_with(&format!("{}/", home)) {
+            // Extract the first directory after home (e.g., ~/Documents -> Documents)
+            let after_home = &path[home.len() + 1..];
+            if let Some(slash_pos) = after_home.find('/') {
+                let first_dir = &after_home[..slash_pos];
+                if !first_dir.is_empty() {
+                    return Some(first_dir.to_string());
+                }
+            } else if !after_home.is_empty() {
+                return Some(after_home.to_string());
+            }
+        }
+    }
+
+    // Standard project extraction from path
+    let parts: Vec<&str> = path.split('/').collect();
+
+    // Get last non-empty component, skipping common non-project directories
+    let skip_dirs = ["bin", "usr", "etc", "var", "tmp", "dev", "proc", "sys", "home", "root"];
+
+    for part in parts.iter().rev() {
+        let part_lower = part.to_lowercase();
+        if !part.is_empty() && *part != "." && *part != ".." && !skip_dirs.contains(&part_lower.as_str()) {
+            // Additional heuristics: prefer directories that look like projects
+            if part.chars().next().is_some_and(|c| c.is_alphabetic()) && part.len() >= 2 {
+                return Some(part.to_string());
+            }
+        }
+    }
+
+    // Fallback: if we have any valid directory component
+    for part in parts.iter().rev() {
+        if !part.is_empty() && *part != "." && *part != ".." {
+            return Some(part.to_string());
+        }
+    }
+
+    None
+}
+
+/// This machine's hostname, stored with each new session
+pub fn current_hostname() -> Option<String> {
+    whoami::fallible::hostname().ok().filter(|hostname| !hostname.is_empty())
 }
 
+#[cfg(test)]
+impl Session {
+    /// Test fixture: a saved session of `app_name` with every optional field empty; set the fields
+    /// a test cares about with struct update syntax, `Session { is_afk: Some(true), ..Session::for_test(...) }`
+    pub fn for_test(app_name: &str, start_time: DateTime<Local>, duration: i64) -> Session {
+        Session {
+            id: None,
+            app_name: app_name.to_string(),
+            window_name: None,
+            start_time,
+            duration,
+            category: None,
+            browser_url: None,
+            browser_page_title: None,
+            browser_notification_count: None,
+            browser_page_title_renamed: None,
+            browser_page_title_category: None,
+            terminal_username: None,
+            terminal_hostname: None,
+            terminal_directory: None,
+            terminal_project_name: None,
+            terminal_directory_renamed: None,
+            terminal_directory_category: None,
+            editor_filename: None,
+            editor_filepath: None,
+            editor_project_path: None,
+            editor_language: None,
+            editor_filename_renamed: None,
+            editor_filename_category: None,
+            tmux_window_name: None,
+            tmux_pane_count: None,
+            terminal_multiplexer: None,
+            tmux_window_name_renamed: None,
+            tmux_window_name_category: None,
+            ide_project_name: None,
+            ide_file_open: None,
+            ide_workspace: None,
+            parsed_data: None,
+            parsing_success: None,
+            is_afk: None,
+            is_idle: None,
+            tag: None,
+            hostname: None,
+            note: None,
+            resumed_id: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> Option<String> {
+        Some(value.to_string())
+    }
+
+    #[test]
+    fn test_effective_display_name() {
+        let base = || Session::for_test("app", Local::now(), 60);
+        let name = |session: Session| session.effective_display_name();
 
+        // Browser page title, renamed or not, keyed by the original title
+        assert_eq!(
+            name(Session { window_name: text("Docs - Firefox"), browser_page_title: text("Docs"), browser_page_title_category: text("📚 Research"), ..base() }),
+            Some(("Docs".to_string(), "browser_page_title:Docs".to_string(), Some("📚 Research".to_string())))
+        );
+        assert_eq!(
+            name(Session { browser_page_title: text("Docs"), browser_page_title_renamed: text("API docs"), ..base() }),
+            Some(("API docs".to_string(), "browser_page_title:Docs".to_string(), None))
+        );
+
+        // Terminal directory shows the project name unless renamed
+        assert_eq!(
+            name(Session { terminal_directory: text("/srv/work/neura-hustle-tracker/src"), tmux_window_name: text("editor"), ..base() }),
+            Some(("src".to_string(), "terminal_directory:/srv/work/neura-hustle-tracker/src".to_string(), None))
+        );
+        assert_eq!(
+            name(Session { terminal_directory: text("/srv/work/api"), terminal_directory_renamed: text("Backend"), ..base() }).map(|(name, _, _)| name),
+            Some("Backend".to_string())
+        );
+
+        // Editor file, then tmux window, then the plain window title
+        assert_eq!(
+            name(Session { editor_filename: text("main.rs"), editor_filename_renamed: text("entry point"), tmux_window_name: text("build"), ..base() }),
+            Some(("entry point".to_string(), "editor_filename:main.rs".to_string(), None))
+        );
+        assert_eq!(
+            name(Session { tmux_window_name: text("build"), tmux_window_name_category: text("💻 Development"), window_name: text("tmux"), ..base() }),
+            Some(("build".to_string(), "tmux_window_name:build".to_string(), Some("💻 Development".to_string())))
+        );
+        assert_eq!(
+            name(Session { window_name: text("Spotify Premium"), ..base() }),
+            Some(("Spotify Premium".to_string(), "window_name:Spotify Premium".to_string(), None))
+        );
+
+        // Nothing below the app
+        assert_eq!(name(base()), None);
+    }
+
+    #[test]
+    fn test_resumes() {
+        let now = Local::now();
+        let previous = Session { window_name: text("main.rs"), ..Session::for_test("app", now - chrono::Duration::seconds(620), 600) };
+        let fresh = |window: &str| Session { window_name: text(window), ..Session::for_test("app", now, 60) };
+
+        // Ended 20s ago in the same window
+        assert!(fresh("main.rs").resumes(&previous, 30));
+        assert!(!fresh("main.rs").resumes(&previous, 10));
+        assert!(!fresh("main.rs").resumes(&previous, 0));
+        assert!(!fresh("lib.rs").resumes(&previous, 30));
+        assert!(!Session { app_name: "other".to_string(), ..fresh("main.rs") }.resumes(&previous, 30));
+        assert!(!Session { hostname: text("laptop"), ..fresh("main.rs") }.resumes(&previous, 30));
+        assert!(!Session { is_afk: Some(true), ..fresh("main.rs") }.resumes(&previous, 30));
+    }
+}
@@ -0,0 +1,189 @@
+//! `--doctor`: runs each piece of window and idle detection once and prints a pass/fail report,
+//! so users can see which part of their setup keeps tracking from working.
+
+use super::monitor::AppMonitor;
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    /// Optional, or could not be checked here; tracking still works
+    Warn,
+    Fail,
+}
+
+struct Report {
+    failures: usize,
+}
+
+impl Report {
+    fn check(&mut self, status: Status, name: &str, detail: impl AsRef<str>) {
+        let icon = match status {
+            Status::Pass => "✅",
+            Status::Warn => "⚠️ ",
+            Status::Fail => {
+                self.failures += 1;
+                "❌"
+            }
+        };
+        println!("{} {:<18} {}", icon, name, detail.as_ref());
+    }
+}
+
+/// Prints the report; returns false when a required check failed
+pub async fn run() -> bool {
+    let mut report = Report { failures: 0 };
+    println!("Neura Hustle Tracker doctor\n");
+
+    let monitor = AppMonitor::new();
+    check_session(&mut report, &monitor);
+    check_tools(&mut report, &monitor).await;
+
+    match monitor.get_active_window_info_async().await {
+        Ok((app, title)) => report.check(
+            Status::Pass,
+            "Active window",
+            format!("{} | {}", app, title.unwrap_or_else(|| "(no title)".to_string())),
+        ),
+        Err(e) => report.check(Status::Fail, "Active window", e.to_string()),
+    }
+
+    check_idle(&mut report, &monitor).await;
+    crate::daemon::tracker::kwin::unload_script().await;
+
+    println!();
+    if report.failures == 0 {
+        println!("All required checks passed.");
+    } else {
+        println!("{} required check(s) failed. Include this report when opening an issue.", report.failures);
+    }
+    report.failures == 0
+}
+
+fn check_session(report: &mut Report, monitor: &AppMonitor) {
+    report.check(Status::Pass, "Platform", env::consts::OS);
+    if !cfg!(target_os = "linux") {
+        return;
+    }
+
+    let session_type = env::var("XDG_SESSION_TYPE").unwrap_or_else(|_| "unset".to_string());
+    if monitor.uses_wayland() {
+        let desktop = if crate::daemon::tracker::kwin::is_kde_plasma() {
+            "KDE Plasma"
+        } else if super::wlroots_idle::is_wlroots() {
+            "wlroots"
+        } else {
+            "GNOME/other"
+        };
+        report.check(Status::Pass, "Session type", format!("Wayland ({}), XDG_SESSION_TYPE={}", desktop, session_type));
+    } else if env::var("DISPLAY").is_ok() {
+        report.check(Status::Pass, "Session type", format!("X11, XDG_SESSION_TYPE={}", session_type));
+    } else {
+        report.check(
+            Status::Fail,
+            "Session type",
+            "neither WAYLAND_DISPLAY nor DISPLAY is set; run it from inside your desktop session",
+        );
+    }
+}
+
+async fn check_tools(report: &mut Report, monitor: &AppMonitor) {
+    if cfg!(target_os = "macos") {
+        tool(report, "osascript", Status::Fail, "needed for the AppleScript fallback and window titles");
+        return;
+    }
+    if cfg!(target_os = "windows") {
+        tool(report, "powershell", Status::Warn, "used to read window titles when the native API returns none");
+        return;
+    }
+
+    if monitor.uses_wayland() {
+        if crate::daemon::tracker::kwin::is_kde_plasma() {
+            tool(report, "kdotool", Status::Warn, "fallback when KWin scripting is blocked");
+        } else if super::wlroots_idle::is_wlroots() {
+            tool(report, "swayidle", Status::Fail, "reads idle time for AFK detection");
+        } else {
+            check_gnome_extension(report).await;
+        }
+    } else {
+        tool(report, "xprop", Status::Warn, "optional, for checking a window's WM_CLASS by hand");
+        tool(report, "xdotool", Status::Warn, "optional, for checking the active window by hand");
+    }
+    tool(report, "tmux", Status::Warn, "optional, adds tmux window names to terminal sessions");
+}
+
+/// The 'Window Calls' extension (or 'Window Calls Extended') serves the window the Wayland path reads on GNOME
+async fn check_gnome_extension(report: &mut Report) {
+    let extensions = [
+        ("Window Calls", "/org/gnome/Shell/Extensions/Windows", "org.gnome.Shell.Extensions.Windows", "List"),
+        ("Window Calls Extended", "/org/gnome/Shell/Extensions/WindowsExt", "org.gnome.Shell.Extensions.WindowsExt", "FocusClass"),
+    ];
+    let mut errors = Vec::new();
+    for (name, path, interface, method) in extensions {
+        let result = async {
+            let connection = zbus::Connection::session().await?;
+            connection.call_method(Some("org.gnome.Shell"), path, Some(interface), method, &()).await?;
+            anyhow::Ok(())
+        }
+        .await;
+
+        match result {
+            Ok(()) => return report.check(Status::Pass, "Window Calls", format!("GNOME extension '{}' is enabled", name)),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+    report.check(
+        Status::Fail,
+        "Window Calls",
+        format!(
+            "{} - install/enable https://extensions.gnome.org/extension/4724/window-calls/ \
+            or https://extensions.gnome.org/extension/4974/window-calls-extended/",
+            errors.join("; ")
+        ),
+    );
+}
+
+async fn check_idle(report: &mut Report, monitor: &AppMonitor) {
+    if !monitor.uses_wayland() {
+        report.check(Status::Warn, "Idle detection", "uses keyboard/mouse events, which are only seen while the app runs");
+        return;
+    }
+    match crate::ui::app::App::check_wayland_idle_time().await {
+        Ok(idle_secs) => report.check(Status::Pass, "Idle detection", format!("D-Bus reports {}s idle", idle_secs)),
+        Err(e) => report.check(Status::Fail, "Idle detection", format!("{} - AFK detection will not work", e)),
+    }
+}
+
+fn tool(report: &mut Report, name: &str, missing: Status, purpose: &str) {
+    match find_on_path(name, env::var_os("PATH").as_deref()) {
+        Some(path) => report.check(Status::Pass, name, path.display().to_string()),
+        None => report.check(missing, name, format!("not found ({})", purpose)),
+    }
+}
+
+fn find_on_path(name: &str, path_var: Option<&std::ffi::OsStr>) -> Option<PathBuf> {
+    let file_names = [name.to_string(), format!("{}.exe", name)];
+    env::split_paths(path_var?)
+        .flat_map(|dir| file_names.iter().map(move |file| dir.join(file)))
+        .find(|candidate| Path::is_file(candidate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_on_path() {
+        let dir = env::temp_dir().join(format!("hustle-doctor-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("fake-tool"), "").unwrap();
+        let path_var = env::join_paths([Path::new("/nonexistent"), &dir]).unwrap();
+
+        assert_eq!(find_on_path("fake-tool", Some(&path_var)), Some(dir.join("fake-tool")));
+        assert_eq!(find_on_path("missing-tool", Some(&path_var)), None);
+        assert_eq!(find_on_path("fake-tool", None), None);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
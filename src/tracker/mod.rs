@@ -1,2 +1,4 @@
 pub mod monitor;
 pub mod process_inspection;
+pub mod doctor;
+pub mod wlroots_idle;
@@ -1,6 +1,7 @@
-use active_win_pos_rs::get_active_window;
+use active_win_pos_rs::{get_active_window, ActiveWindow};
 use anyhow::Result;
 use std::env;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(target_os = "linux")]
 use super::process_inspection;
 
@@ -12,10 +13,14 @@ struct WindowInfo {
     title: String,
     #[serde(default)]
     focus: bool,
+    #[serde(default)]
+    fullscreen: bool,
 }
 
 pub struct AppMonitor {
     use_wayland: bool,
+    /// Whether the window from the last lookup was fullscreen
+    fullscreen: AtomicBool,
 }
 
 impl Default for AppMonitor {
@@ -54,7 +59,7 @@ impl AppMonitor {
         #[cfg(target_os = "windows")]
         log::info!("Using Win32 APIs for window tracking");
 
-        Self { use_wayland }
+        Self { use_wayland, fullscreen: AtomicBool::new(false) }
     }
 
     pub fn uses_wayland(&self) -> bool {
@@ -74,9 +79,34 @@ impl AppMonitor {
         }
     }
 
-    async fn get_active_window_wayland() -> Result<(String, String)> {
+    /// Whether the last detected window was fullscreen. Only the GNOME 'Window Calls' extension and
+    /// KWin report it, so it is always false on X11, macOS and Windows
+    pub fn is_fullscreen(&self) -> bool {
+        self.fullscreen.load(Ordering::Relaxed)
+    }
+
+    async fn get_active_window_wayland() -> Result<(String, String, bool)> {
+        // KDE Plasma has no GNOME Shell; ask KWin instead
+        if crate::daemon::tracker::kwin::is_kde_plasma() {
+            return crate::daemon::tracker::kwin::get_active_window().await;
+        }
+
         let connection = zbus::Connection::session().await?;
 
+        // 'Window Calls Extended' is a separate extension with its own interface; either one works
+        match Self::get_active_window_window_calls(&connection).await {
+            Ok(window) => Ok(window),
+            Err(window_calls_err) => {
+                log::debug!("Window Calls failed ({}), trying Window Calls Extended", window_calls_err);
+                Self::get_active_window_window_calls_extended(&connection).await.map_err(|extended_err| {
+                    anyhow::anyhow!("Window Calls: {}; Window Calls Extended: {}", window_calls_err, extended_err)
+                })
+            }
+        }
+    }
+
+    /// The focused window from the 'Window Calls' extension's JSON window list
+    async fn get_active_window_window_calls(connection: &zbus::Connection) -> Result<(String, String, bool)> {
         let response = connection.call_method(
             Some("org.gnome.Shell"),
             "/org/gnome/Shell/Extensions/Windows",
@@ -94,64 +124,109 @@ impl AppMonitor {
             .find(|w| w.focus)
             .ok_or(anyhow::anyhow!("No focused window found"))?;
 
-        Ok((focused_window.wm_class.clone(), focused_window.title.clone()))
+        Ok((focused_window.wm_class.clone(), focused_window.title.clone(), focused_window.fullscreen))
     }
 
-    // Get both app and window info in a single call (more efficient for macOS AppleScript)
-    pub async fn get_active_app_async(&self) -> Result<String> {
+    /// The focused window from 'Window Calls Extended', which answers one call per property.
+    /// It doesn't report fullscreen state, so that is always false
+    async fn get_active_window_window_calls_extended(connection: &zbus::Connection) -> Result<(String, String, bool)> {
+        let mut focused = Vec::new();
+        for method in ["FocusClass", "FocusTitle"] {
+            let response = connection.call_method(
+                Some("org.gnome.Shell"),
+                "/org/gnome/Shell/Extensions/WindowsExt",
+                Some("org.gnome.Shell.Extensions.WindowsExt"),
+                method,
+                &(),
+            ).await?;
+            focused.push(response.body().deserialize::<String>()?);
+        }
+        let title = focused.pop().unwrap_or_default();
+        let wm_class = focused.pop().unwrap_or_default();
+        if wm_class.is_empty() {
+            return Err(anyhow::anyhow!("No focused window found"));
+        }
+        Ok((wm_class, title, false))
+    }
+
+    fn wayland_detection_error(e: anyhow::Error) -> anyhow::Error {
+        let error_msg = format!(
+            "Wayland window detection failed: {}. \
+            On GNOME, make sure the 'Window Calls' or 'Window Calls Extended' GNOME extension is installed and enabled. \
+            Install from: https://extensions.gnome.org/extension/4724/window-calls/ \
+            or https://extensions.gnome.org/extension/4974/window-calls-extended/ \
+            On KDE Plasma, KWin scripting is used (Plasma 5.27+); if it is unavailable, install kdotool: https://github.com/jinliu/kdotool",
+            e
+        );
+        log::warn!("{}", error_msg);
+        anyhow::anyhow!(error_msg)
+    }
+
+    // Extract directory from prompt if it looks like a shell prompt
+    fn extract_prompt_directory(title: String) -> String {
+        if title.contains("@") && title.contains(": ") {
+            if let Some(dir) = title.split(": ").last() {
+                return dir.to_string();
+            }
+        }
+        title
+    }
+
+    // Get both app and window info in a single call, so both describe the same window at the same instant
+    pub async fn get_active_window_info_async(&self) -> Result<(String, Option<String>)> {
+        let (app_name, window_title, _pid) = self.get_active_window_info_with_pid_async().await?;
+        Ok((app_name, window_title))
+    }
+
+    // Same as get_active_window_info_async, plus the owning process ID (when known) for attribution debugging
+    pub async fn get_active_window_info_with_pid_async(&self) -> Result<(String, Option<String>, Option<u64>)> {
         if self.use_wayland {
             // Use Wayland D-Bus method
-            match Self::get_active_window_wayland().await {
-                Ok((wm_class, _title)) => {
-                    log::info!("Detected active app (Wayland): {}", wm_class);
-                    Ok(self.fix_app_name(wm_class))
-                }
-                Err(e) => {
-                    let error_msg = format!(
-                        "Wayland window detection failed: {}. \
-                        Make sure the 'Window Calls' GNOME extension is installed and enabled. \
-                        Install from: https://extensions.gnome.org/extension/4724/window-calls/",
-                        e
-                    );
-                    log::warn!("{}", error_msg);
-                    Err(anyhow::anyhow!(error_msg))
+            let (wm_class, title, fullscreen) = Self::get_active_window_wayland().await.map_err(Self::wayland_detection_error)?;
+            log::info!("Detected active app (Wayland): {}", wm_class);
+            self.fullscreen.store(fullscreen, Ordering::Relaxed);
+            return Ok((self.fix_app_name(wm_class), Some(Self::extract_prompt_directory(title)), None));
+        }
+
+        // Use platform-specific native APIs
+        match get_active_window() {
+            Ok(active_window) => {
+                let app_name = self.normalize_active_app(&active_window);
+                let window_title = self.enhance_window_title(&active_window).await;
+                log::debug!("Active window pid {} -> app '{}', title '{}'", active_window.process_id, app_name, window_title);
+                Ok((app_name, Some(window_title), Some(active_window.process_id)))
+            }
+            Err(e) => {
+                log::error!("Failed to get active window: {:?}", e);
+
+                // On macOS, try AppleScript as fallback (returns app and title together)
+                #[cfg(target_os = "macos")]
+                {
+                    log::info!("active-win-pos-rs failed, trying AppleScript fallback...");
+                    if let Ok((app_name, title)) = Self::get_active_window_info_macos().await {
+                        log::info!("AppleScript successfully detected app: '{}'", app_name);
+                        return Ok((self.fix_app_name(app_name), Some(title), None));
+                    }
                 }
+
+                let error_msg = "Failed to get active window";
+                log::warn!("{}", error_msg);
+                Err(anyhow::anyhow!(error_msg))
             }
+        }
+    }
+
+    pub async fn get_active_app_async(&self) -> Result<String> {
+        if self.use_wayland {
+            // Use Wayland D-Bus method
+            let (wm_class, _title, fullscreen) = Self::get_active_window_wayland().await.map_err(Self::wayland_detection_error)?;
+            log::info!("Detected active app (Wayland): {}", wm_class);
+            self.fullscreen.store(fullscreen, Ordering::Relaxed);
+            Ok(self.fix_app_name(wm_class))
         } else {
             // Use platform-specific native APIs
             match get_active_window() {
-                Ok(active_window) => {
-                    // Platform-specific debug logging
-                    #[cfg(target_os = "macos")]
-                    log::debug!("[macOS] Raw window - app: '{}', title: '{}', path: {:?}, position: {:?}",
-                               active_window.app_name,
-                               active_window.title,
-                               active_window.process_path,
-                               active_window.position);
-
-                    #[cfg(target_os = "windows")]
-                    log::debug!("[Windows] Raw window - app: '{}', title: '{}', path: {:?}, position: {:?}",
-                               active_window.app_name,
-                               active_window.title,
-                               active_window.process_path,
-                               active_window.position);
-
-                    #[cfg(target_os = "linux")]
-                    log::debug!("[Linux/X11] Raw window - app: '{}', title: '{}'",
-                               active_window.app_name,
-                               active_window.title);
-
-                    let original_name = active_window.app_name.clone();
-                    let fixed_name = self.fix_app_name(original_name.clone());
-
-                    if original_name != fixed_name {
-                        log::info!("App detected: '{}' (normalized from '{}')", fixed_name, original_name);
-                    } else {
-                        log::info!("App detected: '{}'", fixed_name);
-                    }
-
-                    Ok(fixed_name)
-                }
+                Ok(active_window) => Ok(self.normalize_active_app(&active_window)),
                 Err(e) => {
                     log::error!("Failed to get active window: {:?}", e);
 
@@ -182,15 +257,10 @@ impl AppMonitor {
         if self.use_wayland {
             // Use Wayland D-Bus method
             match Self::get_active_window_wayland().await {
-                Ok((_wm_class, mut title)) => {
-                    // Extract directory from prompt if it looks like a shell prompt
-                    if title.contains("@") && title.contains(": ") {
-                        if let Some(dir) = title.split(": ").last() {
-                            title = dir.to_string();
-                        }
-                    }
-                    Ok(title)
-                },
+                Ok((_wm_class, title, fullscreen)) => {
+                    self.fullscreen.store(fullscreen, Ordering::Relaxed);
+                    Ok(Self::extract_prompt_directory(title))
+                }
                 Err(_) => {
                     log::warn!("Failed to get active window title (Wayland).");
                     Ok("Unknown Window".to_string())
@@ -199,71 +269,106 @@ impl AppMonitor {
         } else {
             // Use platform-specific native APIs
             match get_active_window() {
-                Ok(active_window) => {
-                    let mut title = active_window.title.clone();
+                Ok(active_window) => Ok(self.enhance_window_title(&active_window).await),
+                Err(_) => {
+                    log::warn!("Failed to get active window title.");
+                    Ok("Unknown Window".to_string())
+                }
+            }
+        }
+    }
 
-                    // On macOS, if we get a generic title (app name only), try AppleScript fallback
-                    #[cfg(target_os = "macos")]
-                    {
-                        let app_name = active_window.app_name.clone();
-                        if title == app_name || title.is_empty() || title == "Unknown" {
-                            log::debug!("Generic title detected for '{}', trying AppleScript fallback", app_name);
-                            if let Ok(detailed_title) = Self::get_window_title_macos(&app_name).await {
-                                if !detailed_title.is_empty() && detailed_title != app_name {
-                                    log::info!("AppleScript retrieved title for {}: '{}'", app_name, detailed_title);
-                                    return Ok(detailed_title);
-                                }
-                            }
-                        }
-                    }
+    fn normalize_active_app(&self, active_window: &ActiveWindow) -> String {
+        // Platform-specific debug logging
+        #[cfg(target_os = "macos")]
+        log::debug!("[macOS] Raw window - app: '{}', title: '{}', path: {:?}, position: {:?}",
+                   active_window.app_name,
+                   active_window.title,
+                   active_window.process_path,
+                   active_window.position);
 
-                    // On Windows, if we get a generic title, try PowerShell fallback
-                    #[cfg(target_os = "windows")]
-                    {
-                        if title == app_name || title.is_empty() || title == "Unknown" {
-                            log::debug!("Generic title detected for '{}', trying PowerShell fallback", app_name);
-                            if let Ok(detailed_title) = Self::get_window_title_windows(&app_name).await {
-                                if !detailed_title.is_empty() && detailed_title != app_name {
-                                    log::info!("PowerShell retrieved title for {}: '{}'", app_name, detailed_title);
-                                    return Ok(detailed_title);
-                                }
-                            }
-                        }
-                    }
+        #[cfg(target_os = "windows")]
+        log::debug!("[Windows] Raw window - app: '{}', title: '{}', path: {:?}, position: {:?}",
+                   active_window.app_name,
+                   active_window.title,
+                   active_window.process_path,
+                   active_window.position);
 
-                    // On Linux, enhance title with process inspection
-                    #[cfg(target_os = "linux")]
-                    {
-                        // First, extract directory from prompt if it looks like a shell prompt
-                        if title.contains("@") && title.contains(": ") {
-                            if let Some(dir) = title.split(": ").last() {
-                                title = dir.to_string();
-                            }
-                        }
-                        let pid = active_window.process_id;
-                        if pid != 0 {
-                             if let Some(info) = process_inspection::inspect_process_tree(pid) {
-                                 if let Some(window) = info.tmux_window {
-                                     title = format!("{} - {}", window, title);
-                                 } else if info.has_tmux {
-                                     let session = info.tmux_session.unwrap_or("session".to_string());
-                                     title = format!("tmux: {} - {}", session, title);
-                                 }
-                                 if let Some(editor) = info.editor_info {
-                                     title = format!("{} ({}) - {}", editor.filename, editor.filepath, title);
-                                 }
-                             }
-                        }
-                    }
+        #[cfg(target_os = "linux")]
+        log::debug!("[Linux/X11] Raw window - app: '{}', title: '{}'",
+                   active_window.app_name,
+                   active_window.title);
 
-                    Ok(title)
+        let original_name = active_window.app_name.clone();
+        let fixed_name = self.fix_app_name(original_name.clone());
+
+        if original_name != fixed_name {
+            log::info!("App detected: '{}' (normalized from '{}')", fixed_name, original_name);
+        } else {
+            log::info!("App detected: '{}'", fixed_name);
+        }
+
+        fixed_name
+    }
+
+    async fn enhance_window_title(&self, active_window: &ActiveWindow) -> String {
+        let title = active_window.title.clone();
+
+        // On macOS/Windows, a generic title (app name only) means the native API could not read it
+        #[cfg(any(target_os = "macos", target_os = "windows"))]
+        let app_name = active_window.app_name.clone();
+
+        // On macOS, if we get a generic title (app name only), try AppleScript fallback
+        #[cfg(target_os = "macos")]
+        {
+            if title == app_name || title.is_empty() || title == "Unknown" {
+                log::debug!("Generic title detected for '{}', trying AppleScript fallback", app_name);
+                if let Ok(detailed_title) = Self::get_window_title_macos(&app_name).await {
+                    if !detailed_title.is_empty() && detailed_title != app_name {
+                        log::info!("AppleScript retrieved title for {}: '{}'", app_name, detailed_title);
+                        return detailed_title;
+                    }
                 }
-                Err(_) => {
-                    log::warn!("Failed to get active window title.");
-                    Ok("Unknown Window".to_string())
+            }
+        }
+
+        // On Windows, if we get a generic title, try PowerShell fallback
+        #[cfg(target_os = "windows")]
+        {
+            if title == app_name || title.is_empty() || title == "Unknown" {
+                log::debug!("Generic title detected for '{}', trying PowerShell fallback", app_name);
+                if let Ok(detailed_title) = Self::get_window_title_windows(&app_name).await {
+                    if !detailed_title.is_empty() && detailed_title != app_name {
+                        log::info!("PowerShell retrieved title for {}: '{}'", app_name, detailed_title);
+                        return detailed_title;
+                    }
                 }
             }
         }
+
+        // On Linux, enhance title with process inspection
+        #[cfg(target_os = "linux")]
+        let title = {
+            // First, extract directory from prompt if it looks like a shell prompt
+            let mut title = Self::extract_prompt_directory(title);
+            let pid = active_window.process_id;
+            if pid != 0 {
+                 if let Some(info) = process_inspection::inspect_process_tree(pid) {
+                     if let Some(window) = info.tmux_window {
+                         title = format!("{} - {}", window, title);
+                     } else if info.has_tmux {
+                         let session = info.tmux_session.unwrap_or("session".to_string());
+                         title = format!("tmux: {} - {}", session, title);
+                     }
+                     if let Some(editor) = info.editor_info {
+                         title = format!("{} ({}) - {}", editor.filename, editor.filepath, title);
+                     }
+                 }
+            }
+            title
+        };
+
+        title
     }
 
     #[cfg(target_os = "macos")]
@@ -326,6 +431,20 @@ impl AppMonitor {
                         return appName & "|" & windowTitle
                     end try
                 end tell
+            else if appName is "Arc" then
+                tell application "Arc"
+                    try
+                        set windowTitle to title of active tab of front window
+                        return appName & "|" & windowTitle
+                    end try
+                end tell
+            else if appName contains "Safari Technology Preview" then
+                tell application "Safari Technology Preview"
+                    try
+                        set windowTitle to name of front document
+                        return appName & "|" & windowTitle
+                    end try
+                end tell
             else if appName contains "Safari" then
                 tell application "Safari"
                     try
@@ -499,7 +618,13 @@ impl AppMonitor {
         #[cfg(target_os = "linux")]
         let normalized = {
             if app_lower.contains('.') {
-                app_lower.split('.').last().unwrap_or(&app_lower).to_string()
+                // Flatpak IDs often end in a generic word ("com.spotify.Client", "org.telegram.desktop")
+                app_lower
+                    .split('.')
+                    .rev()
+                    .find(|part| !crate::daemon::tracker::monitor::GENERIC_ID_SEGMENTS.contains(part))
+                    .unwrap_or(&app_lower)
+                    .to_string()
             } else if app_lower.contains('_') {
                 app_lower.split('_').next().unwrap_or(&app_lower).to_string()
             } else {
@@ -516,6 +641,11 @@ impl AppMonitor {
             return "chrome".to_string();
         } else if normalized.contains("firefox") {
             return "firefox".to_string();
+        } else if normalized.contains("safari") {
+            // Covers Safari Technology Preview so both builds aggregate together
+            return "safari".to_string();
+        } else if normalized == "arc" {
+            return "arc".to_string();
         } else if normalized.contains("code") || normalized.contains("vscode") || normalized.contains("vscodium") {
             return "vscode".to_string();
         } else if normalized.contains("slack") {
@@ -587,4 +717,13 @@ mod tests {
         let window_name = monitor.get_active_window_name_async().await.unwrap_or_else(|_| "test".to_string());
         assert!(!window_name.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_get_active_window_info_async() {
+        let monitor = AppMonitor::new();
+        // Note: This test may fail if no active window is available
+        if let Ok((app, _window_title)) = monitor.get_active_window_info_async().await {
+            assert!(!app.is_empty());
+        }
+    }
 }
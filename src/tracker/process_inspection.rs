@@ -35,12 +35,8 @@ pub fn inspect_process_tree(pid: u64) -> Option<ProcessInfo> {
             if cmd.contains("tmux") {
                 info.has_tmux = true;
                 // Try to get session name from cmdline
-                for arg in cmdline.split('\0').skip(1) {
-                    if arg.starts_with("-t") || arg.starts_with("-s") {
-                        if let Some(session) = arg.split('=').nth(1).or_else(|| arg.split(' ').nth(1)) {
-                            info.tmux_session = Some(session.to_string());
-                        }
-                    }
+                if let Some(session) = tmux_session_from_cmdline(&cmdline) {
+                    info.tmux_session = Some(session);
                 }
             }
 
@@ -69,14 +65,15 @@ pub fn inspect_process_tree(pid: u64) -> Option<ProcessInfo> {
     }
 
     // If tmux detected, try to get the current window name
-    if info.has_tmux {
-        let session_arg = if let Some(ref session) = info.tmux_session {
-            format!("-t {}", session)
-        } else {
-            "".to_string()
-        };
-        let cmd = format!("tmux list-windows {} -F \"#{{window_name}}:#{{window_active}}\"", session_arg);
-        if let Ok(output) = std::process::Command::new("sh").arg("-c").arg(&cmd).output() {
+    if info.has_tmux && tmux_installed() {
+        // Pass args directly so the session name is never interpreted by a shell
+        let mut command = std::process::Command::new("tmux");
+        command.arg("list-windows");
+        if let Some(ref session) = info.tmux_session {
+            command.arg("-t").arg(session);
+        }
+        command.arg("-F").arg("#{window_name}:#{window_active}");
+        if let Ok(output) = command.output() {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
                 for line in stdout.lines() {
@@ -96,6 +93,31 @@ pub fn inspect_process_tree(pid: u64) -> Option<ProcessInfo> {
     Some(info)
 }
 
+/// Whether a tmux binary is on PATH, looked up once so terminals without tmux never spawn it
+#[cfg(target_os = "linux")]
+fn tmux_installed() -> bool {
+    static TMUX_INSTALLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *TMUX_INSTALLED.get_or_init(|| {
+        std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join("tmux").is_file()))
+    })
+}
+
+/// Extract the tmux session name from a NUL-separated /proc cmdline.
+/// tmux receives `-t`/`-s` and the name as separate argv entries, but `-tname` is accepted too.
+#[cfg(target_os = "linux")]
+fn tmux_session_from_cmdline(cmdline: &str) -> Option<String> {
+    let mut args = cmdline.split('\0').skip(1).filter(|arg| !arg.is_empty());
+    while let Some(arg) = args.next() {
+        if arg == "-t" || arg == "-s" {
+            return args.next().map(|session| session.to_string());
+        }
+        if let Some(attached) = arg.strip_prefix("-t").or_else(|| arg.strip_prefix("-s")) {
+            return Some(attached.trim_start_matches('=').to_string());
+        }
+    }
+    None
+}
+
 #[cfg(target_os = "linux")]
 fn get_child_processes(pid: u64) -> Vec<u64> {
     let mut children = Vec::new();
@@ -0,0 +1,122 @@
+//! Idle time on wlroots compositors (Sway, Hyprland, river...), which offer none of the GNOME/KDE
+//! D-Bus idle interfaces. A long-running `swayidle` watches the ext-idle-notify-v1 (or older
+//! org_kde_kwin_idle) protocol and reports idle/resume on stdout; logind's IdleHint is the fallback.
+
+use anyhow::Result;
+use std::env;
+use std::process::Stdio;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// swayidle's smallest timeout; idle time is counted from when it fires, plus this
+const IDLE_TIMEOUT_SECS: u64 = 1;
+
+#[derive(Default)]
+struct WatcherState {
+    started: bool,
+    exited: bool,
+    idle_since: Option<Instant>,
+}
+
+fn watcher() -> &'static Mutex<WatcherState> {
+    static WATCHER: OnceLock<Mutex<WatcherState>> = OnceLock::new();
+    WATCHER.get_or_init(|| Mutex::new(WatcherState::default()))
+}
+
+/// Sway and Hyprland set their own socket variables; other wlroots compositors show up in XDG_CURRENT_DESKTOP
+pub fn is_wlroots() -> bool {
+    env::var("SWAYSOCK").is_ok()
+        || env::var("HYPRLAND_INSTANCE_SIGNATURE").is_ok()
+        || env::var("XDG_CURRENT_DESKTOP")
+            .map(|desktop| {
+                desktop.split(':').any(|part| {
+                    ["sway", "hyprland", "river", "wayfire", "labwc"].iter().any(|name| part.eq_ignore_ascii_case(name))
+                })
+            })
+            .unwrap_or(false)
+}
+
+/// Seconds without input, from swayidle, falling back to logind's IdleHint
+pub async fn idle_seconds() -> Result<u32> {
+    match swayidle_idle_seconds() {
+        Ok(idle) => Ok(idle),
+        Err(swayidle_err) => {
+            log::debug!("swayidle idle watcher failed ({}), trying logind IdleHint", swayidle_err);
+            logind_idle_seconds().await.map_err(|logind_err| {
+                anyhow::anyhow!("swayidle failed: {}; logind IdleHint failed: {}", swayidle_err, logind_err)
+            })
+        }
+    }
+}
+
+/// Idle time from the swayidle watcher, starting it on first use. Reads 0 until swayidle has
+/// reported anything; once it exits (no idle protocol in the compositor) this keeps failing
+fn swayidle_idle_seconds() -> Result<u32> {
+    let mut state = watcher().lock().unwrap();
+    if state.exited {
+        return Err(anyhow::anyhow!("swayidle isn't running"));
+    }
+    if !state.started {
+        state.started = true;
+        if let Err(e) = start_swayidle() {
+            state.exited = true;
+            return Err(e);
+        }
+    }
+    Ok(state.idle_since.map(|since| since.elapsed().as_secs() as u32).unwrap_or(0))
+}
+
+fn start_swayidle() -> Result<()> {
+    let timeout = IDLE_TIMEOUT_SECS.to_string();
+    let mut child = tokio::process::Command::new("swayidle")
+        .args(["-w", "timeout", timeout.as_str(), "echo idle", "resume", "echo active"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("couldn't start swayidle ({}); install it for AFK detection", e))?;
+    let stdout = child.stdout.take().ok_or_else(|| anyhow::anyhow!("swayidle has no stdout"))?;
+
+    // The task owns the child, so swayidle is killed when the runtime shuts down
+    tokio::spawn(async move {
+        let _child = child;
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut state = watcher().lock().unwrap();
+            match line.trim() {
+                "idle" => state.idle_since = Some(Instant::now() - Duration::from_secs(IDLE_TIMEOUT_SECS)),
+                "active" => state.idle_since = None,
+                other => log::debug!("Unexpected swayidle output: {}", other),
+            }
+        }
+        log::warn!("swayidle exited; falling back to logind IdleHint");
+        let mut state = watcher().lock().unwrap();
+        state.exited = true;
+        state.idle_since = None;
+    });
+    Ok(())
+}
+
+/// logind only knows whether the session is idle, and since when; it's set by swayidle's
+/// `idlehint` or the compositor itself
+async fn logind_idle_seconds() -> Result<u32> {
+    let connection = zbus::Connection::system().await?;
+    if !bool::try_from(session_property(&connection, "IdleHint").await?)? {
+        return Ok(0);
+    }
+    let idle_since_usec = u64::try_from(session_property(&connection, "IdleSinceHint").await?)?;
+    let now_usec = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros() as u64;
+    Ok((now_usec.saturating_sub(idle_since_usec) / 1_000_000) as u32)
+}
+
+async fn session_property(connection: &zbus::Connection, property: &str) -> Result<zbus::zvariant::OwnedValue> {
+    let response = connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1/session/auto",
+        Some("org.freedesktop.DBus.Properties"),
+        "Get",
+        &("org.freedesktop.login1.Session", property),
+    ).await?;
+    Ok(response.body().deserialize()?)
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, EnableMouseCapture, DisableMouseCapture};
+use crossterm::event::{self, Event, KeyCode, EnableMouseCapture, DisableMouseCapture, MouseButton, MouseEvent, MouseEventKind};
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::execute;
 
@@ -7,19 +7,22 @@ use ratatui::backend::CrosstermBackend;
 use ratatui::style::Color;
 use ratatui::{Frame, Terminal};
 use std::collections::BTreeMap;
-use std::io;
+use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Local};
-use rdev::{listen, EventType};
+use rdev::listen;
 use std::sync::{Arc, Mutex};
 
-use crate::database::connection::Database;
+use crate::config::settings::{AppConfig, Theme, WeekStart};
+use crate::daemon::afk::AfkHysteresis;
+use crate::database::connection::{Database, AFK_PSEUDO_APP, IDLE_PSEUDO_APP};
 use crate::models::session::Session;
 use crate::tracker::monitor::AppMonitor;
 use crate::ui::{commands::{self, CommandContext}, tracking};
 use crate::ui::hierarchical::HierarchicalDisplayItem;
+use crate::ui::ui_state::UiState;
 
 // Re-export ViewMode for other ui modules
 pub use crate::ui::tracking::ViewMode;
@@ -28,51 +31,196 @@ pub use crate::ui::tracking::ViewMode;
 pub enum InputAction {
     RenameApp { old_name: String },
     CreateCategory { app_name: String },
+    RenameCategory { old_name: String },
+    SplitSession { session_id: i32, start_time: chrono::DateTime<Local> },
+    // No session_id tags the running session; sticky also tags the sessions that follow
+    TagSession { session_id: Option<i32>, sticky: bool },
+    // `history` is the popup to go back to, scroll position included
+    NoteSession { session_id: i32, history: Box<AppState> },
+    JumpToDate { view_mode: ViewMode, hide_afk: bool, merge_same_app: bool },
+    MarkAway,
+    Search { view_mode: ViewMode },
+}
+
+/// Editable entries shown on the settings screen
+#[derive(Debug, Clone)]
+pub enum SettingsField {
+    AfkThreshold { secs: u64 },
+    RefreshInterval { secs: u64 },
+    DebugLogs { enabled: bool },
+    Theme { theme: Theme },
+}
+
+impl SettingsField {
+    pub fn from_config(config: &AppConfig) -> Vec<SettingsField> {
+        vec![
+            SettingsField::AfkThreshold { secs: config.afk_threshold_secs },
+            SettingsField::RefreshInterval { secs: config.refresh_interval_secs },
+            SettingsField::DebugLogs { enabled: config.debug_logs_enabled },
+            SettingsField::Theme { theme: config.theme },
+        ]
+    }
+
+    /// Step the value up or down, keeping it within sane bounds
+    pub fn adjust(&mut self, increase: bool) {
+        match self {
+            SettingsField::AfkThreshold { secs } => {
+                *secs = if increase { (*secs + 60).min(3600) } else { secs.saturating_sub(60).max(60) };
+            }
+            SettingsField::RefreshInterval { secs } => {
+                *secs = if increase { (*secs + 1).min(60) } else { secs.saturating_sub(1).max(1) };
+            }
+            SettingsField::DebugLogs { enabled } => *enabled = !*enabled,
+            SettingsField::Theme { theme } => *theme = crate::ui::theme::cycle(*theme, increase),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum AppState {
     Dashboard { view_mode: ViewMode },
     ViewingLogs,
-    SelectingApp { selected_index: usize, selected_unique_id: String },
-    SelectingCategory { selected_index: usize, selected_unique_id: String },
+    // `items` is a snapshot of the usage list taken on entry, so refreshes can't shift the selection
+    SelectingApp { selected_index: usize, selected_unique_id: String, items: Vec<HierarchicalDisplayItem> },
+    SelectingCategory { selected_index: usize, selected_unique_id: String, items: Vec<HierarchicalDisplayItem> },
     CategoryMenu { unique_id: String, selected_index: usize },
+    // `categories` is a snapshot so a refresh can't shift the selection
+    SelectingCategoryToRename { selected_index: usize, categories: Vec<String> },
     Input { prompt: String, buffer: String, action: InputAction },
     CommandsPopup,
-    HistoryPopup { view_mode: ViewMode, scroll_position: usize },
+    // `date` is a single day picked with [g] or [ / ]; None shows the view mode's range
+    HistoryPopup { view_mode: ViewMode, scroll_position: usize, hide_afk: bool, merge_same_app: bool, date: Option<chrono::NaiveDate> },
     BreakdownDashboard { view_mode: ViewMode, selected_panel: usize, panel_scrolls: [usize; 5] },
+    // Matches of a [/] search over the view mode's range, newest first, with their total seconds
+    SearchResults { view_mode: ViewMode, term: String, total_secs: i64, sessions: Vec<Session>, scroll_position: usize },
+    CodingStats,
+    WeekdayAverages,
+    WeeklyTotals,
+    CategoryDays,
+    ConfirmQuit,
+    Settings { fields: Vec<SettingsField>, selected: usize },
 }
 
+/// Days of history behind the context-switches-per-hour chart
+pub const SWITCHES_DAYS: i64 = 7;
+/// Days shown in the per-day category mix view
+pub const CATEGORY_DAYS: i64 = 7;
+/// Weeks of history averaged in the weekday view
+pub const WEEKDAY_WEEKS: i64 = 4;
+/// ISO weeks shown in the long-term weekly totals view
+pub const TREND_WEEKS: i64 = 12;
+/// Seconds a status bar confirmation stays up
+const FLASH_SECS: u64 = 3;
+/// Hours of unread counts in the browser services sparkline
+pub const NOTIFICATION_TREND_HOURS: i64 = 24;
+/// Sessions fetched per page in the history popup
+pub const HISTORY_PAGE_SIZE: i64 = 100;
+/// Catch-all category for apps no rule recognizes
+const OTHER_CATEGORY: &str = "📦 Other";
+/// Last entry of the category menu, which asks for a new category name
+const CREATE_CATEGORY_OPTION: &str = "➕ Create New Category";
+/// Category of AFK sessions, drawn in gray
+pub const AWAY_CATEGORY: &str = "💤 Away";
+/// Fetch the next history page once the scroll position gets this close to the end of what's loaded
+const HISTORY_PREFETCH_ROWS: usize = 30;
+/// Desktop-environment prefixes dropped from app names ("gnome-calculator" -> "calculator")
+const DESKTOP_PREFIXES: &[&str] = &["gnome-", "xfce4-", "mate-"];
+/// First segment of reverse-DNS app IDs (Flatpak, Wayland app_id) such as "org.mozilla.firefox"
+const REVERSE_DNS_ROOTS: &[&str] = &["com", "org", "io", "net", "app", "dev", "md", "us", "de", "eu", "me"];
+/// Flatpak IDs whose segments don't give the name people know the app by
+const FLATPAK_APP_NAMES: &[(&str, &str)] = &[
+    ("org.gnome.nautilus", "files"),
+    ("org.gnome.texteditor", "text-editor"),
+    ("com.visualstudio.code", "vscode"),
+    ("com.obsproject.studio", "obs"),
+    ("io.github.shiftey.desktop", "github-desktop"),
+];
+
 pub struct App {
     pub state: AppState,
     database: Database,
     monitor: AppMonitor,
     history: Vec<Session>,
     pub current_history: Vec<Session>,
-    pub usage: Vec<(String, i64)>,
+    pub history_page: Vec<Session>, // Sessions loaded so far in the history popup, newest first
+    pub history_has_more: bool,
+    pub usage: Vec<(String, i64)>, // Per-app totals for current_view_mode, same range as the dashboard
         pub daily_usage: Vec<HierarchicalDisplayItem>, // Hierarchical for Detailed Stats
         pub weekly_usage: Vec<HierarchicalDisplayItem>,
         pub monthly_usage: Vec<HierarchicalDisplayItem>,    pub flat_daily_usage: Vec<(String, i64)>, // Flat for Today's Activity Progress
     pub current_view_mode: ViewMode,  // Track current dashboard view mode
+    last_breakdown_panel: usize,  // Restored when reopening the breakdown dashboard
     pub logs: Vec<String>,
     pub manual_app_name: Option<String>,
     pub current_app: String,
     current_window: Option<String>,
+    current_fullscreen: bool, // Only compared when fullscreen_category is set
+    dnd_active: Arc<AtomicBool>, // Do-Not-Disturb state, polled in the background when dnd_category or dnd_tag is set
+    current_dnd: bool, // DND state the current session started with
+    screen_locked: Arc<AtomicBool>, // Set while the screen is locked, when lock_is_afk is on
+    afk_state: AfkHysteresis, // Idle readings smoothed over afk_hysteresis_checks
     pub current_session: Option<Session>,
     pub last_input: Arc<Mutex<DateTime<Local>>>,
     // Breakdown data caches
     pub browser_breakdown: Vec<(String, i64)>,
     pub project_breakdown: Vec<(String, i64)>,
     pub file_breakdown: Vec<(String, String, i64)>,
+    pub weekly_top_files: Vec<(String, i64)>, // "file [Language]" totals for the coding stats popup
+    pub weekly_languages: Vec<(String, i64)>,
+    pub weekday_averages: [f64; 7], // Average active seconds per weekday, Monday first
+    pub weekly_totals: Vec<(i32, i32, i64)>, // (ISO year, ISO week, active seconds) for the weekly totals view, oldest first
+    pub category_days: Vec<(chrono::NaiveDate, Vec<(String, i64)>)>, // Category totals per day for the category mix view, oldest first
+    pub redact_titles: bool, // Hide window/file/page titles on screen (for screen sharing); not saved
+    pub flash: Option<(String, Instant)>, // Short confirmation shown in the status bar, with when it was set
+    pub follow_logs: bool, // --follow: the live log replaces the dashboard and detection details are logged
+    pub viewer_database: Option<String>, // --db-url: host/database being viewed; nothing is tracked into it
+    pub start_view_mode: Option<ViewMode>, // --mode: overrides the view mode remembered in ui_state.json
+    pub start_in_breakdown: bool, // --view breakdown: open straight into the breakdown dashboard
+    pub collapse_subcategories: bool, // Roll sub-categories into their parent; starts from config, not saved
+    pub show_away_usage: bool, // AFK and idle time as dashboard bars; starts from config, not saved
+    pub away_usage: Vec<(String, i64)>, // AFK and idle pseudo-app rows for current_view_mode, loaded while shown
     pub terminal_breakdown: Vec<(String, i64)>,
     pub category_breakdown: Vec<(String, i64)>,
+    pub streak_days: u32,
+    pub first_session_date: Option<chrono::NaiveDate>, // For "Tracking since" in the status bar
+    pub billable_today: Vec<(String, i64)>, // Today's category totals, for the billable estimate
+    pub switches_per_hour: [i64; 24], // Session starts per local hour over the last SWITCHES_DAYS days
+    pub week_start: WeekStart, // Resolved from config and WEEK_START; the database uses the same value
+    pub rounding_minutes: u64, // Display rounding resolved from config and ROUNDING_MINUTES; 0 is exact
+    pub notification_trend: Vec<u64>, // Unread counts per hour, oldest first, over NOTIFICATION_TREND_HOURS
+    pub busiest_project: Option<(String, i64)>, // Top project (or app) this week, shown in the stats panel
+    pub hostname_usage: Vec<(String, i64)>, // Time per machine for current_view_mode, when several share the database
+    pub daemon_last_seen: Option<chrono::DateTime<Local>>, // This machine's daemon heartbeat, None if it never ran here
+    pub app_limit_alerts: Vec<(String, i64, i64)>, // Apps the daemon found past their daily limit today
+    pub other_app_usage: Vec<(String, i64)>, // Apps categorized as Other in current_view_mode, largest first
+    pub afk_stats: crate::ui::metrics::AfkStats, // Breaks in current_view_mode, shown in the AFK panel
+    pub sticky_tag: Option<String>, // Given to each new session until cleared with [T]
     pub categories: Vec<String>,
+    pub config: AppConfig,
 }
 
 impl App {
-    pub fn new(database: Database) -> Self {
+    pub fn new(mut database: Database, config: AppConfig) -> Self {
         let monitor = AppMonitor::new();
         let last_input = Arc::new(Mutex::new(Local::now()));
+        let ui_state = UiState::load();
+
+        // THEME in .env takes precedence over the theme saved in config.toml
+        let theme = std::env::var("THEME")
+            .ok()
+            .and_then(|name| crate::ui::theme::parse(&name))
+            .unwrap_or(config.theme);
+        crate::ui::theme::set_active(theme);
+        // App icons are emoji too, so emoji-free mode turns them off
+        crate::ui::icons::configure(config.app_icons_enabled && crate::ui::render::emoji_enabled(), config.app_icons.clone());
+        let week_start = match std::env::var("WEEK_START").map(|name| name.to_lowercase()).as_deref() {
+            Ok("rolling") => WeekStart::Rolling,
+            Ok("monday") => WeekStart::Monday,
+            Ok("sunday") => WeekStart::Sunday,
+            _ => config.week_start,
+        };
+        database.set_week_start(week_start);
+        let rounding_minutes = crate::ui::metrics::resolve_rounding_minutes(config.rounding_minutes);
 
         // Choose input monitoring method based on session type
         if monitor.uses_wayland() {
@@ -81,45 +229,87 @@ impl App {
             Self::start_wayland_input_monitoring(Arc::clone(&last_input));
         } else {
             // On X11, use rdev for direct input event monitoring
-            Self::start_rdev_input_monitoring(Arc::clone(&last_input));
+            Self::start_rdev_input_monitoring(Arc::clone(&last_input), config.ignores_mouse_move());
+        }
+        let dnd_active = Arc::new(AtomicBool::new(false));
+        if config.dnd_enabled() {
+            Self::start_dnd_monitoring(Arc::clone(&dnd_active));
+        }
+        let screen_locked = Arc::new(AtomicBool::new(false));
+        if config.lock_is_afk {
+            crate::daemon::screen_lock::spawn_watcher(Arc::clone(&screen_locked), Arc::clone(&last_input));
         }
         Self {
-            state: AppState::Dashboard { view_mode: ViewMode::Daily },
+            state: AppState::Dashboard { view_mode: ui_state.view_mode.clone() },
             database,
             monitor,
             history: vec![],
             current_history: vec![],
+            history_page: vec![],
+            history_has_more: false,
             usage: vec![],
             daily_usage: vec![],
             weekly_usage: vec![],
             monthly_usage: vec![],
             flat_daily_usage: vec![],
-            current_view_mode: ViewMode::Daily,
+            current_view_mode: ui_state.view_mode,
+            last_breakdown_panel: ui_state.breakdown_panel,
             logs: vec![],
             manual_app_name: None,
             current_app: "unknown".to_string(),
             current_window: None,
+            current_fullscreen: false,
+            dnd_active,
+            current_dnd: false,
+            screen_locked,
+            afk_state: AfkHysteresis::default(),
             current_session: None,
             last_input,
             browser_breakdown: vec![],
             project_breakdown: vec![],
             file_breakdown: vec![],
+            weekly_top_files: vec![],
+            weekly_languages: vec![],
+            weekday_averages: [0.0; 7],
+            weekly_totals: vec![],
+            category_days: vec![],
+            redact_titles: false,
+            flash: None,
+            follow_logs: false,
+            viewer_database: None,
+            start_view_mode: None,
+            start_in_breakdown: false,
+            collapse_subcategories: config.collapse_subcategories,
+            show_away_usage: config.show_afk_in_usage,
+            away_usage: vec![],
             terminal_breakdown: vec![],
             category_breakdown: vec![],
+            streak_days: 0,
+            first_session_date: None,
+            billable_today: vec![],
+            switches_per_hour: [0; 24],
+            week_start,
+            rounding_minutes,
+            notification_trend: vec![],
+            busiest_project: None,
+            hostname_usage: vec![],
+            daemon_last_seen: None,
+            app_limit_alerts: Vec::new(),
+            other_app_usage: vec![],
+            afk_stats: Default::default(),
+            sticky_tag: None,
             categories: vec![],
+            config,
         }
     }
 
     // Cross-platform input monitoring using rdev
-    fn start_rdev_input_monitoring(last_input: Arc<Mutex<DateTime<Local>>>) {
+    fn start_rdev_input_monitoring(last_input: Arc<Mutex<DateTime<Local>>>, ignore_mouse_move: bool) {
         std::thread::spawn(move || {
             let callback = move |event: rdev::Event| {
-                match event.event_type {
-                    EventType::KeyPress(_) | EventType::KeyRelease(_) | EventType::ButtonPress(_) | EventType::ButtonRelease(_) | EventType::MouseMove { .. } => {
-                        log::debug!("Input event detected: {:?}", event.event_type);
-                        *last_input.lock().unwrap() = Local::now();
-                    }
-                    _ => {}
+                if crate::daemon::afk::counts_as_input(&event.event_type, ignore_mouse_move) {
+                    log::debug!("Input event detected: {:?}", event.event_type);
+                    *last_input.lock().unwrap() = Local::now();
                 }
             };
             if let Err(error) = listen(callback) {
@@ -169,12 +359,9 @@ impl App {
 
                         // Also check for window changes as additional activity detection
                         if last_window_check.elapsed() >= tokio::time::Duration::from_secs(2) {
-                            match tokio::join!(
-                                monitor.get_active_app_async(),
-                                monitor.get_active_window_name_async()
-                            ) {
-                                (Ok(app), Ok(window_name)) => {
-                                    let window = if window_name.is_empty() { None } else { Some(window_name) };
+                            match monitor.get_active_window_info_async().await {
+                                Ok((app, window_name)) => {
+                                    let window = window_name.filter(|name| !name.is_empty());
                                     let current_info = (app.clone(), window.clone());
                                     if last_window_info.as_ref() != Some(&current_info) {
                                         // Window changed - consider this as activity
@@ -183,7 +370,7 @@ impl App {
                                         last_window_info = Some(current_info);
                                     }
                                 }
-                                _ => {
+                                Err(_) => {
                                     // Window detection also failed - this is bad
                                     log::warn!("Both idle monitoring and window detection failed");
                                 }
@@ -197,8 +384,12 @@ impl App {
         });
     }
 
-    // Check idle time using GNOME D-Bus interfaces
+    // Check idle time using GNOME D-Bus interfaces, or swayidle on wlroots compositors
     pub async fn check_wayland_idle_time() -> Result<u32> {
+        if crate::tracker::wlroots_idle::is_wlroots() {
+            return crate::tracker::wlroots_idle::idle_seconds().await;
+        }
+
         let connection = zbus::Connection::session().await?;
 
         // Try GNOME Mutter Idle Monitor with proper monitor creation
@@ -345,6 +536,75 @@ impl App {
         }
     }
 
+    // Poll Do-Not-Disturb; where the state can't be read it stays off and tracking is unaffected
+    fn start_dnd_monitoring(dnd_active: Arc<AtomicBool>) {
+        tokio::spawn(async move {
+            let mut reported_unavailable = false;
+            loop {
+                match Self::check_dnd_state().await {
+                    Ok(active) => dnd_active.store(active, Ordering::Relaxed),
+                    Err(e) => {
+                        if !reported_unavailable {
+                            log::info!("Do-Not-Disturb state unavailable: {}", e);
+                            reported_unavailable = true;
+                        }
+                        dnd_active.store(false, Ordering::Relaxed);
+                    }
+                }
+                tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+            }
+        });
+    }
+
+    // Check Do-Not-Disturb: the freedesktop Notifications "Inhibited" property (KDE Plasma),
+    // falling back to GNOME's show-banners setting
+    #[cfg(target_os = "linux")]
+    pub async fn check_dnd_state() -> Result<bool> {
+        let connection = zbus::Connection::session().await?;
+        match connection.call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.DBus.Properties"),
+            "Get",
+            &("org.freedesktop.Notifications", "Inhibited"),
+        ).await {
+            Ok(response) => {
+                let inhibited: zbus::zvariant::OwnedValue = response.body().deserialize()?;
+                Ok(bool::try_from(inhibited)?)
+            }
+            Err(e) => {
+                log::debug!("Notifications Inhibited property failed: {}", e);
+                // GNOME stores DND as show-banners = false
+                let output = tokio::process::Command::new("gsettings")
+                    .args(["get", "org.gnome.desktop.notifications", "show-banners"])
+                    .output()
+                    .await?;
+                if !output.status.success() {
+                    return Err(anyhow::anyhow!("No Do-Not-Disturb state found over D-Bus or GSettings"));
+                }
+                Ok(String::from_utf8_lossy(&output.stdout).trim() == "false")
+            }
+        }
+    }
+
+    // Check Do-Not-Disturb from the Notification Center preference
+    #[cfg(target_os = "macos")]
+    pub async fn check_dnd_state() -> Result<bool> {
+        let output = tokio::process::Command::new("defaults")
+            .args(["-currentHost", "read", "com.apple.notificationcenterui", "doNotDisturb"])
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(anyhow::anyhow!("doNotDisturb preference not set"));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim() == "1")
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    pub async fn check_dnd_state() -> Result<bool> {
+        Err(anyhow::anyhow!("Do-Not-Disturb detection isn't supported on this platform"))
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         log::info!("Starting UI...");
 
@@ -356,18 +616,47 @@ impl App {
         signal_hook::flag::register(signal_hook::consts::SIGINT, Arc::clone(&shutdown_flag))?;
 
         // Start tracking initial app before enabling raw mode
-        self.start_tracking().await?;
+        if self.viewer_database.is_none() {
+            self.start_tracking().await?;
+        }
+        if let Some(view_mode) = self.start_view_mode.take() {
+            self.current_view_mode = view_mode.clone();
+            self.state = AppState::Dashboard { view_mode };
+        }
+        if self.follow_logs {
+            self.state = AppState::ViewingLogs;
+        }
 
-        // Fix any old category data from previous versions
-        if let Err(e) = self.database.fix_old_categories().await {
+        // Fix any old category data from previous versions (not in a database that's only being viewed)
+        let fixed = if self.viewer_database.is_none() { self.database.fix_old_categories().await } else { Ok(()) };
+        if let Err(e) = fixed {
             log::warn!("Failed to fix old categories: {}", e);
         }
 
         // Load history and usage (load 30 sessions for display)
         self.history = self.database.get_recent_sessions(30).await.unwrap();
-        self.usage = self.database.get_app_usage().await.unwrap();
-        self.current_history = self.database.get_daily_sessions().await.unwrap();
+        self.usage = self.database.get_app_usage_for_range(self.current_view_mode.clone()).await.unwrap();
+        self.current_history = match &self.current_view_mode {
+            ViewMode::Daily => self.database.get_daily_sessions().await.unwrap(),
+            ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap(),
+            ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap(),
+        };
         self.refresh_categories().await.unwrap();
+        self.refresh_category_usage().await;
+        self.refresh_streak().await;
+        self.refresh_switches_per_hour().await;
+        self.refresh_billable_today().await;
+        self.refresh_busiest_project().await;
+        self.refresh_hostname_usage().await;
+        self.refresh_other_app_usage().await;
+        self.refresh_afk_stats().await;
+        self.refresh_away_usage().await;
+        self.refresh_daemon_heartbeat().await;
+        self.refresh_app_limit_alerts().await;
+        match self.database.get_first_session_time().await {
+            Ok(first) => self.first_session_date = first.map(|start| start.date_naive()),
+            Err(e) => log::warn!("Failed to load first session time: {}", e),
+        }
 
         // Create hierarchical usage data from sessions for Detailed Stats
         self.daily_usage = crate::ui::hierarchical::create_hierarchical_usage(&self.current_history);
@@ -377,6 +666,11 @@ impl App {
         // Create flat usage data for Today's Activity Progress
         self.flat_daily_usage = self.database.get_daily_usage().await.unwrap();
 
+        // The breakdown data has to be in place before the first draw
+        if self.start_in_breakdown {
+            self.open_breakdown(self.current_view_mode.clone()).await;
+        }
+
         eprintln!("Enabling raw mode...");
         if let Err(e) = enable_raw_mode() {
             eprintln!("Failed to enable raw mode: {}. This may happen when running in environments without proper terminal support (e.g., SSH without pseudo-terminal, containers, etc.)", e);
@@ -396,13 +690,15 @@ impl App {
         let save_interval = Duration::from_secs(3600); // 1 hour
 
         let mut last_data_refresh = Instant::now();
-        let data_refresh_interval = Duration::from_secs(5); // Refresh dashboard data every 5 seconds for near real-time updates
 
         let mut last_afk_check = Instant::now();
         let afk_check_interval = Duration::from_secs(1); // Check AFK status every second
-        let afk_threshold = Duration::from_secs(300); // 5 minutes of idle = AFK
 
         loop {
+            // Read thresholds every iteration so changes from the settings screen apply without restart
+            let data_refresh_interval = Duration::from_secs(self.config.refresh_interval_secs);
+            let afk_threshold = Duration::from_secs(self.current_afk_threshold_secs());
+
             terminal.draw(|f| self.draw(f))?;
 
             // Check for shutdown signal (SIGTERM/SIGINT)
@@ -412,7 +708,7 @@ impl App {
             }
 
             // Check for AFK status every second
-            if last_afk_check.elapsed() >= afk_check_interval {
+            if self.viewer_database.is_none() && last_afk_check.elapsed() >= afk_check_interval {
                 let time_since_last_check = last_afk_check.elapsed();
 
                 // Detect system sleep: if more than 10 minutes passed since last check, system was likely asleep
@@ -420,7 +716,9 @@ impl App {
                 let was_system_asleep = time_since_last_check > sleep_threshold;
 
                 let idle_duration = Local::now().signed_duration_since(*self.last_input.lock().unwrap());
-                let is_currently_afk = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+                let is_idle = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+                // A locked screen is AFK straight away; idle time has to stay past (or back under) the threshold
+                let is_currently_afk = self.afk_state.observe(is_idle, self.config.afk_hysteresis_checks) || self.screen_locked.load(Ordering::Relaxed);
                 log::debug!("Idle duration: {} seconds, is_afk: {}", idle_duration.num_seconds(), is_currently_afk);
 
                 // If system was asleep, force AFK state for the sleep period
@@ -428,28 +726,11 @@ impl App {
                     log::info!("System sleep detected (gap: {:.1} minutes), creating AFK session for sleep period",
                               time_since_last_check.as_secs_f64() / 60.0);
                     // End current session and start AFK session for the sleep period
-                    if let Some(ref mut session) = self.current_session {
-                        if !session.is_afk.unwrap_or(false) {
-                            // Save the current session up to sleep time
-                            let mut old_session = self.current_session.take().unwrap();
-                            let sleep_start_time = Local::now() - chrono::Duration::from_std(time_since_last_check).unwrap_or(chrono::Duration::minutes(0));
-                            old_session.duration = sleep_start_time.signed_duration_since(old_session.start_time).num_seconds();
-
-                            if let Err(e) = self.database.insert_session(&old_session).await {
-                                log::error!("Failed to save session during sleep detection: {}", e);
-                            } else {
-                                log::info!("Session saved due to system sleep: {} for {:.1} minutes",
-                                          old_session.app_name, old_session.duration as f64 / 60.0);
-                            }
-
-                            // Start AFK session for sleep period with is_afk=true
-                            self.switch_app_with_afk("AFK".to_string(), Some(true)).await?;
-                            if let Some(ref mut new_session) = self.current_session {
-                                new_session.start_time = sleep_start_time;
-                            }
+                    if self.current_session.as_ref().is_some_and(|session| !session.is_afk.unwrap_or(false)) {
+                        let sleep_start_time = Local::now() - chrono::Duration::from_std(time_since_last_check).unwrap_or(chrono::Duration::minutes(0));
+                        self.start_afk_from(sleep_start_time).await?;
 
-                            // Now continue with normal AFK check
-                        }
+                        // Now continue with normal AFK check
                     }
                 }
 
@@ -469,6 +750,13 @@ impl App {
                             log::info!("Session saved on AFK state change: {} -> is_afk={}", old_session.app_name, is_currently_afk);
                         }
 
+                        let transition = if is_currently_afk {
+                            format!("Went AFK after {}s without input", idle_duration.num_seconds())
+                        } else {
+                            "Back from AFK".to_string()
+                        };
+                        self.logs.push(format!("[{}] {}", Local::now().format("%H:%M:%S"), transition));
+
                         // Start new session with updated AFK state
                         if is_currently_afk {
                             // Starting AFK session
@@ -491,14 +779,40 @@ impl App {
                 last_afk_check = Instant::now();
             }
 
-            // Check for app or window change (but not if we're AFK)
-            if let Ok(active_app) = self.monitor.get_active_app_async().await {
-                let active_window = self.monitor.get_active_window_name_async().await.ok();
-                let idle_duration = Local::now().signed_duration_since(*self.last_input.lock().unwrap());
-                let is_currently_afk = idle_duration.num_seconds() >= afk_threshold.as_secs() as i64;
+            // Check for app or window change (but not if we're AFK, or only viewing another database)
+            let active_window_info = if self.viewer_database.is_none() {
+                // Some setups report the focused window with an empty app name; the current session carries on
+                self.monitor.get_active_window_info_async().await.ok().filter(|(active_app, _)| {
+                    let blank = active_app.trim().is_empty();
+                    if blank {
+                        log::debug!("Active window has no app name, keeping {}", self.current_app);
+                    }
+                    !blank
+                })
+            } else {
+                None
+            };
+            // Windows on the do-not-track list end the running session and start none
+            let active_window_info = match active_window_info {
+                Some((active_app, active_window)) if self.config.is_do_not_track(&active_app, active_window.as_deref()) => {
+                    self.stop_for_do_not_track(&active_app).await?;
+                    None
+                }
+                other => other,
+            };
+            if let Some((active_app, active_window)) = active_window_info {
+                let is_currently_afk = self.afk_state.is_afk() || self.screen_locked.load(Ordering::Relaxed);
 
                 // Only track app changes if not AFK
-                if !is_currently_afk && (active_app != self.current_app || active_window != self.current_window) {
+                // Going in or out of fullscreen starts a new session when fullscreen windows get their own category
+                let fullscreen_changed = self.config.fullscreen_category.is_some() && self.monitor.is_fullscreen() != self.current_fullscreen;
+                // Likewise turning Do-Not-Disturb on or off, when it sets a category or tag
+                let dnd_changed = self.config.dnd_enabled() && self.dnd_active.load(Ordering::Relaxed) != self.current_dnd;
+                if dnd_changed {
+                    let state = if self.current_dnd { "off" } else { "on" };
+                    self.logs.push(format!("[{}] Do-Not-Disturb {}", Local::now().format("%H:%M:%S"), state));
+                }
+                if !is_currently_afk && (active_app != self.current_app || active_window != self.current_window || fullscreen_changed || dnd_changed) {
                     self.switch_app(active_app.clone()).await?;
                     self.current_app = active_app;
                     self.current_window = active_window;
@@ -511,9 +825,15 @@ impl App {
             }
 
             if event::poll(Duration::from_millis(100))? {
-                if let Event::Key(key) = event::read()? {
+                let event = event::read()?;
+                if let Event::Mouse(mouse) = event {
+                    let size = terminal.size()?;
+                    self.handle_mouse(mouse, ratatui::layout::Rect::new(0, 0, size.width, size.height)).await?;
+                } else if let Event::Key(key) = event {
                     log::debug!("Key pressed: {:?} in state: {:?}", key.code, self.state);
-                    self.logs.push(format!("[{}] Key: {:?} State: {:?}", Local::now().format("%H:%M:%S"), key.code, self.state));
+                    if !self.follow_logs {
+                        self.logs.push(format!("[{}] Key: {:?} State: {:?}", Local::now().format("%H:%M:%S"), key.code, self.state));
+                    }
 
                      let dashboard_view_mode = match &self.state {
                          AppState::Dashboard { view_mode } => Some(view_mode.clone()),
@@ -522,11 +842,25 @@ impl App {
                      if dashboard_view_mode.is_some() {
                          let view_mode = dashboard_view_mode.as_ref().unwrap();
                          match key.code {
-                             KeyCode::Char('q') => break,
-                             KeyCode::Char('r') => self.start_app_selection(),
-                             KeyCode::Char('c') => self.start_category_selection(),
+                             KeyCode::Char('q') if !self.config.confirm_quit => break,
+                             KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
+                             KeyCode::Char('r') if self.viewer_database.is_none() => self.start_app_selection(),
+                             KeyCode::Char('c') if self.viewer_database.is_none() => self.start_category_selection(),
+                             KeyCode::Char('R') if self.viewer_database.is_none() => self.start_category_rename(),
                              KeyCode::Char('l') => self.view_logs(),
+                             KeyCode::Char('s') => self.open_settings(),
                              KeyCode::Char('C') => self.state = AppState::CommandsPopup,
+                             KeyCode::Char('e') => self.open_coding_stats().await,
+                             KeyCode::Char('w') => self.open_weekday_averages().await,
+                             KeyCode::Char('W') => self.open_weekly_totals().await,
+                             KeyCode::Char('m') => self.open_category_days().await,
+                             KeyCode::Char('p') => self.toggle_redact_titles(),
+                             KeyCode::Char('a') => self.toggle_away_usage().await,
+                             KeyCode::Char('A') if self.viewer_database.is_none() => self.start_mark_away(),
+                             KeyCode::Char('t') if self.viewer_database.is_none() => self.start_tag_session(None, false),
+                             KeyCode::Char('T') if self.viewer_database.is_none() => self.start_tag_session(None, true),
+                             KeyCode::Char('/') => self.start_search(),
+                             KeyCode::Char('y') => self.copy_current_session(),
                              KeyCode::Tab => {
                                  let new_view_mode = match view_mode {
                                      ViewMode::Daily => ViewMode::Weekly,
@@ -535,122 +869,144 @@ impl App {
                                  };
                                  self.current_view_mode = new_view_mode.clone();
                                  self.update_history().await?;
+                                 self.refresh_hostname_usage().await;
+                                 self.refresh_other_app_usage().await;
+                                 self.refresh_afk_stats().await;
+                                 self.refresh_away_usage().await;
                                  self.state = AppState::Dashboard { view_mode: new_view_mode };
                              }
                              KeyCode::Char('h') => {
                                  log::debug!("'h' key pressed - opening history popup");
                                  self.logs.push(format!("[{}] Opening history popup", Local::now().format("%H:%M:%S")));
-                                 self.current_history = match view_mode {
-                                     ViewMode::Daily => self.database.get_daily_sessions().await.unwrap_or_default(),
-                                     ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap_or_default(),
-                                     ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap_or_default(),
-                                 };
-                                 self.state = AppState::HistoryPopup { view_mode: view_mode.clone(), scroll_position: 0 };
+                                 let view_mode = view_mode.clone();
+                                 self.open_history(view_mode).await;
                              }
                              KeyCode::Char('b') => {
                                  log::debug!("'b' key pressed - opening breakdown dashboard");
                                  self.logs.push(format!("[{}] Opening breakdown dashboard", Local::now().format("%H:%M:%S")));
-                                 // Load current_history first (filtered by view mode)
-                                 self.current_history = match view_mode {
-                                     ViewMode::Daily => self.database.get_daily_sessions().await.unwrap_or_default(),
-                                     ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap_or_default(),
-                                     ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap_or_default(),
-                                 };
-                                 // Then aggregate breakdown data from current_history
-                                 self.load_breakdown_data_from_history();
-self.state = AppState::BreakdownDashboard {
-                                      view_mode: view_mode.clone(),
-                                      selected_panel: 0,
-                                      panel_scrolls: [0; 5],
-                                  };
+                                 let view_mode = view_mode.clone();
+                                 self.open_breakdown(view_mode).await;
                              }
                              _ => {}
                          }
                      } else if matches!(self.state, AppState::CommandsPopup) {
                          match key.code {
                              KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
-                             KeyCode::Char('q') => break,
-                             KeyCode::Char('r') => self.start_app_selection(),
-                             KeyCode::Char('c') => self.start_category_selection(),
+                             KeyCode::Char('q') if !self.config.confirm_quit => break,
+                             KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
+                             KeyCode::Char('e') => self.open_coding_stats().await,
+                             KeyCode::Char('w') => self.open_weekday_averages().await,
+                             KeyCode::Char('W') => self.open_weekly_totals().await,
+                             KeyCode::Char('m') => self.open_category_days().await,
+                             KeyCode::Char('p') => self.toggle_redact_titles(),
+                             KeyCode::Char('a') => self.toggle_away_usage().await,
+                             KeyCode::Char('A') if self.viewer_database.is_none() => self.start_mark_away(),
+                             KeyCode::Char('t') if self.viewer_database.is_none() => self.start_tag_session(None, false),
+                             KeyCode::Char('T') if self.viewer_database.is_none() => self.start_tag_session(None, true),
+                             KeyCode::Char('/') => self.start_search(),
+                             KeyCode::Char('r') if self.viewer_database.is_none() => self.start_app_selection(),
+                             KeyCode::Char('c') if self.viewer_database.is_none() => self.start_category_selection(),
+                             KeyCode::Char('R') if self.viewer_database.is_none() => self.start_category_rename(),
                              KeyCode::Char('l') => self.view_logs(),
+                             KeyCode::Char('s') => self.open_settings(),
                              KeyCode::Char('h') => {
                                  log::debug!("'h' key pressed from CommandsPopup - opening history popup");
                                  self.logs.push(format!("[{}] Opening history popup from commands menu", Local::now().format("%H:%M:%S")));
-                                 self.current_history = match &self.current_view_mode {
-                                     ViewMode::Daily => self.database.get_daily_sessions().await.unwrap_or_default(),
-                                     ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap_or_default(),
-                                     ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap_or_default(),
-                                 };
-                                 self.state = AppState::HistoryPopup { view_mode: self.current_view_mode.clone(), scroll_position: 0 };
+                                 self.open_history(self.current_view_mode.clone()).await;
                              }
                              KeyCode::Char('b') => {
                                  log::debug!("'b' key pressed from CommandsPopup - opening breakdown dashboard");
                                  self.logs.push(format!("[{}] Opening breakdown dashboard from commands menu", Local::now().format("%H:%M:%S")));
-                                 // Load current_history first (filtered by view mode)
-                                 self.current_history = match &self.current_view_mode {
-                                     ViewMode::Daily => self.database.get_daily_sessions().await.unwrap_or_default(),
-                                     ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap_or_default(),
-                                     ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap_or_default(),
-                                 };
-                                 // Then aggregate breakdown data from current_history
-                                 self.load_breakdown_data_from_history();
-self.state = AppState::BreakdownDashboard {
-                                      view_mode: self.current_view_mode.clone(),
-                                      selected_panel: 0,
-                                      panel_scrolls: [0; 5],
-                                  };
+                                 self.open_breakdown(self.current_view_mode.clone()).await;
                              }
                              _ => {}
                          }
                      } else {
                          match &mut self.state {
-                             AppState::ViewingLogs => {
+                             AppState::ConfirmQuit => {
                                  match key.code {
-                                     KeyCode::Char('q') => break,
-                                     KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
-                                     _ => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
+                                     KeyCode::Char('y') | KeyCode::Char('Y') => break,
+                                     KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
+                                         self.state = self.home_state();
+                                     }
+                                     _ => {}
                                  }
                              }
-                             AppState::SelectingApp { selected_index, selected_unique_id } => {
+                             AppState::SearchResults { sessions, scroll_position, .. } => {
+                                 match key.code {
+                                     KeyCode::Esc => self.state = self.home_state(),
+                                     KeyCode::Char('q') if !self.config.confirm_quit => break,
+                                     KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
+                                     KeyCode::Char('/') => self.start_search(),
+                                     KeyCode::Up => *scroll_position = scroll_position.saturating_sub(1),
+                                     KeyCode::Down => *scroll_position = (*scroll_position + 1).min(sessions.len().saturating_sub(1)),
+                                     KeyCode::PageUp => *scroll_position = scroll_position.saturating_sub(10),
+                                     KeyCode::PageDown => *scroll_position = (*scroll_position + 10).min(sessions.len().saturating_sub(1)),
+                                     _ => {}
+                                 }
+                             }
+                             AppState::ViewingLogs | AppState::CodingStats | AppState::WeekdayAverages | AppState::WeeklyTotals | AppState::CategoryDays => {
+                                 match key.code {
+                                     KeyCode::Char('q') if !self.config.confirm_quit => break,
+                                     KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
+                                     _ => self.state = self.home_state(),
+                                 }
+                             }
+                             AppState::SelectingApp { selected_index, selected_unique_id, items } => {
+                                 *selected_index = (*selected_index).min(items.len().saturating_sub(1));
                                  match key.code {
                                      KeyCode::Up => {
                                          if *selected_index > 0 {
                                              *selected_index -= 1;
-                                             *selected_unique_id = self.daily_usage[*selected_index].unique_id.clone();
+                                             *selected_unique_id = items[*selected_index].unique_id.clone();
                                          }
                                      }
                                      KeyCode::Down => {
-                                         if *selected_index < self.daily_usage.len().saturating_sub(1) {
+                                         if *selected_index < items.len().saturating_sub(1) {
                                              *selected_index += 1;
-                                             *selected_unique_id = self.daily_usage[*selected_index].unique_id.clone();
+                                             *selected_unique_id = items[*selected_index].unique_id.clone();
                                          }
                                      }
                                      KeyCode::Enter => {
-                                         if let Some(item) = self.daily_usage.get(*selected_index) {
-                                             self.start_rename_app(item.unique_id.clone());
+                                         if let Some(unique_id) = items.get(*selected_index).map(|item| item.unique_id.clone()) {
+                                             self.start_rename_app(unique_id).await;
                                          }
                                      }
                                      KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
                                      _ => {}
                                  }
                              }
-                             AppState::SelectingCategory { selected_index, selected_unique_id } => {
+                             AppState::SelectingCategory { selected_index, selected_unique_id, items } => {
+                                 *selected_index = (*selected_index).min(items.len().saturating_sub(1));
                                  match key.code {
                                      KeyCode::Up => {
                                          if *selected_index > 0 {
                                              *selected_index -= 1;
-                                             *selected_unique_id = self.daily_usage[*selected_index].unique_id.clone();
+                                             *selected_unique_id = items[*selected_index].unique_id.clone();
                                          }
                                      }
                                      KeyCode::Down => {
-                                         if *selected_index < self.daily_usage.len().saturating_sub(1) {
+                                         if *selected_index < items.len().saturating_sub(1) {
                                              *selected_index += 1;
-                                             *selected_unique_id = self.daily_usage[*selected_index].unique_id.clone();
+                                             *selected_unique_id = items[*selected_index].unique_id.clone();
                                          }
                                      }
                                      KeyCode::Enter => {
-                                         if let Some(item) = self.daily_usage.get(*selected_index) {
-                                             self.start_category_menu(item.unique_id.clone());
+                                         if let Some(unique_id) = items.get(*selected_index).map(|item| item.unique_id.clone()) {
+                                             self.start_category_menu(unique_id);
+                                         }
+                                     }
+                                     KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
+                                     _ => {}
+                                 }
+                             }
+                             AppState::SelectingCategoryToRename { selected_index, categories } => {
+                                 match key.code {
+                                     KeyCode::Up => *selected_index = selected_index.saturating_sub(1),
+                                     KeyCode::Down => *selected_index = (*selected_index + 1).min(categories.len().saturating_sub(1)),
+                                     KeyCode::Enter => {
+                                         if let Some(category) = categories.get(*selected_index).cloned() {
+                                             self.start_rename_category_input(category);
                                          }
                                      }
                                      KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
@@ -690,17 +1046,63 @@ self.state = AppState::BreakdownDashboard {
                                      _ => {}
                                  }
                              }
-                             AppState::HistoryPopup { view_mode, scroll_position } => {
+                             AppState::HistoryPopup { view_mode, scroll_position, hide_afk, merge_same_app, date } => {
+                                 // Scroll bounds follow what is actually displayed
+                                 let visible_count = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).len();
                                  match key.code {
                                      KeyCode::Esc => self.state = AppState::Dashboard { view_mode: view_mode.clone() },
-                                     KeyCode::Char('q') => break,
+                                     KeyCode::Char('q') if !self.config.confirm_quit => break,
+                                     KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
+                                     KeyCode::Char('f') => {
+                                         *hide_afk = !*hide_afk;
+                                         *scroll_position = 0;
+                                     }
+                                     KeyCode::Char('m') => {
+                                         *merge_same_app = !*merge_same_app;
+                                         *scroll_position = 0;
+                                     }
+                                     KeyCode::Char('x') if self.viewer_database.is_none() => {
+                                         // The top row of the list is the highlighted session
+                                         let target = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).into_iter().nth(*scroll_position).map(|row| row.session);
+                                         if let Some(session) = target {
+                                             self.start_split_session(session);
+                                         }
+                                     }
+                                     KeyCode::Char('t') if self.viewer_database.is_none() => {
+                                         let target = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).into_iter().nth(*scroll_position).map(|row| row.session);
+                                         if let Some(session) = target {
+                                             self.start_tag_session(Some(session), false);
+                                         }
+                                     }
+                                     KeyCode::Char('n') if self.viewer_database.is_none() => {
+                                         let target = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).into_iter().nth(*scroll_position).map(|row| row.session);
+                                         if let Some(session) = target {
+                                             self.start_note_session(session);
+                                         }
+                                     }
+                                     KeyCode::Char('g') => {
+                                         self.state = AppState::Input {
+                                             prompt: "Go to date: YYYY-MM-DD, MM-DD, today, yesterday or a weekday (e.g. tue)".to_string(),
+                                             buffer: String::new(),
+                                             action: InputAction::JumpToDate { view_mode: view_mode.clone(), hide_afk: *hide_afk, merge_same_app: *merge_same_app },
+                                         };
+                                     }
+                                     KeyCode::Char('[') | KeyCode::Char(']') => {
+                                         let today = Local::now().date_naive();
+                                         let day = date.unwrap_or(today);
+                                         let target = if key.code == KeyCode::Char('[') { day.pred_opt() } else { day.succ_opt() };
+                                         if let Some(target) = target.filter(|target| *target <= today) {
+                                             let (view_mode, hide_afk, merge_same_app) = (view_mode.clone(), *hide_afk, *merge_same_app);
+                                             self.open_history_day(view_mode, target, hide_afk, merge_same_app).await;
+                                         }
+                                     }
                                      KeyCode::Up => {
                                          if *scroll_position > 0 {
                                              *scroll_position -= 1;
                                          }
                                      }
                                      KeyCode::Down => {
-                                         let max_scroll = self.current_history.len().saturating_sub(1);
+                                         let max_scroll = visible_count.saturating_sub(1);
                                          if *scroll_position < max_scroll {
                                              *scroll_position += 1;
                                          }
@@ -709,22 +1111,29 @@ self.state = AppState::BreakdownDashboard {
                                          *scroll_position = scroll_position.saturating_sub(10);
                                      }
                                      KeyCode::PageDown => {
-                                         let max_scroll = self.current_history.len().saturating_sub(1);
+                                         let max_scroll = visible_count.saturating_sub(1);
                                          *scroll_position = (*scroll_position + 10).min(max_scroll);
                                      }
                                      _ => {}
                                  }
+                                 self.load_more_history().await;
                              }
 AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
                                   match key.code {
                                       KeyCode::Esc => self.state = AppState::Dashboard { view_mode: view_mode.clone() },
-                                      KeyCode::Char('q') => break,
+                                      KeyCode::Char('q') if !self.config.confirm_quit => break,
+                                      KeyCode::Char('q') => self.state = AppState::ConfirmQuit,
                                       KeyCode::Tab => {
                                           *selected_panel = (*selected_panel + 1) % 5;
+                                          self.last_breakdown_panel = *selected_panel;
                                       }
                                       KeyCode::Enter => {
                                           // Enter selects/highlights the current panel - visual feedback only
                                       }
+                                      KeyCode::Char('c') => {
+                                          self.collapse_subcategories = !self.collapse_subcategories;
+                                          self.refresh_category_usage().await;
+                                      }
                                       KeyCode::Up => {
                                           panel_scrolls[*selected_panel] = panel_scrolls[*selected_panel].saturating_sub(1);
                                       }
@@ -740,6 +1149,25 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
                                       _ => {}
                                   }
                               }
+                             AppState::Settings { fields, selected } => {
+                                 match key.code {
+                                     KeyCode::Esc => self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() },
+                                     KeyCode::Up => *selected = selected.saturating_sub(1),
+                                     KeyCode::Down => *selected = (*selected + 1).min(fields.len().saturating_sub(1)),
+                                     KeyCode::Left | KeyCode::Char('-') => {
+                                         if let Some(field) = fields.get_mut(*selected) {
+                                             field.adjust(false);
+                                         }
+                                     }
+                                     KeyCode::Right | KeyCode::Char('+') | KeyCode::Char(' ') => {
+                                         if let Some(field) = fields.get_mut(*selected) {
+                                             field.adjust(true);
+                                         }
+                                     }
+                                     KeyCode::Enter => self.save_settings(),
+                                     _ => {}
+                                 }
+                             }
                              _ => {}
                          }
                      }
@@ -761,7 +1189,7 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
             // Refresh dashboard data every 5 seconds for near real-time updates
             if last_data_refresh.elapsed() >= data_refresh_interval {
                 self.history = self.database.get_recent_sessions(30).await.unwrap_or_default();
-                self.usage = self.database.get_app_usage().await.unwrap_or_default();
+                self.usage = self.database.get_app_usage_for_range(self.current_view_mode.clone()).await.unwrap_or_default();
 
                 // Update current_history based on current view mode
                 if let AppState::Dashboard { ref view_mode } = self.state {
@@ -778,21 +1206,29 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
 
                     // Create flat usage data for Today's Activity Progress
                     self.flat_daily_usage = self.database.get_daily_usage().await.unwrap_or_default();
+
+                    self.refresh_category_usage().await;
+                    self.refresh_streak().await;
+                    self.refresh_switches_per_hour().await;
+                    self.refresh_billable_today().await;
+                    self.refresh_busiest_project().await;
+                    self.refresh_hostname_usage().await;
+                    self.refresh_other_app_usage().await;
+                    self.refresh_afk_stats().await;
+                    self.refresh_away_usage().await;
+                    self.refresh_daemon_heartbeat().await;
+                    self.refresh_app_limit_alerts().await;
                 }
 
                 // Update current session duration in history for real-time display
                 if let Some(current_session) = &self.current_session {
                     let current_duration = Local::now().signed_duration_since(current_session.start_time).num_seconds();
                     // Update the most recent session in history if it matches the current one
-                    if let Some(latest_session) = self.current_history.first_mut() {
+                    for latest_session in [self.current_history.first_mut(), self.history_page.first_mut()].into_iter().flatten() {
                         if latest_session.app_name == current_session.app_name &&
                            latest_session.start_time == current_session.start_time {
-                            latest_session.duration = current_duration;
-                            // Update renamed fields for persistence
-                            latest_session.browser_page_title_renamed = current_session.browser_page_title_renamed.clone();
-                            latest_session.terminal_directory_renamed = current_session.terminal_directory_renamed.clone();
-                            latest_session.editor_filename_renamed = current_session.editor_filename_renamed.clone();
-                            latest_session.tmux_window_name_renamed = current_session.tmux_window_name_renamed.clone();
+                            // Take renames and categories along with the duration, keeping the stored row's id
+                            *latest_session = Session { id: latest_session.id, duration: current_duration, ..current_session.clone() };
                         }
                     }
                 }
@@ -812,11 +1248,19 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
                 self.logs.push(format!("Failed to save session: {}", e));
             } else {
                 self.history = self.database.get_recent_sessions(30).await?;
-                self.usage = self.database.get_app_usage().await?;
+                self.usage = self.database.get_app_usage_for_range(self.current_view_mode.clone()).await?;
                 self.logs.push(format!("[{}] Ended session: {} for {}s", Local::now().format("%H:%M:%S"), session.app_name, session.duration));
             }
         }
 
+        crate::daemon::tracker::kwin::unload_script().await;
+
+        // Remember the dashboard view for next launch
+        let ui_state = UiState { view_mode: self.current_view_mode.clone(), breakdown_panel: self.last_breakdown_panel };
+        if let Err(e) = ui_state.save() {
+            log::warn!("Failed to save UI state: {}", e);
+        }
+
         // Clean up terminal state
         if let Err(e) = disable_raw_mode() {
             log::warn!("Failed to disable raw mode: {}", e);
@@ -844,8 +1288,8 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
         crate::ui::render::draw_history(self, f, area);
     }
 
-    pub fn draw_pie_chart(&self, f: &mut Frame, area: ratatui::layout::Rect, data: &[HierarchicalDisplayItem]) {
-        crate::ui::render::draw_pie_chart(self, f, area, data);
+    pub fn draw_pie_chart(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        crate::ui::render::draw_pie_chart(self, f, area);
     }
 
     pub fn draw_timeline(&self, f: &mut Frame, area: ratatui::layout::Rect) {
@@ -856,6 +1300,10 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
         crate::ui::render::draw_afk(self, f, area);
     }
 
+    pub fn draw_switches_per_hour(&self, f: &mut Frame, area: ratatui::layout::Rect) {
+        crate::ui::render::draw_switches_per_hour(self, f, area);
+    }
+
     pub fn draw_file_breakdown_section_with_style(
         &self,
         f: &mut Frame,
@@ -871,30 +1319,149 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
     }
 
     async fn start_tracking(&mut self) -> Result<()> {
+        let dnd_active = self.dnd_active.load(Ordering::Relaxed);
         let ctx = tracking::TrackingContext {
             monitor: &self.monitor,
             database: &self.database,
             manual_app_name: self.manual_app_name.clone(),
+            config: &self.config,
+            dnd_active,
         };
 
-        let result = tracking::start_tracking(&ctx, Self::categorize_app).await?;
+        let result = tracking::start_tracking(&ctx, Self::categorize_session).await?;
 
         self.current_app = result.app_name;
         self.current_session = Some(result.session);
         self.current_window = result.window_name;
+        self.current_dnd = dnd_active;
+        self.apply_sticky_tag();
+        self.current_fullscreen = self.monitor.is_fullscreen();
         self.logs.push(result.log_message);
         Ok(())
     }
 
+    // A sticky tag wins over the Do-Not-Disturb tag
+    fn apply_sticky_tag(&mut self) {
+        let dnd_tag = self.config.dnd_tag.clone().filter(|_| self.current_dnd);
+        if let Some(session) = self.current_session.as_mut().filter(|session| session.app_name != "AFK") {
+            session.tag = self.sticky_tag.clone().or(dnd_tag);
+        }
+    }
+
+    /// Save the running session as ending at `away_since` and start an AFK session from there,
+    /// for time the AFK check missed (system sleep, or marked by hand)
+    async fn start_afk_from(&mut self, away_since: chrono::DateTime<Local>) -> Result<()> {
+        let Some(mut old_session) = self.current_session.take() else {
+            return Ok(());
+        };
+        old_session.duration = away_since.signed_duration_since(old_session.start_time).num_seconds();
+
+        if let Err(e) = self.database.insert_session(&old_session).await {
+            log::error!("Failed to save session before AFK period: {}", e);
+        } else {
+            log::info!("Session saved before AFK period: {} for {:.1} minutes",
+                      old_session.app_name, old_session.duration as f64 / 60.0);
+        }
+
+        // Start AFK session for the away period with is_afk=true
+        self.switch_app_with_afk("AFK".to_string(), Some(true)).await?;
+        if let Some(ref mut new_session) = self.current_session {
+            new_session.start_time = away_since;
+        }
+        Ok(())
+    }
+
+    fn start_mark_away(&mut self) {
+        let Some((live_app, elapsed)) = self.live_session_elapsed() else {
+            self.logs.push(format!("[{}] No running session to mark as AFK", Local::now().format("%H:%M:%S")));
+            return;
+        };
+        self.state = AppState::Input {
+            prompt: format!("Minutes of this {} session you were away for (up to {})", App::clean_app_name(live_app), elapsed / 60),
+            buffer: String::new(),
+            action: InputAction::MarkAway,
+        };
+    }
+
+    fn start_search(&mut self) {
+        self.state = AppState::Input {
+            prompt: "Search app names, window and page titles, URLs, directories, files and tags".to_string(),
+            buffer: String::new(),
+            action: InputAction::Search { view_mode: self.current_view_mode.clone() },
+        };
+    }
+
+    /// Runs a search over the view mode's range and shows the matches with their total time
+    async fn search(&mut self, view_mode: ViewMode, term: &str) {
+        match self.database.search_usage(term, view_mode.clone()).await {
+            Ok((total_secs, sessions)) => {
+                self.logs.push(format!("[{}] Search '{}': {} sessions", Local::now().format("%H:%M:%S"), term.trim(), sessions.len()));
+                self.state = AppState::SearchResults { view_mode, term: term.trim().to_string(), total_secs, sessions, scroll_position: 0 };
+            }
+            Err(e) => {
+                self.logs.push(format!("[{}] Search failed: {}", Local::now().format("%H:%M:%S"), e));
+                self.state = self.home_state();
+            }
+        }
+    }
+
+    /// Turn the last `minutes` of the running session into an AFK session, then carry on tracking
+    async fn mark_away(&mut self, minutes: i64) -> Result<()> {
+        // Starting the AFK session replaces current_app, so remember what to resume
+        let active_app = self.current_app.clone();
+        let away_since = Local::now() - chrono::Duration::minutes(minutes);
+        self.start_afk_from(away_since).await?;
+
+        self.switch_app(active_app).await?;
+        if let Some(ref mut session) = self.current_session {
+            session.is_afk = Some(false);
+        }
+        self.logs.push(format!("[{}] Marked the last {} minutes as AFK", Local::now().format("%H:%M:%S"), minutes));
+        Ok(())
+    }
+
+    /// End the running session when a do-not-track window gets focus; the next tracked window
+    /// starts a new one
+    async fn stop_for_do_not_track(&mut self, app_name: &str) -> Result<()> {
+        let Some(session) = self.current_session.take() else {
+            return Ok(());
+        };
+        let ctx = tracking::TrackingContext {
+            monitor: &self.monitor,
+            database: &self.database,
+            manual_app_name: self.manual_app_name.clone(),
+            config: &self.config,
+            dnd_active: self.current_dnd,
+        };
+        let mut logs = Vec::new();
+        let saved_session = tracking::end_session(&ctx, Some(session), &mut logs).await;
+        self.logs.extend(logs);
+        self.logs.push(format!("[{}] Not tracking {} (do-not-track window)", Local::now().format("%H:%M:%S"), app_name));
+        self.current_app = String::new();
+        self.current_window = None;
+        if saved_session.is_some() {
+            self.refresh_all_data().await?;
+        }
+        Ok(())
+    }
+
     async fn switch_app(&mut self, new_app: String) -> Result<()> {
         self.switch_app_with_afk(new_app, None).await
     }
 
     async fn switch_app_with_afk(&mut self, new_app: String, is_afk: Option<bool>) -> Result<()> {
+        if new_app.trim().is_empty() {
+            log::debug!("Not switching to an empty app name, keeping {}", self.current_app);
+            return Ok(());
+        }
+
+        let dnd_active = self.dnd_active.load(Ordering::Relaxed);
         let ctx = tracking::TrackingContext {
             monitor: &self.monitor,
             database: &self.database,
             manual_app_name: self.manual_app_name.clone(),
+            config: &self.config,
+            dnd_active,
         };
 
         let view_mode = match &self.state {
@@ -903,9 +1470,9 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
         };
 
         let result = if let Some(afk_flag) = is_afk {
-            tracking::switch_app_with_afk(&ctx, self.current_session.take(), new_app, Self::categorize_app, Some(afk_flag)).await?
+            tracking::switch_app_with_afk(&ctx, self.current_session.take(), new_app, Self::categorize_session, Some(afk_flag)).await?
         } else {
-            tracking::switch_app(&ctx, self.current_session.take(), new_app, Self::categorize_app).await?
+            tracking::switch_app(&ctx, self.current_session.take(), new_app, Self::categorize_session).await?
         };
 
         // If session was saved, refresh all data
@@ -917,38 +1484,281 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
             self.monthly_usage = refresh_data.monthly_usage;
             self.history = refresh_data.history;
             self.current_history = refresh_data.current_history;
+            self.refresh_category_usage().await;
         }
 
         self.current_session = Some(result.new_session);
         self.current_app = result.app_name;
         self.current_window = result.window_name;
+        self.current_dnd = dnd_active;
+        self.apply_sticky_tag();
+        self.current_fullscreen = self.monitor.is_fullscreen();
         self.logs.extend(result.logs);
+        if self.follow_logs {
+            self.log_detection();
+        }
+        Ok(())
+    }
+
+    /// Window title and parser output of the session that just started, for --follow
+    fn log_detection(&mut self) {
+        let Some(session) = &self.current_session else {
+            return;
+        };
+        let time = Local::now().format("%H:%M:%S");
+        let window = session.window_name.as_deref().map(|title| self.shown_title(title)).unwrap_or("(no window title)");
+        let category = session.category.as_deref().unwrap_or("uncategorized");
+        let mut lines = vec![format!("[{}]   window: {} | category: {}", time, window, category)];
+        if !self.redact_titles {
+            let parsed = match (&session.parsed_data, session.parsing_success) {
+                (Some(parsed), Some(true)) => parsed.to_string(),
+                _ => "nothing recognized".to_string(),
+            };
+            lines.push(format!("[{}]   parsed: {}", time, parsed));
+        }
+        self.logs.extend(lines);
+    }
+
+    /// Where closing a popup goes back to: the live log with --follow, else the dashboard
+    fn home_state(&self) -> AppState {
+        if self.follow_logs {
+            AppState::ViewingLogs
+        } else {
+            AppState::Dashboard { view_mode: self.current_view_mode.clone() }
+        }
+    }
+
+    /// Clicks select list rows (clicking the selected row again opens it) and the wheel scrolls
+    async fn handle_mouse(&mut self, mouse: MouseEvent, size: ratatui::layout::Rect) -> Result<()> {
+        let content_area = crate::ui::render::screen_chunks(size)[1];
+        let is_click = mouse.kind == MouseEventKind::Down(MouseButton::Left);
+        let scroll: isize = match mouse.kind {
+            MouseEventKind::ScrollUp => -1,
+            MouseEventKind::ScrollDown => 1,
+            _ => 0,
+        };
+        // Nothing but the "too small" message is drawn, so there is nothing to click
+        if (!is_click && scroll == 0) || crate::ui::render::is_too_small(size) {
+            return Ok(());
+        }
+        let clicked_row = if is_click { crate::ui::render::list_row_at(content_area, mouse.column, mouse.row) } else { None };
+        let step = |index: usize, len: usize| index.saturating_add_signed(scroll).min(len.saturating_sub(1));
+
+        let is_rename = matches!(self.state, AppState::SelectingApp { .. });
+        let clicked_session = match &self.state {
+            AppState::HistoryPopup { scroll_position, hide_afk, merge_same_app, .. } if is_click => {
+                crate::ui::render::history_session_at(self, *hide_afk, *merge_same_app, *scroll_position, size, mouse.column, mouse.row)
+            }
+            _ => None,
+        };
+        let mut open_usage_item = None;
+        let mut chosen_category = None;
+        let mut category_to_rename = None;
+
+        match &mut self.state {
+            AppState::SelectingApp { selected_index, selected_unique_id, items }
+            | AppState::SelectingCategory { selected_index, selected_unique_id, items } => {
+                let target = if is_click { clicked_row } else { Some(step(*selected_index, items.len())) };
+                if let Some(item) = target.and_then(|idx| items.get(idx).map(|item| (idx, item))) {
+                    if is_click && item.0 == *selected_index {
+                        open_usage_item = Some(item.1.unique_id.clone());
+                    }
+                    *selected_index = item.0;
+                    *selected_unique_id = item.1.unique_id.clone();
+                }
+            }
+            AppState::CategoryMenu { unique_id, selected_index } => {
+                let target = if is_click { clicked_row } else { Some(step(*selected_index, self.categories.len())) };
+                if let Some(idx) = target.filter(|idx| *idx < self.categories.len()) {
+                    if is_click && idx == *selected_index {
+                        chosen_category = Some((unique_id.clone(), self.categories[idx].clone()));
+                    }
+                    *selected_index = idx;
+                }
+            }
+            AppState::SelectingCategoryToRename { selected_index, categories } => {
+                let target = if is_click { clicked_row } else { Some(step(*selected_index, categories.len())) };
+                if let Some(idx) = target.filter(|idx| *idx < categories.len()) {
+                    if is_click && idx == *selected_index {
+                        category_to_rename = Some(categories[idx].clone());
+                    }
+                    *selected_index = idx;
+                }
+            }
+            AppState::HistoryPopup { scroll_position, hide_afk, merge_same_app, .. } => {
+                let visible_count = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).len();
+                // Clicking a session scrolls it to the top of the list
+                *scroll_position = match clicked_session {
+                    Some(idx) => idx,
+                    None => scroll_position.saturating_add_signed(scroll * 3).min(visible_count.saturating_sub(1)),
+                };
+            }
+            AppState::BreakdownDashboard { selected_panel, panel_scrolls, .. } => {
+                panel_scrolls[*selected_panel] = panel_scrolls[*selected_panel].saturating_add_signed(scroll * 3);
+            }
+            AppState::SearchResults { sessions, scroll_position, .. } => {
+                *scroll_position = scroll_position.saturating_add_signed(scroll * 3).min(sessions.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+
+        self.load_more_history().await;
+        if let Some(unique_id) = open_usage_item {
+            if is_rename {
+                self.start_rename_app(unique_id).await;
+            } else {
+                self.start_category_menu(unique_id);
+            }
+        }
+        if let Some(category) = category_to_rename {
+            self.start_rename_category_input(category);
+        }
+        if let Some((unique_id, category)) = chosen_category {
+            self.handle_category_selection(unique_id, category).await?;
+        }
         Ok(())
     }
 
+    /// Freeze the usage list (with the live session's time) for the selector screens
+    fn selector_snapshot(&self) -> Vec<HierarchicalDisplayItem> {
+        let mut items = self.daily_usage.clone();
+        crate::ui::render::add_live_session_time(self, &mut items);
+        items
+    }
+
     fn start_app_selection(&mut self) {
-        if !self.daily_usage.is_empty() {
-            let initial_unique_id = self.daily_usage[0].unique_id.clone();
-            self.state = AppState::SelectingApp { selected_index: 0, selected_unique_id: initial_unique_id };
+        let items = self.selector_snapshot();
+        if let Some(first) = items.first() {
+            let initial_unique_id = first.unique_id.clone();
+            self.state = AppState::SelectingApp { selected_index: 0, selected_unique_id: initial_unique_id, items };
         }
     }
 
-    fn start_rename_app(&mut self, unique_id: String) {
-        let display_name = self.daily_usage.iter().find(|item| item.unique_id == unique_id).map(|item| item.display_name.clone()).unwrap_or(unique_id.clone());
+    async fn start_rename_app(&mut self, unique_id: String) {
+        let items = match &self.state {
+            AppState::SelectingApp { items, .. } => items,
+            _ => &self.daily_usage,
+        };
+        let display_name = items.iter().find(|item| item.unique_id == unique_id).map(|item| item.display_name.clone()).unwrap_or(unique_id.clone());
+        let mut prompt = format!("Rename '{}' to", display_name);
+
+        // App renames rewrite every matching row, so show how much history is affected before confirming
+        let (id_type, original_value) = unique_id.split_once(':').unwrap_or(("", &unique_id));
+        if !matches!(id_type, "browser_page_title" | "terminal_directory" | "editor_filename" | "tmux_window_name") {
+            match self.database.count_sessions_for_app(original_value).await {
+                Ok((count, total_seconds)) => {
+                    prompt.push_str(&format!(
+                        "\nThis will rename {} sessions ({}h {}m)",
+                        count,
+                        total_seconds / 3600,
+                        (total_seconds % 3600) / 60
+                    ));
+                }
+                Err(e) => log::warn!("Failed to count sessions for {}: {}", original_value, e),
+            }
+        }
+
         self.state = AppState::Input {
-            prompt: format!("Rename '{}' to", display_name),
+            prompt,
             buffer: String::new(),
             action: InputAction::RenameApp { old_name: unique_id },
         };
     }
 
+    fn start_split_session(&mut self, session: Session) {
+        let is_running = self.current_session.as_ref().is_some_and(|current| {
+            current.app_name == session.app_name && current.start_time == session.start_time
+        });
+        let Some(session_id) = session.id.filter(|_| !is_running) else {
+            self.logs.push(format!("[{}] The running session can't be split; switch apps first", Local::now().format("%H:%M:%S")));
+            return;
+        };
+
+        let end = session.start_time + chrono::Duration::seconds(session.duration);
+        self.state = AppState::Input {
+            prompt: format!(
+                "Split {} ({} - {}) at HH:MM[:SS], optionally followed by a new app name\nExample: {} slack",
+                App::clean_app_name(&session.app_name),
+                session.start_time.format("%H:%M:%S"),
+                end.format("%H:%M:%S"),
+                (session.start_time + chrono::Duration::seconds(session.duration / 2)).format("%H:%M"),
+            ),
+            buffer: String::new(),
+            action: InputAction::SplitSession { session_id, start_time: session.start_time },
+        };
+    }
+
+    /// Asks for a note on a saved session from the history popup, prefilled with the current one
+    fn start_note_session(&mut self, session: Session) {
+        let Some(session_id) = session.id else {
+            self.logs.push(format!("[{}] This session isn't saved yet and can't have a note", Local::now().format("%H:%M:%S")));
+            return;
+        };
+        let history = Box::new(self.state.clone());
+        self.state = AppState::Input {
+            prompt: format!("Note for this {} session (empty removes it)\nExample: fixed the auth bug", App::clean_app_name(&session.app_name)),
+            buffer: session.note.unwrap_or_default(),
+            action: InputAction::NoteSession { session_id, history },
+        };
+    }
+
+    /// Prompt for a tag for `session`, or for the running session when None.
+    /// Sticky tags also go to every new session until cleared with an empty input.
+    fn start_tag_session(&mut self, session: Option<Session>, sticky: bool) {
+        let is_running = |session: &Session| self.current_session.as_ref().is_some_and(|current| {
+            current.app_name == session.app_name && current.start_time == session.start_time
+        });
+        let (session_id, label, current_tag) = match &session {
+            Some(session) if !is_running(session) => {
+                let Some(id) = session.id else {
+                    self.logs.push(format!("[{}] This session isn't saved yet and can't be tagged", Local::now().format("%H:%M:%S")));
+                    return;
+                };
+                (Some(id), App::clean_app_name(&session.app_name), session.tag.clone())
+            }
+            _ => {
+                let Some(current) = &self.current_session else {
+                    self.logs.push(format!("[{}] Nothing is being tracked to tag", Local::now().format("%H:%M:%S")));
+                    return;
+                };
+                let current_tag = if sticky { self.sticky_tag.clone() } else { current.tag.clone() };
+                (None, App::clean_app_name(&current.app_name), current_tag)
+            }
+        };
+
+        let prompt = if sticky {
+            format!("Sticky tag for {} and every session after it (empty clears)", label)
+        } else {
+            format!("Tag for this {} session (empty clears)", label)
+        };
+        self.state = AppState::Input {
+            prompt: format!("{}\nExample: PROJ-123", prompt),
+            buffer: current_tag.unwrap_or_default(),
+            action: InputAction::TagSession { session_id, sticky },
+        };
+    }
+
     fn start_category_selection(&mut self) {
-        if !self.daily_usage.is_empty() {
-            let initial_unique_id = self.daily_usage[0].unique_id.clone();
-            self.state = AppState::SelectingCategory { selected_index: 0, selected_unique_id: initial_unique_id };
+        let items = self.selector_snapshot();
+        if let Some(first) = items.first() {
+            let initial_unique_id = first.unique_id.clone();
+            self.state = AppState::SelectingCategory { selected_index: 0, selected_unique_id: initial_unique_id, items };
         }
     }
 
+    fn start_category_rename(&mut self) {
+        let categories: Vec<String> = self.categories.iter().filter(|c| *c != CREATE_CATEGORY_OPTION).cloned().collect();
+        self.state = AppState::SelectingCategoryToRename { selected_index: 0, categories };
+    }
+
+    fn start_rename_category_input(&mut self, old_name: String) {
+        self.state = AppState::Input {
+            prompt: format!("Rename category '{}' to (applies to all sessions and its sub-categories)", old_name),
+            buffer: String::new(),
+            action: InputAction::RenameCategory { old_name },
+        };
+    }
+
     fn start_category_menu(&mut self, unique_id: String) {
         self.state = AppState::CategoryMenu { unique_id, selected_index: 0 };
     }
@@ -965,7 +1775,13 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
             Ok(custom_cats) => {
                 for cat in custom_cats {
                     if !categories.contains(&cat) {
-                        categories.insert(categories.len() - 1, cat);
+                        // Sub-categories are listed after their parent's last entry
+                        let parent = crate::ui::metrics::parent_category(&cat);
+                        let position = categories
+                            .iter()
+                            .rposition(|existing| crate::ui::metrics::parent_category(existing) == parent)
+                            .map_or(categories.len() - 1, |last| last + 1);
+                        categories.insert(position, cat);
                     }
                 }
             }
@@ -978,10 +1794,10 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
         Ok(())
     }
     async fn handle_category_selection(&mut self, app_name: String, category: String) -> Result<()> {
-        if category == "➕ Create New Category" {
+        if category == CREATE_CATEGORY_OPTION {
             // User wants to create custom category
             self.state = AppState::Input {
-                prompt: format!("Enter custom category for '{}' (e.g., 🎮 Gaming)", app_name),
+                prompt: format!("Enter custom category for '{}' (e.g., 🎮 Gaming or 💻 Development/Rust)", app_name),
                 buffer: String::new(),
                 action: InputAction::CreateCategory { app_name },
             };
@@ -1008,70 +1824,178 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
         self.state = AppState::ViewingLogs;
     }
 
+    fn open_settings(&mut self) {
+        self.state = AppState::Settings { fields: SettingsField::from_config(&self.config), selected: 0 };
+    }
+
+    fn save_settings(&mut self) {
+        let fields = if let AppState::Settings { fields, .. } = &self.state {
+            fields.clone()
+        } else {
+            return;
+        };
+
+        let mut config = self.config.clone();
+        for field in fields {
+            match field {
+                SettingsField::AfkThreshold { secs } => config.afk_threshold_secs = secs,
+                SettingsField::RefreshInterval { secs } => config.refresh_interval_secs = secs,
+                SettingsField::DebugLogs { enabled } => config.debug_logs_enabled = enabled,
+                SettingsField::Theme { theme } => config.theme = theme,
+            }
+        }
+
+        match config.save() {
+            Ok(()) => self.logs.push(format!("[{}] Settings saved to config.toml", Local::now().format("%H:%M:%S"))),
+            Err(e) => self.logs.push(format!("[{}] Failed to save settings: {}", Local::now().format("%H:%M:%S"), e)),
+        }
+        if config.debug_logs_enabled != self.config.debug_logs_enabled {
+            self.logs.push(format!("[{}] Debug logging change takes effect after restart", Local::now().format("%H:%M:%S")));
+        }
+
+        // AFK threshold and refresh interval are read by the run loop each iteration
+        crate::ui::theme::set_active(config.theme);
+        crate::daemon::title_trim::set_title_trim_rules(&config.title_trim_rules);
+        self.config = config;
+        self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() };
+    }
+
+    /// AFK threshold in effect for the current session, longer when its category is passive
+    pub fn current_afk_threshold_secs(&self) -> u64 {
+        self.config.afk_threshold_for(self.current_session.as_ref().and_then(|session| session.category.as_deref()))
+    }
+
     pub fn is_afk(&self, threshold_secs: i64) -> bool {
         let last = *self.last_input.lock().unwrap();
         Local::now().signed_duration_since(last).num_seconds() > threshold_secs
     }
 
     pub fn clean_app_name(app_name: &str) -> String {
-        if app_name.starts_with("gnome-") {
-            app_name.strip_prefix("gnome-").unwrap_or(app_name).to_string()
+        if let Some(name) = Self::app_name_from_id(app_name) {
+            return name;
+        }
+        DESKTOP_PREFIXES
+            .iter()
+            .find_map(|prefix| app_name.strip_prefix(prefix))
+            .filter(|name| !name.is_empty())
+            .unwrap_or(app_name)
+            .to_string()
+    }
+
+    /// Friendly name for a reverse-DNS app ID, or None when the name isn't one
+    fn app_name_from_id(app_name: &str) -> Option<String> {
+        let id = app_name.to_lowercase();
+        if let Some((_, name)) = FLATPAK_APP_NAMES.iter().find(|(known_id, _)| *known_id == id) {
+            return Some(name.to_string());
+        }
+
+        let segments: Vec<&str> = id.split('.').collect();
+        let is_reverse_dns = segments.len() >= 3
+            && REVERSE_DNS_ROOTS.contains(&segments[0])
+            && segments.iter().all(|segment| !segment.is_empty());
+        if !is_reverse_dns {
+            return None;
+        }
+        segments[1..]
+            .iter()
+            .rev()
+            .find(|segment| !crate::daemon::tracker::monitor::GENERIC_ID_SEGMENTS.contains(segment))
+            .map(|segment| segment.to_string())
+    }
+
+    /// Category for a new session: a `directory_categories` rule for its directory or project,
+    /// else the rules for its app and window
+    pub fn categorize_session(session: &Session, config: &AppConfig) -> (String, Color) {
+        match config.directory_category(session) {
+            Some(category) => Self::themed_category(category),
+            None => Self::categorize_window(&session.app_name, session.window_name.as_deref()),
+        }
+    }
+
+    /// Categorize using the window title first, so video-call windows are counted as meetings
+    pub fn categorize_window(app: &str, window_name: Option<&str>) -> (String, Color) {
+        if window_name.is_some_and(Self::is_meeting_window) {
+            Self::themed_category("📅 Meetings")
         } else {
-            app_name.to_string()
+            Self::categorize_app(app)
         }
     }
 
+    /// Check if a window title belongs to an active Zoom/Meet/Teams call
+    pub fn is_meeting_window(window_name: &str) -> bool {
+        let title_lower = window_name.to_lowercase();
+        title_lower.contains("zoom meeting") ||
+        title_lower.starts_with("meet - ") || title_lower.contains("google meet") ||
+        (title_lower.contains("teams") && title_lower.contains("meeting")) ||
+        title_lower.contains("jitsi meet") || title_lower.contains("webex meeting")
+    }
+
     pub fn categorize_app(app: &str) -> (String, Color) {
         let app_lower = app.to_lowercase();
-        if app_lower.contains("code") || app_lower.contains("vim") || app_lower.contains("nvim") ||
+        if app == "AFK" {
+            Self::themed_category(AWAY_CATEGORY)
+        } else if app_lower.contains("code") || app_lower.contains("vim") || app_lower.contains("nvim") ||
            app_lower.contains("terminal") || app_lower.contains("alacritty") || app_lower.contains("kitty") ||
            app_lower.contains("rust") || app_lower.contains("cargo") || app_lower.contains("editor") ||
            app_lower.contains("vscode") || app_lower.contains("vscodium") || app_lower.contains("gedit") ||
            app_lower.contains("nano") || app_lower.contains("emacs") || app_lower.contains("atom") ||
            app_lower.contains("sublime") || app_lower.contains("console") || app_lower.contains("iterm") {
-            ("💻 Development".to_string(), Color::Yellow)
+            Self::themed_category("💻 Development")
         } else if app_lower.contains("browser") || app_lower.contains("chrome") || app_lower.contains("firefox") ||
                   app_lower.contains("brave") || app_lower.contains("edge") || app_lower.contains("chromium") {
-            ("🌐 Browsing".to_string(), Color::Blue)
+            Self::themed_category("🌐 Browsing")
         } else if app_lower.contains("slack") || app_lower.contains("zoom") || app_lower.contains("teams") ||
                   app_lower.contains("discord") || app_lower.contains("telegram") || app_lower.contains("chat") ||
                   app_lower.contains("signal") || app_lower.contains("element") || app_lower.contains("video-call") ||
                   app_lower.contains("skype") || app_lower.contains("jitsi") {
-            ("💬 Communication".to_string(), Color::Green)
+            Self::themed_category("💬 Communication")
         } else if app_lower.contains("spotify") || app_lower.contains("vlc") || app_lower.contains("music") ||
                   app_lower.contains("media") || app_lower.contains("rhythmbox") || app_lower.contains("audacious") ||
                   app_lower.contains("clementine") {
-            ("🎵 Media".to_string(), Color::Magenta)
+            Self::themed_category("🎵 Media")
         } else if app_lower.contains("nautilus") || app_lower.contains("files") || app_lower.contains("dolphin") ||
                   app_lower.contains("file-manager") || app_lower.contains("thunar") || app_lower.contains("nemo") {
-            ("📁 Files".to_string(), Color::Cyan)
+            Self::themed_category("📁 Files")
         } else if app_lower.contains("thunderbird") || app_lower.contains("evolution") || app_lower.contains("geary") ||
                   app_lower.contains("email") {
-            ("📧 Email".to_string(), Color::LightYellow)
+            Self::themed_category("📧 Email")
         } else if app_lower.contains("libreoffice") || app_lower.contains("soffice") {
-            ("📄 Office".to_string(), Color::LightBlue)
+            Self::themed_category("📄 Office")
+        } else if app_lower.contains("obsidian") || app_lower.contains("logseq") || app_lower.contains("notion") {
+            Self::themed_category("🗒️ Notes")
         } else {
-            ("📦 Other".to_string(), Color::White)
+            Self::themed_category(OTHER_CATEGORY)
         }
     }
 
+    /// Stored categories keep their name; custom ones get the theme's custom color
     pub fn category_from_string(category: &str) -> (String, Color) {
-        match category {
-            "💻 Development" => ("💻 Development".to_string(), Color::Yellow),
-            "🌐 Browsing" => ("🌐 Browsing".to_string(), Color::Blue),
-            "💬 Communication" => ("💬 Communication".to_string(), Color::Green),
-            "🎵 Media" => ("🎵 Media".to_string(), Color::Magenta),
-            "📁 Files" => ("📁 Files".to_string(), Color::Cyan),
-            "📧 Email" => ("📧 Email".to_string(), Color::LightYellow),
-            "📄 Office" => ("📄 Office".to_string(), Color::LightBlue),
-            _ => {
-                if category == "📦 Other" {
-                    ("📦 Other".to_string(), Color::White)
-                } else {
-                    (category.to_string(), Color::LightMagenta)
-                }
-            }
-        }
+        Self::themed_category(category)
+    }
+
+    /// Category colors come from the active theme palette rather than literals
+    fn themed_category(category: &str) -> (String, Color) {
+        (category.to_string(), crate::ui::theme::category_color(category))
+    }
+
+    /// App name and elapsed seconds of the in-progress session, unless it is an AFK session
+    pub fn live_session_elapsed(&self) -> Option<(&str, i64)> {
+        let session = self.current_session.as_ref().filter(|session| !Self::is_away_session(session))?;
+        let elapsed = Local::now().signed_duration_since(session.start_time).num_seconds().max(0);
+        Some((session.app_name.trim(), elapsed))
+    }
+
+    /// App name and seconds of the in-progress session not yet in the stored usage; `duration`
+    /// holds what the last auto save (or the resumed row) already counted
+    pub fn live_session_unsaved(&self) -> Option<(&str, i64)> {
+        let (live_app, elapsed) = self.live_session_elapsed()?;
+        let saved = self.current_session.as_ref().map_or(0, |session| session.duration);
+        Some((live_app, (elapsed - saved).max(0)))
+    }
+
+    /// AFK and idle sessions can be hidden from the history popup
+    pub fn is_away_session(session: &Session) -> bool {
+        session.is_afk.unwrap_or(false) || session.is_idle.unwrap_or(false)
     }
 
     pub fn get_app_category(&self, app: &str) -> (String, Color) {
@@ -1096,12 +2020,13 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
                 ViewMode::Monthly => self.database.get_monthly_sessions().await?,
             };
         }
+        self.refresh_category_usage().await;
         Ok(())
     }
 
     async fn refresh_all_data(&mut self) -> Result<()> {
         // Refresh ALL usage data
-        self.usage = self.database.get_app_usage().await?;
+        self.usage = self.database.get_app_usage_for_range(self.current_view_mode.clone()).await?;
 
         // Update current_history based on current view mode FIRST
         self.current_history = match &self.current_view_mode {
@@ -1117,32 +2042,380 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
 
         // Create flat usage data for Today's Activity Progress
         self.flat_daily_usage = self.database.get_daily_usage().await?;
+        self.refresh_category_usage().await;
 
         self.history = self.database.get_recent_sessions(30).await?;
         Ok(())
     }
 
+    fn toggle_redact_titles(&mut self) {
+        self.redact_titles = !self.redact_titles;
+        let status = if self.redact_titles { "hidden" } else { "shown" };
+        self.logs.push(format!("[{}] Window titles {}", Local::now().format("%H:%M:%S"), status));
+    }
+
+    /// Copy "app — name — duration" of the running session to the clipboard with OSC 52
+    fn copy_current_session(&mut self) {
+        let Some(session) = &self.current_session else {
+            self.flash = Some(("Nothing to copy".to_string(), Instant::now()));
+            return;
+        };
+        let app_name = self.manual_app_name.clone().unwrap_or_else(|| Self::clean_app_name(&session.app_name));
+        let display_name = session.effective_display_name().map(|(name, _, _)| self.shown_title(&name).to_string());
+        let duration = Local::now().signed_duration_since(session.start_time).num_seconds();
+        let summary = commands::session_summary(&app_name, display_name.as_deref(), duration);
+
+        let mut stdout = io::stdout();
+        let copied = stdout.write_all(commands::osc52_sequence(&summary).as_bytes()).and_then(|_| stdout.flush());
+        let message = match copied {
+            Ok(()) => {
+                self.logs.push(format!("[{}] Copied to clipboard: {}", Local::now().format("%H:%M:%S"), summary));
+                "📋 Copied!".to_string()
+            }
+            Err(e) => {
+                log::warn!("Failed to copy the session to the clipboard: {}", e);
+                "Copy failed".to_string()
+            }
+        };
+        self.flash = Some((message, Instant::now()));
+    }
+
+    /// The status bar confirmation, while it's still recent
+    pub fn flash_message(&self) -> Option<&str> {
+        self.flash
+            .as_ref()
+            .filter(|(_, set_at)| set_at.elapsed() < Duration::from_secs(FLASH_SECS))
+            .map(|(message, _)| message.as_str())
+    }
+
+    /// Title as displayed: the placeholder while titles are redacted
+    pub fn shown_title<'a>(&self, title: &'a str) -> &'a str {
+        if self.redact_titles { crate::ui::hierarchical::REDACTED } else { title }
+    }
+
+    /// Usage rows as displayed: sub-entry titles are hidden while titles are redacted
+    pub fn shown_items(&self, items: &[HierarchicalDisplayItem]) -> Vec<HierarchicalDisplayItem> {
+        if self.redact_titles { crate::ui::hierarchical::redact_sub_entries(items) } else { items.to_vec() }
+    }
+
+    /// Breakdown rows as displayed: names are hidden while titles are redacted
+    pub fn shown_breakdown(&self, items: &[(String, i64)]) -> Vec<(String, i64)> {
+        if self.redact_titles { crate::ui::hierarchical::redact_breakdown(items) } else { items.to_vec() }
+    }
+
+    /// Opens the breakdown dashboard, loading the range's sessions and aggregating them first
+    async fn open_breakdown(&mut self, view_mode: ViewMode) {
+        // Load current_history first (filtered by view mode)
+        self.current_history = match view_mode {
+            ViewMode::Daily => self.database.get_daily_sessions().await.unwrap_or_default(),
+            ViewMode::Weekly => self.database.get_weekly_sessions().await.unwrap_or_default(),
+            ViewMode::Monthly => self.database.get_monthly_sessions().await.unwrap_or_default(),
+        };
+        // Then aggregate breakdown data from current_history
+        self.load_breakdown_data_from_history();
+        self.refresh_category_usage().await;
+        self.refresh_notification_trend().await;
+        self.state = AppState::BreakdownDashboard {
+            view_mode,
+            selected_panel: self.last_breakdown_panel,
+            panel_scrolls: [0; 5],
+        };
+    }
+
+    /// Opens the history popup on the first page of the range; later pages load as the user scrolls
+    async fn open_history(&mut self, view_mode: ViewMode) {
+        self.history_page = match self.database.get_sessions_paged(view_mode.clone(), None, HISTORY_PAGE_SIZE).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                log::warn!("Failed to load session history: {}", e);
+                Vec::new()
+            }
+        };
+        self.history_has_more = self.history_page.len() as i64 == HISTORY_PAGE_SIZE;
+        self.state = AppState::HistoryPopup { view_mode, scroll_position: 0, hide_afk: self.config.history_hide_afk, merge_same_app: false, date: None };
+    }
+
+    /// Shows a single day's sessions in the history popup; the whole day loads at once
+    async fn open_history_day(&mut self, view_mode: ViewMode, date: chrono::NaiveDate, hide_afk: bool, merge_same_app: bool) {
+        self.history_page = match self.database.get_sessions_on(date).await {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                log::warn!("Failed to load sessions for {}: {}", date, e);
+                Vec::new()
+            }
+        };
+        self.history_has_more = false;
+        self.state = AppState::HistoryPopup { view_mode, scroll_position: 0, hide_afk, merge_same_app, date: Some(date) };
+    }
+
+    /// Appends the next history page when the popup is scrolled near the end of what's loaded
+    async fn load_more_history(&mut self) {
+        let AppState::HistoryPopup { view_mode, scroll_position, hide_afk, merge_same_app, .. } = &self.state else {
+            return;
+        };
+        let visible_count = crate::ui::render::history_filtered(&self.history_page, *hide_afk, *merge_same_app).len();
+        if !Self::needs_history_page(self.history_has_more, visible_count, *scroll_position) {
+            return;
+        }
+        let view_mode = view_mode.clone();
+        let after = self.history_page.last().and_then(|session| Some((session.start_time, session.id?)));
+        match self.database.get_sessions_paged(view_mode, after, HISTORY_PAGE_SIZE).await {
+            Ok(sessions) => {
+                self.history_has_more = sessions.len() as i64 == HISTORY_PAGE_SIZE;
+                self.history_page.extend(sessions);
+            }
+            Err(e) => {
+                log::warn!("Failed to load more session history: {}", e);
+                self.history_has_more = false;
+            }
+        }
+    }
+
+    fn needs_history_page(has_more: bool, visible_count: usize, scroll_position: usize) -> bool {
+        has_more && visible_count.saturating_sub(scroll_position) <= HISTORY_PREFETCH_ROWS
+    }
+
+    async fn open_weekday_averages(&mut self) {
+        self.logs.push(format!("[{}] Opening weekday averages", Local::now().format("%H:%M:%S")));
+        match self.database.get_weekday_averages(WEEKDAY_WEEKS).await {
+            Ok(averages) => self.weekday_averages = averages,
+            Err(e) => log::warn!("Failed to load weekday averages: {}", e),
+        }
+        self.state = AppState::WeekdayAverages;
+    }
+
+    async fn open_weekly_totals(&mut self) {
+        self.logs.push(format!("[{}] Opening weekly totals", Local::now().format("%H:%M:%S")));
+        match self.database.get_weekly_totals(TREND_WEEKS).await {
+            Ok(weeks) => self.weekly_totals = weeks,
+            Err(e) => log::warn!("Failed to load weekly totals: {}", e),
+        }
+        self.state = AppState::WeeklyTotals;
+    }
+
+    async fn open_category_days(&mut self) {
+        self.logs.push(format!("[{}] Opening category mix per day", Local::now().format("%H:%M:%S")));
+        match self.database.get_category_usage_by_day(CATEGORY_DAYS).await {
+            Ok(days) => self.category_days = days,
+            Err(e) => log::warn!("Failed to load category usage by day: {}", e),
+        }
+        self.state = AppState::CategoryDays;
+    }
+
+    /// Top files and per-language editing time over the week, independent of the dashboard view mode
+    async fn open_coding_stats(&mut self) {
+        self.logs.push(format!("[{}] Opening coding stats", Local::now().format("%H:%M:%S")));
+        let sessions = self.database.get_weekly_sessions().await.unwrap_or_default();
+
+        let mut files: Vec<(String, i64)> = crate::ui::hierarchical::create_file_breakdown(&sessions)
+            .into_iter()
+            .map(|(filename, language, duration)| (format!("{} [{}]", filename, language), duration))
+            .collect();
+        files.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+        self.weekly_top_files = files;
+        self.weekly_languages = crate::ui::hierarchical::create_language_breakdown(&sessions);
+        self.state = AppState::CodingStats;
+    }
+
     fn load_breakdown_data_from_history(&mut self) {
         // Use hierarchical module to create all breakdown data from current_history
-        self.browser_breakdown = crate::ui::hierarchical::create_browser_breakdown(&self.current_history);
+        self.browser_breakdown = crate::ui::hierarchical::create_browser_breakdown(&self.current_history, self.config.browser_group_by_profile);
         self.project_breakdown = crate::ui::hierarchical::create_project_breakdown(&self.current_history);
         self.file_breakdown = crate::ui::hierarchical::create_file_breakdown(&self.current_history);
         self.terminal_breakdown = crate::ui::hierarchical::create_terminal_breakdown(&self.current_history);
+    }
+
+    /// Category totals backing both the pie chart and the category breakdown.
+    /// Stored categories are summed in the database; only uncategorized sessions are classified here.
+    async fn refresh_category_usage(&mut self) {
+        let stored = match self.database.get_category_usage(self.current_view_mode.clone()).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                log::warn!("Failed to load category usage: {}", e);
+                vec![]
+            }
+        };
 
-        // Category breakdown - exclude AFK sessions
         let mut category_map: BTreeMap<String, i64> = BTreeMap::new();
-        for session in &self.current_history {
-            // Skip AFK sessions
-            if session.is_afk.unwrap_or(false) {
-                continue;
+        let uncounted = self.current_history
+            .iter()
+            // Skip AFK sessions and anything already counted by the database
+            .filter(|session| !session.is_afk.unwrap_or(false) && session.category.is_none())
+            .map(|session| (self.get_app_category(&session.app_name).0, session.duration));
+        for (category, duration) in stored.into_iter().chain(uncounted) {
+            let category = if self.collapse_subcategories {
+                crate::ui::metrics::parent_category(&category).to_string()
+            } else {
+                category
+            };
+            *category_map.entry(category).or_insert(0) += duration;
+        }
+
+        self.category_breakdown = crate::ui::metrics::sort_categories(category_map, &self.config.pinned_categories);
+    }
+
+    /// Consecutive days with non-AFK activity, counting the live session towards today.
+    async fn refresh_streak(&mut self) {
+        let mut active_days = match self.database.get_active_days(366).await {
+            Ok(days) => days,
+            Err(e) => {
+                log::warn!("Failed to load active days: {}", e);
+                vec![]
             }
+        };
+
+        let today = Local::now().date_naive();
+        if self.current_session.as_ref().is_some_and(|session| !Self::is_away_session(session)) {
+            active_days.push(today);
+        }
+        self.streak_days = crate::ui::metrics::compute_streak(&active_days, today);
+    }
 
-            if let Some(category) = &session.category {
-                *category_map.entry(category.clone()).or_insert(0) += session.duration;
+    /// Estimated billable amount for today including the live session, if a rate is configured
+    pub fn billable_today_amount(&self) -> Option<f64> {
+        if self.config.hourly_rate <= 0.0 {
+            return None;
+        }
+        let mut usage = self.billable_today.clone();
+        let live_category = self.current_session.as_ref()
+            .filter(|session| !Self::is_away_session(session))
+            .and_then(|session| session.category.clone().map(|category| (session, category)));
+        if let Some((session, category)) = live_category {
+            usage.push((category, Local::now().signed_duration_since(session.start_time).num_seconds().max(0)));
+        }
+        Some(crate::ui::metrics::billable_amount(&usage, &self.config.billable_categories, self.config.hourly_rate))
+    }
+
+    pub fn billable_today_label(&self) -> Option<String> {
+        self.billable_today_amount()
+            .map(|amount| format!("{}{:.2}", self.config.currency_symbol, amount))
+    }
+
+    async fn refresh_billable_today(&mut self) {
+        if self.config.hourly_rate <= 0.0 {
+            return;
+        }
+        match self.database.get_category_usage(ViewMode::Daily).await {
+            Ok(rows) => self.billable_today = rows,
+            Err(e) => log::warn!("Failed to load today's category usage: {}", e),
+        }
+    }
+
+    async fn refresh_busiest_project(&mut self) {
+        match self.database.get_busiest_project_this_week().await {
+            Ok(busiest) => self.busiest_project = busiest,
+            Err(e) => log::warn!("Failed to load this week's busiest project: {}", e),
+        }
+    }
+
+    /// Project names are hidden while titles are redacted
+    pub fn busiest_project_label(&self) -> Option<String> {
+        self.busiest_project.as_ref().map(|(name, secs)| {
+            crate::ui::metrics::busiest_project_label(self.shown_title(&App::clean_app_name(name)), *secs, self.rounding_minutes)
+        })
+    }
+
+    async fn refresh_hostname_usage(&mut self) {
+        match self.database.get_usage_by_hostname(self.current_view_mode.clone()).await {
+            Ok(usage) => self.hostname_usage = usage,
+            Err(e) => log::warn!("Failed to load usage by hostname: {}", e),
+        }
+    }
+
+    async fn refresh_daemon_heartbeat(&mut self) {
+        match self.database.get_daemon_heartbeat().await {
+            Ok(last_seen) => self.daemon_last_seen = last_seen,
+            Err(e) => log::warn!("Failed to load daemon heartbeat: {}", e),
+        }
+    }
+
+    async fn refresh_app_limit_alerts(&mut self) {
+        if !self.config.app_limit_banner {
+            self.app_limit_alerts.clear();
+            return;
+        }
+        match self.database.get_app_limit_alerts().await {
+            Ok(alerts) => self.app_limit_alerts = alerts,
+            Err(e) => log::warn!("Failed to load app limit alerts: {}", e),
+        }
+    }
+
+    pub fn app_limit_banner(&self) -> Option<String> {
+        crate::ui::metrics::app_limit_banner(&self.app_limit_alerts, self.rounding_minutes)
+    }
+
+    pub fn daemon_warning(&self) -> Option<String> {
+        crate::ui::metrics::daemon_warning(self.daemon_last_seen, Local::now(), self.config.daemon_stale_secs)
+    }
+
+    pub fn hostname_usage_label(&self) -> Option<String> {
+        crate::ui::metrics::hostname_usage_label(&self.hostname_usage, self.rounding_minutes)
+    }
+
+    async fn refresh_other_app_usage(&mut self) {
+        match self.database.get_app_usage_for_category(self.current_view_mode.clone(), OTHER_CATEGORY).await {
+            Ok(usage) => self.other_app_usage = usage,
+            Err(e) => log::warn!("Failed to load Other app usage: {}", e),
+        }
+    }
+
+    async fn refresh_away_usage(&mut self) {
+        if !self.show_away_usage {
+            return;
+        }
+        match self.database.get_app_usage_with_afk(self.current_view_mode.clone()).await {
+            Ok(usage) => {
+                self.away_usage = usage.into_iter().filter(|(name, _)| name == AFK_PSEUDO_APP || name == IDLE_PSEUDO_APP).collect();
+            }
+            Err(e) => log::warn!("Failed to load AFK usage: {}", e),
+        }
+    }
+
+    async fn toggle_away_usage(&mut self) {
+        self.show_away_usage = !self.show_away_usage;
+        self.refresh_away_usage().await;
+        let status = if self.show_away_usage { "shown" } else { "hidden" };
+        self.logs.push(format!("[{}] AFK time in usage {}", Local::now().format("%H:%M:%S"), status));
+    }
+
+    /// AFK and Idle bars for the dashboard, the in-progress away session included; empty while hidden
+    pub fn away_usage_items(&self) -> Vec<HierarchicalDisplayItem> {
+        if !self.show_away_usage {
+            return vec![];
+        }
+        let live = self.current_session.as_ref().filter(|session| Self::is_away_session(session)).map(|session| {
+            let name = if session.is_idle.unwrap_or(false) { IDLE_PSEUDO_APP } else { AFK_PSEUDO_APP };
+            (name, Local::now().signed_duration_since(session.start_time).num_seconds().max(0))
+        });
+        crate::ui::metrics::away_usage_items(&self.away_usage, live)
+    }
+
+    async fn refresh_afk_stats(&mut self) {
+        match self.database.get_afk_stats(self.current_view_mode.clone()).await {
+            Ok(stats) => self.afk_stats = stats,
+            Err(e) => log::warn!("Failed to load AFK stats: {}", e),
+        }
+    }
+
+    /// Hint naming the top Other apps when they take more than other_hint_percent of `total_secs`
+    pub fn other_hint(&self, total_secs: i64) -> Option<String> {
+        crate::ui::metrics::other_hint(&self.other_app_usage, total_secs, self.config.other_hint_percent, self.rounding_minutes)
+    }
+
+    async fn refresh_switches_per_hour(&mut self) {
+        match self.database.get_switches_per_hour(SWITCHES_DAYS, self.config.switches_coalesce_same_app).await {
+            Ok(per_hour) => self.switches_per_hour = per_hour,
+            Err(e) => log::warn!("Failed to load switches per hour: {}", e),
+        }
+    }
+
+    async fn refresh_notification_trend(&mut self) {
+        match self.database.get_notification_trend(NOTIFICATION_TREND_HOURS).await {
+            Ok(points) => {
+                self.notification_trend = crate::ui::metrics::notification_trend(&points, Local::now(), NOTIFICATION_TREND_HOURS);
             }
+            Err(e) => log::warn!("Failed to load notification trend: {}", e),
         }
-        self.category_breakdown = category_map.into_iter().collect();
-        self.category_breakdown.sort_by(|a, b| b.1.cmp(&a.1));
     }
 
     async fn handle_input(&mut self) -> Result<()> {
@@ -1188,9 +2461,114 @@ AppState::BreakdownDashboard { view_mode, selected_panel, panel_scrolls } => {
 
                 self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() };
             }
+            InputAction::RenameCategory { old_name } => {
+                let mut ctx = CommandContext {
+                    database: &self.database,
+                    current_session: &mut self.current_session,
+                    logs: &mut self.logs,
+                };
+
+                let result = commands::execute_rename_category(&mut ctx, &old_name, &buffer).await?;
+
+                if result.should_refresh {
+                    if commands::rename_category_in_config(&mut self.config, &old_name, buffer.trim()) {
+                        match self.config.save() {
+                            Ok(()) => self.logs.push(format!("[{}] Updated config.toml entries for '{}'", Local::now().format("%H:%M:%S"), old_name)),
+                            Err(e) => self.logs.push(format!("[{}] Failed to save config.toml: {}", Local::now().format("%H:%M:%S"), e)),
+                        }
+                    }
+                    self.refresh_all_data().await?;
+                    self.refresh_categories().await?;
+                }
+
+                self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() };
+            }
+            InputAction::SplitSession { session_id, start_time } => {
+                let mut ctx = CommandContext {
+                    database: &self.database,
+                    current_session: &mut self.current_session,
+                    logs: &mut self.logs,
+                };
+
+                let result = commands::execute_split_session(&mut ctx, session_id, start_time, &buffer).await?;
+
+                if result.should_refresh {
+                    self.refresh_all_data().await?;
+                }
+
+                // Back to the history so the result is visible
+                self.open_history(self.current_view_mode.clone()).await;
+            }
+            InputAction::TagSession { session_id, sticky } => {
+                let tag = commands::parse_tag(&buffer);
+                let mut ctx = CommandContext {
+                    database: &self.database,
+                    current_session: &mut self.current_session,
+                    logs: &mut self.logs,
+                };
+
+                let result = commands::execute_tag_session(&mut ctx, session_id, tag.clone()).await?;
+
+                if sticky {
+                    self.sticky_tag = tag.clone();
+                    self.send_sticky_tag_to_daemon().await;
+                }
+
+                if session_id.is_some() {
+                    if result.should_refresh {
+                        self.refresh_all_data().await?;
+                    }
+                    self.open_history(self.current_view_mode.clone()).await;
+                } else {
+                    self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() };
+                }
+            }
+            InputAction::NoteSession { session_id, history } => {
+                let note = commands::parse_note(&buffer);
+                match self.database.set_session_note(session_id, note.as_deref()).await {
+                    Ok(()) => {
+                        self.logs.push(format!("[{}] Note on session {}: {}", Local::now().format("%H:%M:%S"), session_id, note.as_deref().unwrap_or("(none)")));
+                        // Update the loaded page in place so the popup comes back where it was
+                        if let Some(session) = self.history_page.iter_mut().find(|session| session.id == Some(session_id)) {
+                            session.note = note;
+                        }
+                    }
+                    Err(e) => self.logs.push(format!("[{}] Failed to save note on session {}: {}", Local::now().format("%H:%M:%S"), session_id, e)),
+                }
+                self.state = *history;
+            }
+            InputAction::MarkAway => {
+                let elapsed = self.live_session_elapsed().map_or(0, |(_, elapsed)| elapsed);
+                match commands::parse_away_minutes(&buffer, elapsed) {
+                    Ok(minutes) => self.mark_away(minutes).await?,
+                    Err(e) => self.logs.push(format!("[{}] {}", Local::now().format("%H:%M:%S"), e)),
+                }
+                self.state = AppState::Dashboard { view_mode: self.current_view_mode.clone() };
+            }
+            InputAction::Search { view_mode } => self.search(view_mode, &buffer).await,
+            InputAction::JumpToDate { view_mode, hide_afk, merge_same_app } => {
+                match commands::parse_history_date(&buffer, Local::now().date_naive()) {
+                    Ok(date) => self.open_history_day(view_mode, date, hide_afk, merge_same_app).await,
+                    Err(e) => {
+                        self.logs.push(format!("[{}] {}", Local::now().format("%H:%M:%S"), e));
+                        self.open_history(view_mode).await;
+                    }
+                }
+            }
         }
         Ok(())
     }
+
+    /// In daemon mode the daemon records the sessions, so it needs the sticky tag too
+    async fn send_sticky_tag_to_daemon(&mut self) {
+        let Some(path) = &self.config.ipc_socket_path else {
+            return;
+        };
+        match crate::daemon::ipc::send_tag(std::path::Path::new(path), self.sticky_tag.as_deref()).await {
+            Ok(()) => self.logs.push(format!("[{}] Sent sticky tag to the daemon", Local::now().format("%H:%M:%S"))),
+            Err(e) => log::debug!("Daemon not reachable for sticky tag: {}", e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1231,4 +2609,68 @@ mod tests {
 
         println!("UI AFK session creation logic test passed");
     }
+
+    #[test]
+    fn test_meeting_window_categorization() {
+        assert_eq!(App::categorize_window("zoom", Some("Zoom Meeting")).0, "📅 Meetings");
+        assert_eq!(App::categorize_window("firefox", Some("Meet - abc-defg-hij")).0, "📅 Meetings");
+        assert_eq!(App::categorize_window("teams", Some("Microsoft Teams | Meeting")).0, "📅 Meetings");
+
+        // Chat windows of the same apps stay in Communication
+        assert_eq!(App::categorize_window("zoom", Some("Zoom Workplace")).0, "💬 Communication");
+        assert_eq!(App::categorize_window("slack", Some("general - Slack")).0, "💬 Communication");
+        assert_eq!(App::categorize_window("discord", None).0, "💬 Communication");
+
+        assert_eq!(App::category_from_string("📅 Meetings").1, Color::LightGreen);
+    }
+
+    #[test]
+    fn test_categorize_session_by_directory() {
+        let session = |app: &str| Session::for_test(app, Local::now(), 0);
+        let mut config = AppConfig::default();
+        let infra = Session {
+            terminal_directory: Some("/srv/infra/ansible".to_string()),
+            terminal_project_name: Some("ansible".to_string()),
+            ..session("alacritty")
+        };
+        // No rules: the app decides
+        assert_eq!(App::categorize_session(&infra, &config).0, "💻 Development");
+
+        config.directory_categories = BTreeMap::from([
+            ("/srv/infra/".to_string(), "⚙️ Ops".to_string()),
+            ("billing".to_string(), "💰 Billing".to_string()),
+        ]);
+        assert_eq!(App::categorize_session(&infra, &config).0, "⚙️ Ops");
+        assert_eq!(App::categorize_session(&Session { ide_project_name: Some("billing".to_string()), ..session("code") }, &config).0, "💰 Billing");
+        // Sessions without a matching directory or project fall back to app and window rules
+        assert_eq!(App::categorize_session(&Session { window_name: Some("Zoom Meeting".to_string()), ..session("zoom") }, &config).0, "📅 Meetings");
+    }
+
+    #[test]
+    fn test_clean_app_name() {
+        assert_eq!(App::clean_app_name("com.spotify.Client"), "spotify");
+        assert_eq!(App::clean_app_name("org.gnome.Nautilus"), "files");
+        assert_eq!(App::clean_app_name("org.mozilla.firefox"), "firefox");
+        assert_eq!(App::clean_app_name("org.telegram.desktop"), "telegram");
+        assert_eq!(App::clean_app_name("com.visualstudio.code"), "vscode");
+        assert_eq!(App::clean_app_name("gnome-calculator"), "calculator");
+        assert_eq!(App::clean_app_name("xfce4-terminal"), "terminal");
+        // Plain names and non-ID dotted names are left alone
+        assert_eq!(App::clean_app_name("firefox"), "firefox");
+        assert_eq!(App::clean_app_name("soffice.bin"), "soffice.bin");
+        assert_eq!(App::clean_app_name("gnome-"), "gnome-");
+    }
+
+    #[test]
+    fn test_history_page_prefetch() {
+        // Far from the end of the loaded sessions: nothing to fetch
+        assert!(!App::needs_history_page(true, 100, 10));
+        // Close to the end with more in the database: fetch
+        assert!(App::needs_history_page(true, 100, 80));
+        // Everything already loaded
+        assert!(!App::needs_history_page(false, 100, 99));
+        // AFK filter hid most of the page
+        assert!(App::needs_history_page(true, 5, 0));
+    }
+
 }
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use chrono::Local;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
+use crate::config::settings::AppConfig;
 use crate::database::connection::Database;
 use crate::models::session::Session;
 
@@ -29,6 +30,186 @@ impl CommandResult {
     }
 }
 
+/// Parse split input "HH:MM[:SS] [new app]" for a session that started at `session_start`.
+/// The time is taken on the session's start day, or the next day for sessions running past midnight.
+pub fn parse_split_input(input: &str, session_start: DateTime<Local>) -> Result<(DateTime<Local>, Option<String>)> {
+    let input = input.trim();
+    let (time_str, new_app) = match input.split_once(char::is_whitespace) {
+        Some((time, app)) => (time, Some(app.trim().to_string())),
+        None => (input, None),
+    };
+    let time = NaiveTime::parse_from_str(time_str, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time_str, "%H:%M"))
+        .map_err(|_| anyhow::anyhow!("Invalid time '{}', expected HH:MM or HH:MM:SS", time_str))?;
+
+    let mut at = session_start
+        .date_naive()
+        .and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| anyhow::anyhow!("{} does not exist in the local timezone", time_str))?;
+    if at < session_start {
+        at += chrono::Duration::days(1);
+    }
+    Ok((at, new_app.filter(|app| !app.is_empty())))
+}
+
+/// Split session command - ends a session at the entered time and starts a new one from there
+pub async fn execute_split_session(
+    ctx: &mut CommandContext<'_>,
+    session_id: i32,
+    session_start: DateTime<Local>,
+    input: &str,
+) -> Result<CommandResult> {
+    if input.trim().is_empty() {
+        return Ok(CommandResult::success_no_refresh());
+    }
+
+    let result = match parse_split_input(input, session_start) {
+        Ok((at, new_app)) => ctx.database.split_session(session_id, at, new_app).await.map(|new_id| (at, new_id)),
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok((at, new_id)) => {
+            ctx.logs.push(format!("[{}] Split session {} at {} (new session {})", Local::now().format("%H:%M:%S"), session_id, at.format("%H:%M:%S"), new_id));
+            Ok(CommandResult::success_with_refresh())
+        }
+        Err(e) => {
+            ctx.logs.push(format!("[{}] Failed to split session {}: {}", Local::now().format("%H:%M:%S"), session_id, e));
+            Ok(CommandResult::success_no_refresh())
+        }
+    }
+}
+
+/// Day to show in the history popup: YYYY-MM-DD, MM-DD (this year), "today", "yesterday",
+/// or a weekday name for its most recent occurrence before today ("tue" is last Tuesday)
+pub fn parse_history_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    let date = match input.as_str() {
+        "today" => Some(today),
+        "yesterday" => today.pred_opt(),
+        _ => match input.parse::<chrono::Weekday>() {
+            Ok(weekday) => {
+                let days_back = (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+                today.checked_sub_days(chrono::Days::new(if days_back == 0 { 7 } else { days_back as u64 }))
+            }
+            Err(_) => NaiveDate::parse_from_str(&input, "%Y-%m-%d")
+                .or_else(|_| NaiveDate::parse_from_str(&format!("{}-{}", today.year(), input), "%Y-%m-%d"))
+                .ok(),
+        },
+    };
+    let date = date.ok_or_else(|| anyhow::anyhow!("Invalid date '{}', expected YYYY-MM-DD, MM-DD, today, yesterday or a weekday", input))?;
+    if date > today {
+        return Err(anyhow::anyhow!("{} is in the future", date));
+    }
+    Ok(date)
+}
+
+/// Minutes to turn into AFK time from the end of the running session, which has run `elapsed_secs`
+pub fn parse_away_minutes(input: &str, elapsed_secs: i64) -> Result<i64> {
+    let minutes: i64 = input
+        .trim()
+        .parse()
+        .ok()
+        .filter(|minutes| *minutes > 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid number of minutes '{}'", input.trim()))?;
+    if minutes * 60 > elapsed_secs {
+        return Err(anyhow::anyhow!("The current session has only run for {} minutes", elapsed_secs / 60));
+    }
+    Ok(minutes)
+}
+
+/// Tag from the input line; empty clears it
+pub fn parse_tag(input: &str) -> Option<String> {
+    let tag = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Note from the input line, trimmed; empty removes it
+pub fn parse_note(input: &str) -> Option<String> {
+    let note = input.trim();
+    (!note.is_empty()).then(|| note.to_string())
+}
+
+/// ILIKE pattern matching the search term anywhere in a column, with `%`, `_` and `\` in the
+/// term matched literally
+pub fn search_pattern(input: &str) -> Result<String> {
+    let term = input.trim();
+    if term.is_empty() {
+        return Err(anyhow::anyhow!("Enter something to search for"));
+    }
+    let escaped = term.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+    Ok(format!("%{}%", escaped))
+}
+
+/// "app — name — duration" for pasting into an external time log; the name is left out when the
+/// session has nothing more specific than its app
+pub fn session_summary(app_name: &str, display_name: Option<&str>, duration_secs: i64) -> String {
+    let duration = crate::ui::render::format_duration(duration_secs, 0).replace(' ', "");
+    match display_name.map(str::trim).filter(|name| !name.is_empty()) {
+        Some(name) => format!("{} — {} — {}", app_name, name, duration),
+        None => format!("{} — {}", app_name, duration),
+    }
+}
+
+/// OSC 52 escape sequence asking the terminal to put `text` on the system clipboard. Works over
+/// SSH and needs no clipboard library; terminals that don't support it ignore it
+pub fn osc52_sequence(text: &str) -> String {
+    use base64::Engine;
+    format!("\x1b]52;c;{}\x07", base64::engine::general_purpose::STANDARD.encode(text))
+}
+
+/// Tag a saved session by id, or the running session (saved with its tag when it ends) when None
+pub async fn execute_tag_session(ctx: &mut CommandContext<'_>, session_id: Option<i32>, tag: Option<String>) -> Result<CommandResult> {
+    let label = tag.as_deref().unwrap_or("(none)");
+    match session_id {
+        Some(id) => match ctx.database.set_session_tag(id, tag.as_deref()).await {
+            Ok(()) => {
+                ctx.logs.push(format!("[{}] Tagged session {}: {}", Local::now().format("%H:%M:%S"), id, label));
+                Ok(CommandResult::success_with_refresh())
+            }
+            Err(e) => {
+                ctx.logs.push(format!("[{}] Failed to tag session {}: {}", Local::now().format("%H:%M:%S"), id, e));
+                Ok(CommandResult::success_no_refresh())
+            }
+        },
+        None => {
+            if let Some(session) = ctx.current_session.as_mut() {
+                session.tag = tag.clone();
+                ctx.logs.push(format!("[{}] Tagged current session: {}", Local::now().format("%H:%M:%S"), label));
+            }
+            Ok(CommandResult::success_no_refresh())
+        }
+    }
+}
+
+/// Point config entries naming a renamed category (or its sub-categories) at the new name, so limits,
+/// pins, billable and passive status and category overrides keep applying. Returns whether any changed
+pub fn rename_category_in_config(config: &mut AppConfig, old_name: &str, new_name: &str) -> bool {
+    let mut changed = false;
+    let mut rename = |category: &mut String| {
+        if let Some(renamed) = crate::ui::metrics::renamed_category(category, old_name, new_name) {
+            *category = renamed;
+            changed = true;
+        }
+    };
+
+    for categories in [&mut config.pinned_categories, &mut config.billable_categories, &mut config.passive_categories] {
+        categories.iter_mut().for_each(&mut rename);
+    }
+    config.directory_categories.values_mut().for_each(&mut rename);
+    config.fullscreen_category.iter_mut().for_each(&mut rename);
+    config.dnd_category.iter_mut().for_each(&mut rename);
+    config.category_limits_minutes = std::mem::take(&mut config.category_limits_minutes)
+        .into_iter()
+        .map(|(mut category, minutes)| {
+            rename(&mut category);
+            (category, minutes)
+        })
+        .collect();
+    changed
+}
+
 /// Get predefined category options
 pub fn get_category_options() -> Vec<String> {
     vec![
@@ -39,6 +220,8 @@ pub fn get_category_options() -> Vec<String> {
         "\u{1F4C1} Files".to_string(),
         "\u{1F4E7} Email".to_string(),
         "\u{1F4C4} Office".to_string(),
+        "\u{1F4C5} Meetings".to_string(),
+        "\u{1F5D2}\u{FE0F} Notes".to_string(),
         "\u{1F4E6} Other".to_string(),
         "\u{2795} Create New Category".to_string(),
     ]
@@ -192,6 +375,63 @@ pub async fn execute_update_category(
     Ok(CommandResult::success_with_refresh())
 }
 
+/// Rename a category across all sessions, its sub-categories included
+pub async fn execute_rename_category(
+    ctx: &mut CommandContext<'_>,
+    old_name: &str,
+    new_name: &str,
+) -> Result<CommandResult> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        ctx.logs.push(format!("[{}] Category name can't be empty", Local::now().format("%H:%M:%S")));
+        return Ok(CommandResult::success_no_refresh());
+    }
+    if new_name == old_name {
+        return Ok(CommandResult::success_no_refresh());
+    }
+    if crate::database::connection::is_default_category(new_name) {
+        ctx.logs.push(format!(
+            "[{}] '{}' is a built-in category; '{}' sessions will be merged into it",
+            Local::now().format("%H:%M:%S"),
+            new_name,
+            old_name
+        ));
+    }
+
+    let changed = match ctx.database.rename_category(old_name, new_name).await {
+        Ok(changed) => changed,
+        Err(e) => {
+            ctx.logs.push(format!("[{}] Failed to rename category {}: {}", Local::now().format("%H:%M:%S"), old_name, e));
+            return Ok(CommandResult::success_no_refresh());
+        }
+    };
+
+    // Update current session if it matches
+    if let Some(session) = ctx.current_session {
+        for category in [
+            &mut session.category,
+            &mut session.browser_page_title_category,
+            &mut session.terminal_directory_category,
+            &mut session.editor_filename_category,
+            &mut session.tmux_window_name_category,
+        ] {
+            if let Some(renamed) = category.as_deref().and_then(|c| crate::ui::metrics::renamed_category(c, old_name, new_name)) {
+                *category = Some(renamed);
+            }
+        }
+    }
+
+    ctx.logs.push(format!(
+        "[{}] Renamed category '{}' to '{}' ({} sessions)",
+        Local::now().format("%H:%M:%S"),
+        old_name,
+        new_name,
+        changed
+    ));
+
+    Ok(CommandResult::success_with_refresh())
+}
+
 /// Create and apply custom category command
 pub async fn execute_create_category(
     ctx: &mut CommandContext<'_>,
@@ -215,3 +455,107 @@ pub async fn execute_create_category(
 
     Ok(CommandResult::success_with_refresh())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_category_in_config() {
+        let mut config = AppConfig {
+            pinned_categories: vec!["💻 Development".to_string()],
+            billable_categories: vec!["💻 Development".to_string(), "📅 Meetings".to_string()],
+            fullscreen_category: Some("🎵 Media".to_string()),
+            directory_categories: std::collections::BTreeMap::from([("~/work".to_string(), "💻 Development/Rust".to_string())]),
+            category_limits_minutes: std::collections::BTreeMap::from([("💻 Development".to_string(), 480)]),
+            ..AppConfig::default()
+        };
+        assert!(rename_category_in_config(&mut config, "💻 Development", "🛠️ Engineering"));
+        assert_eq!(config.pinned_categories, vec!["🛠️ Engineering"]);
+        assert_eq!(config.billable_categories, vec!["🛠️ Engineering", "📅 Meetings"]);
+        assert_eq!(config.directory_categories["~/work"], "🛠️ Engineering/Rust");
+        assert_eq!(config.category_limits_minutes.get("🛠️ Engineering"), Some(&480));
+        assert!(!config.category_limits_minutes.contains_key("💻 Development"));
+        assert_eq!(config.fullscreen_category.as_deref(), Some("🎵 Media"));
+
+        assert!(!rename_category_in_config(&mut config, "🎮 Gaming", "🕹️ Games"));
+    }
+
+    #[test]
+    fn test_parse_split_input() {
+        use chrono::TimeZone;
+        let start = Local.with_ymd_and_hms(2025, 3, 10, 22, 15, 0).unwrap();
+
+        let (at, new_app) = parse_split_input("23:40 slack", start).unwrap();
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 3, 10, 23, 40, 0).unwrap());
+        assert_eq!(new_app.as_deref(), Some("slack"));
+
+        // Earlier than the start: the session ran past midnight
+        let (at, new_app) = parse_split_input("00:05:30", start).unwrap();
+        assert_eq!(at, Local.with_ymd_and_hms(2025, 3, 11, 0, 5, 30).unwrap());
+        assert_eq!(new_app, None);
+
+        assert!(parse_split_input("later", start).is_err());
+    }
+
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(parse_tag("  PROJ-123 "), Some("PROJ-123".to_string()));
+        assert_eq!(parse_tag("client  demo\tprep"), Some("client demo prep".to_string()));
+        assert_eq!(parse_tag("   "), None);
+    }
+
+    #[test]
+    fn test_parse_history_date() {
+        // A Wednesday
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 3, 12).unwrap();
+        let day = |y, m, d| chrono::NaiveDate::from_ymd_opt(y, m, d).unwrap();
+
+        assert_eq!(parse_history_date("2025-03-01", today).unwrap(), day(2025, 3, 1));
+        assert_eq!(parse_history_date("03-05", today).unwrap(), day(2025, 3, 5));
+        assert_eq!(parse_history_date(" Today ", today).unwrap(), today);
+        assert_eq!(parse_history_date("yesterday", today).unwrap(), day(2025, 3, 11));
+        assert_eq!(parse_history_date("mon", today).unwrap(), day(2025, 3, 10));
+        // Today's weekday means a week ago
+        assert_eq!(parse_history_date("wednesday", today).unwrap(), day(2025, 3, 5));
+        assert!(parse_history_date("2025-03-13", today).is_err());
+        assert!(parse_history_date("someday", today).is_err());
+    }
+
+    #[test]
+    fn test_parse_away_minutes() {
+        assert_eq!(parse_away_minutes("20", 3600).unwrap(), 20);
+        assert_eq!(parse_away_minutes(" 60 ", 3600).unwrap(), 60);
+        // Can't be longer than the session has run
+        assert!(parse_away_minutes("61", 3600).is_err());
+        assert!(parse_away_minutes("0", 3600).is_err());
+        assert!(parse_away_minutes("-5", 3600).is_err());
+        assert!(parse_away_minutes("abc", 3600).is_err());
+    }
+
+    #[test]
+    fn test_search_pattern() {
+        assert_eq!(search_pattern("  kubernetes ").unwrap(), "%kubernetes%");
+        // LIKE wildcards in the term match literally
+        assert_eq!(search_pattern("100%").unwrap(), "%100\\%%");
+        assert_eq!(search_pattern("my_file.rs").unwrap(), "%my\\_file.rs%");
+        assert_eq!(search_pattern("C:\\dev").unwrap(), "%C:\\\\dev%");
+        assert!(search_pattern("   ").is_err());
+    }
+
+    #[test]
+    fn test_parse_note() {
+        assert_eq!(parse_note("  fixed the auth bug \n"), Some("fixed the auth bug".to_string()));
+        // Spacing inside the note is kept
+        assert_eq!(parse_note("step 1:  repro"), Some("step 1:  repro".to_string()));
+        assert_eq!(parse_note(" \t "), None);
+    }
+
+    #[test]
+    fn test_session_summary() {
+        assert_eq!(session_summary("vscode", Some("main.rs"), 4920), "vscode — main.rs — 1h22m");
+        assert_eq!(session_summary("Slack", None, 300), "Slack — 5m");
+        assert_eq!(session_summary("Slack", Some("  "), 300), "Slack — 5m");
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
@@ -1,7 +1,8 @@
 use std::collections::BTreeMap;
-use crate::models::session::Session;
+use std::sync::OnceLock;
+use crate::models::session::{extract_project_name, Session};
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct HierarchicalDisplayItem {
     pub display_name: String,
     pub unique_id: String, // This will be the key for database operations
@@ -11,52 +12,6 @@ pub struct HierarchicalDisplayItem {
     pub is_sub_entry: bool,
 }
 
-/// Extract project name from directory path with improved heuristics
-fn extract_project_name(path: &str) -> Option<String> {
-    // Handle home directory specially
-    if let Ok(home) = std::env::var("HOME") {
-        if path == home || path == "~" {
-            return Some("Home".to_string());
-        }
-        if path.starts_with(&format!("{}/", home)) {
-            // Extract the first directory after home (e.g., ~/Documents -> Documents)
-            let after_home = &path[home.len() + 1..];
-            if let Some(slash_pos) = after_home.find('/') {
-                let first_dir = &after_home[..slash_pos];
-                if !first_dir.is_empty() {
-                    return Some(first_dir.to_string());
-                }
-            } else if !after_home.is_empty() {
-                return Some(after_home.to_string());
-            }
-        }
-    }
-
-    // Standard project extraction from path
-    let parts: Vec<&str> = path.split('/').collect();
-
-    // Get last non-empty component, skipping common non-project directories
-    let skip_dirs = ["bin", "usr", "etc", "var", "tmp", "dev", "proc", "sys", "home", "root"];
-
-    for part in parts.iter().rev() {
-        let part_lower = part.to_lowercase();
-        if !part.is_empty() && *part != "." && *part != ".." && !skip_dirs.contains(&part_lower.as_str()) {
-            // Additional heuristics: prefer directories that look like projects
-            if part.chars().next().map_or(false, |c| c.is_alphabetic()) && part.len() >= 2 {
-                return Some(part.to_string());
-            }
-        }
-    }
-
-    // Fallback: if we have any valid directory component
-    for part in parts.iter().rev() {
-        if !part.is_empty() && *part != "." && *part != ".." {
-            return Some(part.to_string());
-        }
-    }
-
-    None
-}
 /// Creates hierarchical usage data from sessions for display in stats
 /// Format: App entries with sub-entries indented with "  └─ "
 pub fn create_hierarchical_usage(sessions: &[Session]) -> Vec<HierarchicalDisplayItem> {
@@ -71,36 +26,18 @@ pub fn create_hierarchical_usage(sessions: &[Session]) -> Vec<HierarchicalDispla
 
         let app_name = session.app_name.trim().to_string();
 
-        // Determine sub-entry unique ID, display name, and category
-        let (sub_entry_unique_id, sub_entry_display_name, sub_entry_category) = if let Some(page_title) = &session.browser_page_title {
-            let display = session.browser_page_title_renamed.as_ref().unwrap_or(page_title).clone();
-            (format!("browser_page_title:{}", page_title), display, session.browser_page_title_category.clone())
-        } else if let Some(dir) = &session.terminal_directory {
-            let original_project_name = extract_project_name(dir).unwrap_or_else(|| dir.clone());
-            let display = session.terminal_directory_renamed.as_ref().unwrap_or(&original_project_name).clone();
-            (format!("terminal_directory:{}", dir), display, session.terminal_directory_category.clone())
-        } else if let Some(filename) = &session.editor_filename {
-            let display = session.editor_filename_renamed.as_ref().unwrap_or(filename).clone();
-            (format!("editor_filename:{}", filename), display, session.editor_filename_category.clone())
-        } else if let Some(tmux_window) = &session.tmux_window_name {
-            let display = session.tmux_window_name_renamed.as_ref().unwrap_or(tmux_window).clone();
-            (format!("tmux_window_name:{}", tmux_window), display, session.tmux_window_name_category.clone())
-        } else if let Some(window) = &session.window_name {
-            (format!("window_name:{}", window), window.clone(), None)
-        } else {
-            // Fallback for entries with no specific sub-entry data
-            (app_name.clone(), app_name.clone(), None)
-        };
-
         let app_sessions = app_map.entry(app_name.clone()).or_insert_with(BTreeMap::new);
 
-        // If sub_entry_unique_id is the same as app_name, it's a general entry
-        if sub_entry_unique_id == app_name {
-            let (current_duration, _, _) = app_sessions.entry("(general)".to_string()).or_insert((0, "(general)".to_string(), None));
-            *current_duration += session.duration;
-        } else if !sub_entry_unique_id.is_empty() {
-            let (current_duration, _, _) = app_sessions.entry(sub_entry_unique_id).or_insert((0, sub_entry_display_name, sub_entry_category));
-            *current_duration += session.duration;
+        // Sessions with nothing below the app name count as a general entry
+        match session.effective_display_name() {
+            Some((display_name, unique_id, category)) => {
+                let (current_duration, _, _) = app_sessions.entry(unique_id).or_insert((0, display_name, category));
+                *current_duration += session.duration;
+            }
+            None => {
+                let (current_duration, _, _) = app_sessions.entry("(general)".to_string()).or_insert((0, "(general)".to_string(), None));
+                *current_duration += session.duration;
+            }
         }
     }
 
@@ -151,8 +88,11 @@ pub fn create_hierarchical_usage(sessions: &[Session]) -> Vec<HierarchicalDispla
 
 /// Creates hierarchical breakdown data for browser sessions
 /// Groups by service, then shows page titles
-pub fn create_browser_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
+/// With `group_by_profile`, services are split per browser profile, e.g. "Gmail (Work)"
+/// Services showing unread counts in their titles get the latest one, e.g. "Gmail 🔔 3"
+pub fn create_browser_breakdown(sessions: &[Session], group_by_profile: bool) -> Vec<(String, i64)> {
     let mut browser_map: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+    let mut latest_unread: BTreeMap<String, (chrono::DateTime<chrono::Local>, i32)> = BTreeMap::new();
 
     for session in sessions {
         // Skip AFK sessions
@@ -165,7 +105,18 @@ pub fn create_browser_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
             // Otherwise, don't create a hierarchy - just skip it
             if let Some(url) = &session.browser_url {
                 // We have a recognized service (YouTube, WhatsApp, LinkedIn, etc.)
-                let service_map = browser_map.entry(url.clone()).or_insert_with(BTreeMap::new);
+                // Browser sessions keep the profile in ide_workspace
+                let service = match session.ide_workspace.as_ref().filter(|_| group_by_profile) {
+                    Some(profile) => format!("{} ({})", url, profile),
+                    None => url.clone(),
+                };
+                if let Some(count) = session.browser_notification_count {
+                    let latest = latest_unread.entry(service.clone()).or_insert((session.start_time, count));
+                    if session.start_time >= latest.0 {
+                        *latest = (session.start_time, count);
+                    }
+                }
+                let service_map = browser_map.entry(service).or_insert_with(BTreeMap::new);
                 *service_map.entry(page_title.clone()).or_insert(0) += session.duration;
             }
             // If no browser_url, we don't include it in breakdown (it's already in regular app stats)
@@ -173,6 +124,12 @@ pub fn create_browser_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
     }
 
     flatten_hierarchical_map(browser_map, 5)
+        .into_iter()
+        .map(|(label, duration)| match latest_unread.get(&label) {
+            Some((_, count)) if *count > 0 => (format!("{} 🔔 {}", label, count), duration),
+            _ => (label, duration),
+        })
+        .collect()
 }
 
 /// Creates hierarchical breakdown data for projects/terminal sessions
@@ -198,6 +155,78 @@ pub fn create_project_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
     flatten_hierarchical_map(project_dir_map, 3)
 }
 
+/// Hostname of this machine, used to tell SSH sessions apart from local shells
+fn local_hostname() -> &'static str {
+    static LOCAL_HOSTNAME: OnceLock<String> = OnceLock::new();
+    LOCAL_HOSTNAME.get_or_init(|| {
+        std::fs::read_to_string("/proc/sys/kernel/hostname")
+            .ok()
+            .or_else(|| {
+                std::process::Command::new("hostname")
+                    .output()
+                    .ok()
+                    .filter(|output| output.status.success())
+                    .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            })
+            .map(|name| name.trim().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// Returns the terminal's host when it is not this machine (e.g. "user@remotehost: /path" over SSH).
+/// Shells usually show the short hostname, so only the part before the first dot is compared.
+pub fn remote_host<'a>(terminal_hostname: Option<&'a str>, local_hostname: &str) -> Option<&'a str> {
+    let host = terminal_hostname?.trim();
+    let short = |name: &str| name.split('.').next().unwrap_or(name).to_lowercase();
+    if host.is_empty() || local_hostname.is_empty() || short(host) == short(local_hostname) {
+        None
+    } else {
+        Some(host)
+    }
+}
+
+/// Placeholder shown instead of window titles, file names and page titles while titles are redacted
+pub const REDACTED: &str = "•••";
+
+/// Copy of the usage list with sub-entry names (window titles) hidden; app rows and totals are kept
+pub fn redact_sub_entries(items: &[HierarchicalDisplayItem]) -> Vec<HierarchicalDisplayItem> {
+    items
+        .iter()
+        .cloned()
+        .map(|mut item| {
+            if item.is_sub_entry {
+                item.display_name = REDACTED.to_string();
+            }
+            item
+        })
+        .collect()
+}
+
+/// Copy of a breakdown with every name hidden, keeping the durations
+pub fn redact_breakdown(items: &[(String, i64)]) -> Vec<(String, i64)> {
+    items.iter().map(|(_, duration)| (REDACTED.to_string(), *duration)).collect()
+}
+
+/// Editing time per language, e.g. ("Rust", 10800), sorted by time spent
+pub fn create_language_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
+    let mut language_map: BTreeMap<String, i64> = BTreeMap::new();
+
+    for session in sessions {
+        // Skip AFK sessions
+        if session.is_afk.unwrap_or(false) {
+            continue;
+        }
+
+        if let (Some(_), Some(language)) = (&session.editor_filename, &session.editor_language) {
+            *language_map.entry(language.clone()).or_insert(0) += session.duration;
+        }
+    }
+
+    let mut languages: Vec<(String, i64)> = language_map.into_iter().collect();
+    languages.sort_by_key(|(_, duration)| std::cmp::Reverse(*duration));
+    languages
+}
+
 /// Creates hierarchical breakdown data for terminal sessions
 /// Groups by project, then shows directories
 pub fn create_terminal_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
@@ -222,6 +251,12 @@ pub fn create_terminal_breakdown(sessions: &[Session]) -> Vec<(String, i64)> {
             "Other".to_string()
         };
 
+        // Remote (SSH) work is grouped per host as "remotehost:/project"
+        let project_name = match remote_host(session.terminal_hostname.as_deref(), local_hostname()) {
+            Some(host) => format!("{}:/{}", host, project_name),
+            None => project_name,
+        };
+
         // Add the session to the project map
         let dir_map = terminal_project_map.entry(project_name).or_insert_with(BTreeMap::new);
 
@@ -325,3 +360,75 @@ fn flatten_hierarchical_map(
 
     flattened
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor_session(filename: &str, language: &str, duration: i64, is_afk: bool) -> Session {
+        Session {
+            editor_filename: Some(filename.to_string()),
+            editor_language: Some(language.to_string()),
+            is_afk: Some(is_afk),
+            ..Session::for_test("code", chrono::Local::now(), duration)
+        }
+    }
+
+    #[test]
+    fn test_browser_breakdown_shows_latest_unread_count() {
+        let now = chrono::Local::now();
+        let browser_session = |title: &str, unread: Option<i32>, minutes_ago: i64| Session {
+            browser_url: Some("Gmail".to_string()),
+            browser_page_title: Some(title.to_string()),
+            browser_notification_count: unread,
+            ..Session::for_test("firefox", now - chrono::Duration::minutes(minutes_ago), 60)
+        };
+        let sessions = vec![
+            browser_session("Inbox", Some(7), 30),
+            browser_session("Inbox", Some(2), 5),
+            browser_session("Draft", None, 1),
+        ];
+
+        let breakdown = create_browser_breakdown(&sessions, false);
+        assert_eq!(breakdown[0], ("Gmail 🔔 2".to_string(), 180));
+        assert!(breakdown[1..].iter().all(|(label, _)| label.starts_with("  └─ ")));
+
+        // Read inbox: no badge
+        let read = vec![browser_session("Inbox", Some(0), 1)];
+        assert_eq!(create_browser_breakdown(&read, false)[0].0, "Gmail");
+    }
+
+    #[test]
+    fn test_redact_keeps_app_rows_and_totals() {
+        let item = |name: &str, duration: i64, is_sub_entry: bool| HierarchicalDisplayItem {
+            display_name: name.to_string(),
+            unique_id: format!("app_name:{}", name),
+            duration,
+            category: None,
+            parent_app_name: Some("firefox".to_string()),
+            is_sub_entry,
+        };
+        let items = vec![item("firefox", 600, false), item("Bank statement", 600, true)];
+
+        let redacted = redact_sub_entries(&items);
+        assert_eq!(redacted[0].display_name, "firefox");
+        assert_eq!(redacted[1].display_name, REDACTED);
+        assert_eq!(redacted[1].unique_id, items[1].unique_id);
+        assert_eq!(redact_breakdown(&[("secret.rs".to_string(), 60)]), vec![(REDACTED.to_string(), 60)]);
+    }
+
+    #[test]
+    fn test_create_language_breakdown() {
+        let sessions = vec![
+            editor_session("main.rs", "Rust", 3600, false),
+            editor_session("app.rs", "Rust", 7200, false),
+            editor_session("train.py", "Python", 3600, false),
+            editor_session("lib.rs", "Rust", 9000, true),
+        ];
+
+        assert_eq!(
+            create_language_breakdown(&sessions),
+            vec![("Rust".to_string(), 10800), ("Python".to_string(), 3600)]
+        );
+    }
+}
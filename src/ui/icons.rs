@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+
+/// Whether labels get icons and the user's per-app overrides; set from the config at startup
+static SETTINGS: RwLock<Option<(bool, BTreeMap<String, String>)>> = RwLock::new(None);
+
+/// Shown for apps without a built-in icon
+pub const NEUTRAL_ICON: &str = "🔹";
+
+pub fn configure(enabled: bool, overrides: BTreeMap<String, String>) {
+    let overrides = overrides.into_iter().map(|(app, icon)| (app.to_lowercase(), icon)).collect();
+    *SETTINGS.write().unwrap() = Some((enabled, overrides));
+}
+
+/// Built-in icon for a normalized app name (as produced by `fix_app_name`). Names match whole words
+/// of the app ("google-chrome", "org.gnome.Nautilus"), so "mailspring" isn't taken for "mail"
+pub fn app_icon(app: &str) -> &'static str {
+    let app = app.trim().to_lowercase();
+    let words: Vec<&str> = app.split(|c: char| !c.is_alphanumeric()).filter(|word| !word.is_empty()).collect();
+    const ICONS: [(&[&str], &str); 16] = [
+        (&["firefox", "librewolf", "zen"], "🦊"),
+        (&["chrome", "chromium", "brave", "edge", "vivaldi", "opera"], "🌐"),
+        (&["safari"], "🧭"),
+        (&["code", "vscode", "cursor", "zed"], "🟦"),
+        (&["idea", "pycharm", "webstorm", "clion", "goland", "rustrover"], "🧠"),
+        (&["nvim", "vim", "emacs", "helix"], "📝"),
+        (&["terminal", "alacritty", "kitty", "wezterm", "ghostty", "konsole", "iterm", "iterm2", "foot", "tilix"], "💻"),
+        (&["spotify", "music", "rhythmbox"], "🎵"),
+        (&["vlc", "mpv", "youtube"], "🎬"),
+        (&["slack"], "💬"),
+        (&["discord"], "🎮"),
+        (&["telegram", "signal", "whatsapp"], "✉️"),
+        (&["zoom", "teams", "meet"], "📹"),
+        (&["thunderbird", "mail", "outlook"], "📧"),
+        (&["nautilus", "dolphin", "finder", "explorer", "thunar"], "📁"),
+        (&["obsidian", "notion", "logseq", "notes"], "🗒️"),
+    ];
+    ICONS
+        .iter()
+        .find(|(names, _)| names.iter().any(|name| words.contains(name)))
+        .map(|(_, icon)| *icon)
+        .unwrap_or(NEUTRAL_ICON)
+}
+
+/// Icon to show before an app label: the configured override, else the built-in one; None when icons are off
+pub fn icon_for(app: &str) -> Option<String> {
+    let settings = SETTINGS.read().unwrap();
+    match settings.as_ref() {
+        Some((false, _)) => None,
+        Some((true, overrides)) => Some(
+            overrides
+                .get(&app.trim().to_lowercase())
+                .cloned()
+                .unwrap_or_else(|| app_icon(app).to_string()),
+        ),
+        None => Some(app_icon(app).to_string()),
+    }
+}
+
+/// "🦊 firefox", or just the name when icons are off
+pub fn with_icon(app: &str, label: &str) -> String {
+    match icon_for(app) {
+        Some(icon) => format!("{} {}", icon, label),
+        None => label.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_app_icons() {
+        assert_eq!(app_icon("firefox"), "🦊");
+        assert_eq!(app_icon("Code"), "🟦");
+        assert_eq!(app_icon("spotify"), "🎵");
+        assert_eq!(app_icon("some-unknown-app"), NEUTRAL_ICON);
+        // Whole words of the name, not substrings
+        assert_eq!(app_icon("google-chrome"), "🌐");
+        assert_eq!(app_icon("org.gnome.Nautilus"), "📁");
+        assert_eq!(app_icon("mailspring"), NEUTRAL_ICON);
+        assert_eq!(app_icon("cozen"), NEUTRAL_ICON);
+    }
+}
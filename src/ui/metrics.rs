@@ -0,0 +1,738 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate};
+use crate::config::settings::{TimeFormat, WeekStart};
+use crate::database::connection::{AFK_PSEUDO_APP, IDLE_PSEUDO_APP};
+use crate::models::session::Session;
+use crate::ui::app::AWAY_CATEGORY;
+use crate::ui::hierarchical::HierarchicalDisplayItem;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashSet};
+
+/// Number of consecutive active days ending today.
+/// Today is still in progress, so a streak that ended yesterday is not broken yet.
+pub fn compute_streak(active_days: &[NaiveDate], today: NaiveDate) -> u32 {
+    let days: HashSet<NaiveDate> = active_days.iter().copied().collect();
+
+    let mut day = if days.contains(&today) {
+        today
+    } else {
+        match today.pred_opt() {
+            Some(yesterday) => yesterday,
+            None => return 0,
+        }
+    };
+
+    let mut streak = 0;
+    while days.contains(&day) {
+        streak += 1;
+        match day.pred_opt() {
+            Some(previous) => day = previous,
+            None => break,
+        }
+    }
+    streak
+}
+
+/// "Tracking since 2024-03-01 (142 days)" for the status bar
+pub fn tracking_since_label(first_session_date: Option<NaiveDate>, today: NaiveDate) -> String {
+    match first_session_date {
+        Some(first) => {
+            let days = (today - first).num_days().max(0);
+            let unit = if days == 1 { "day" } else { "days" };
+            format!("Tracking since {} ({} {})", first.format("%Y-%m-%d"), days, unit)
+        }
+        None => "No data yet".to_string(),
+    }
+}
+
+/// One entry per day from `start`, oldest first, with that day's categories merged into their
+/// parents and largest first; days with nothing recorded get an empty list
+pub fn category_days(rows: &[(NaiveDate, String, i64)], start: NaiveDate, days: i64) -> Vec<(NaiveDate, Vec<(String, i64)>)> {
+    start
+        .iter_days()
+        .take(days.max(0) as usize)
+        .map(|day| {
+            let mut totals: BTreeMap<&str, i64> = BTreeMap::new();
+            for (_, category, secs) in rows.iter().filter(|(row_day, _, _)| *row_day == day) {
+                *totals.entry(parent_category(category)).or_default() += secs;
+            }
+            let mut categories: Vec<(String, i64)> = totals.into_iter().map(|(category, secs)| (category.to_string(), secs)).collect();
+            categories.sort_by_key(|(_, secs)| Reverse(*secs));
+            (day, categories)
+        })
+        .collect()
+}
+
+/// Average seconds per weekday (Monday first), dividing each weekday's total by
+/// how many times that weekday occurs between `start` and `end` inclusive
+pub fn weekday_averages(totals: [i64; 7], start: NaiveDate, end: NaiveDate) -> [f64; 7] {
+    let mut occurrences = [0u32; 7];
+    for day in start.iter_days().take_while(|day| *day <= end) {
+        occurrences[day.weekday().num_days_from_monday() as usize] += 1;
+    }
+
+    let mut averages = [0.0; 7];
+    for (idx, average) in averages.iter_mut().enumerate() {
+        if occurrences[idx] > 0 {
+            *average = totals[idx] as f64 / occurrences[idx] as f64;
+        }
+    }
+    averages
+}
+
+/// (ISO year, ISO week, seconds) for every ISO week from `start`'s through `end`'s, oldest first.
+/// Weeks missing from `totals` get 0; days near New Year belong to whichever ISO year their week does
+pub fn iso_week_totals(totals: &[(i32, i32, i64)], start: NaiveDate, end: NaiveDate) -> Vec<(i32, i32, i64)> {
+    let mut weeks: Vec<(i32, i32, i64)> = Vec::new();
+    for day in start.iter_days().take_while(|day| *day <= end) {
+        let iso = day.iso_week();
+        let key = (iso.year(), iso.week() as i32);
+        if weeks.last().map(|(year, week, _)| (*year, *week)) != Some(key) {
+            let secs = totals.iter().find(|(year, week, _)| (*year, *week) == key).map(|(_, _, secs)| *secs).unwrap_or(0);
+            weeks.push((key.0, key.1, secs));
+        }
+    }
+    weeks
+}
+
+/// Estimated earnings for the billable categories in a category usage list
+pub fn billable_amount(category_usage: &[(String, i64)], billable_categories: &[String], hourly_rate: f64) -> f64 {
+    let billable_secs: i64 = category_usage
+        .iter()
+        .filter(|(category, _)| {
+            billable_categories.iter().any(|billable| billable == category || billable == parent_category(category))
+        })
+        .map(|(_, secs)| secs)
+        .sum();
+    billable_secs as f64 * hourly_rate / 3600.0
+}
+
+/// Top-level category of a sub-category like "💻 Development/Rust"; other categories are their own parent
+pub fn parent_category(category: &str) -> &str {
+    category.split_once('/').map_or(category, |(parent, _)| parent.trim_end())
+}
+
+/// What `category` becomes when `old` is renamed to `new`, sub-categories included; None if unaffected
+pub fn renamed_category(category: &str, old: &str, new: &str) -> Option<String> {
+    if category == old {
+        Some(new.to_string())
+    } else if parent_category(category) == old && category.starts_with(old) {
+        Some(format!("{}{}", new, &category[old.len()..]))
+    } else {
+        None
+    }
+}
+
+/// Sort rank for a category: pinned categories in their configured order, then everything else
+pub fn pinned_rank(category: &str, pinned_categories: &[String]) -> usize {
+    pinned_categories
+        .iter()
+        .position(|pinned| pinned == category)
+        .unwrap_or(pinned_categories.len())
+}
+
+/// Category totals in display order: pinned categories first, then by time spent, with each
+/// parent's sub-categories right after it (the parent family ranked by its combined time)
+pub fn sort_categories(totals: BTreeMap<String, i64>, pinned_categories: &[String]) -> Vec<(String, i64)> {
+    let mut family_totals: BTreeMap<String, i64> = BTreeMap::new();
+    for (category, secs) in &totals {
+        *family_totals.entry(parent_category(category).to_string()).or_insert(0) += secs;
+    }
+    let mut sorted: Vec<(String, i64)> = totals.into_iter().collect();
+    sorted.sort_by_key(|(category, secs)| {
+        let parent = parent_category(category);
+        (
+            pinned_rank(parent, pinned_categories),
+            Reverse(family_totals[parent]),
+            parent.to_string(),
+            category.as_str() != parent,
+            Reverse(*secs),
+        )
+    });
+    sorted
+}
+
+/// Stats panel line naming where most of this week's time went
+pub fn busiest_project_label(name: &str, secs: i64, rounding_minutes: u64) -> String {
+    format!("🎯 Most time this week: {} ({})", name, crate::ui::render::format_duration(secs, rounding_minutes))
+}
+
+/// Status bar warning once the daemon's last heartbeat is older than `stale_secs`; None when it's
+/// running, has never run on this machine, or the warning is off
+pub fn daemon_warning(last_seen: Option<DateTime<Local>>, now: DateTime<Local>, stale_secs: u64) -> Option<String> {
+    let age = now.signed_duration_since(last_seen?).num_seconds();
+    if stale_secs == 0 || age < stale_secs as i64 {
+        return None;
+    }
+    let ago = match age / 60 {
+        minutes if minutes < 60 => format!("{}m", minutes),
+        minutes if minutes < 48 * 60 => format!("{}h", minutes / 60),
+        minutes => format!("{}d", minutes / (24 * 60)),
+    };
+    Some(format!("⚠ Daemon not running (last seen {} ago)", ago))
+}
+
+/// Dashboard banner text for apps past their daily limit, like "⛔ firefox 1h 5m today (limit 1h 0m)";
+/// None when no app is over
+pub fn app_limit_banner(alerts: &[(String, i64, i64)], rounding_minutes: u64) -> Option<String> {
+    if alerts.is_empty() {
+        return None;
+    }
+    let duration = |secs| crate::ui::render::format_duration(secs, rounding_minutes);
+    let apps: Vec<String> = alerts
+        .iter()
+        .map(|(app, spent_secs, limit_minutes)| format!("{} {} today (limit {})", app, duration(*spent_secs), duration(limit_minutes * 60)))
+        .collect();
+    Some(format!("⛔ {}", apps.join(" · ")))
+}
+
+/// Status bar badge like "laptop 3h 0m / desktop 5h 12m", only once more than one machine has recorded time
+pub fn hostname_usage_label(hostname_usage: &[(String, i64)], rounding_minutes: u64) -> Option<String> {
+    if hostname_usage.len() < 2 {
+        return None;
+    }
+    let machines: Vec<String> = hostname_usage
+        .iter()
+        .map(|(hostname, secs)| format!("{} {}", hostname, crate::ui::render::format_duration(*secs, rounding_minutes)))
+        .collect();
+    Some(machines.join(" / "))
+}
+
+/// Dashboard hint once "Other" apps take more than `threshold_percent` of the view's `total_secs`,
+/// naming the top three so it's clear which apps need a category. A threshold of 0 turns it off
+pub fn other_hint(other_app_usage: &[(String, i64)], total_secs: i64, threshold_percent: u64, rounding_minutes: u64) -> Option<String> {
+    let other_secs: i64 = other_app_usage.iter().map(|(_, secs)| secs).sum();
+    if threshold_percent == 0 || total_secs <= 0 || other_secs * 100 <= total_secs * threshold_percent as i64 {
+        return None;
+    }
+    let apps: Vec<String> = other_app_usage
+        .iter()
+        .take(3)
+        .map(|(app, secs)| format!("{} {}", app, crate::ui::render::format_duration(*secs, rounding_minutes)))
+        .collect();
+    Some(format!(
+        "{}% of this view is 📦 Other. Top unrecognized apps: {}. Press [c] to give them a category",
+        (other_secs * 100 / total_secs).min(100),
+        apps.join(", ")
+    ))
+}
+
+/// Breaks (AFK sessions) over a range; idle ones (10+ minutes without any input) are also counted apart
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AfkStats {
+    pub breaks: usize,
+    pub total_secs: i64,
+    pub longest_secs: i64,
+    pub idle_breaks: usize,
+    pub idle_secs: i64,
+}
+
+impl AfkStats {
+    /// From each AFK session's (duration, is_idle)
+    pub fn from_sessions(afk_sessions: &[(i64, bool)]) -> Self {
+        let mut stats = AfkStats::default();
+        for &(secs, is_idle) in afk_sessions {
+            stats.breaks += 1;
+            stats.total_secs += secs;
+            stats.longest_secs = stats.longest_secs.max(secs);
+            if is_idle {
+                stats.idle_breaks += 1;
+                stats.idle_secs += secs;
+            }
+        }
+        stats
+    }
+
+    pub fn average_secs(&self) -> i64 {
+        if self.breaks == 0 { 0 } else { self.total_secs / self.breaks as i64 }
+    }
+
+    /// "5 breaks, 1h 12m total, avg 14m, longest 35m"
+    pub fn label(&self, rounding_minutes: u64) -> String {
+        if self.breaks == 0 {
+            return "No breaks yet".to_string();
+        }
+        let duration = |secs| crate::ui::render::format_duration(secs, rounding_minutes);
+        format!(
+            "{} break{}, {} total, avg {}, longest {}",
+            self.breaks,
+            if self.breaks == 1 { "" } else { "s" },
+            duration(self.total_secs),
+            duration(self.average_secs()),
+            duration(self.longest_secs)
+        )
+    }
+
+    /// "2 idle (10m+), 50m", or None without idle breaks
+    pub fn idle_label(&self, rounding_minutes: u64) -> Option<String> {
+        if self.idle_breaks == 0 {
+            return None;
+        }
+        Some(format!("{} idle (10m+), {}", self.idle_breaks, crate::ui::render::format_duration(self.idle_secs, rounding_minutes)))
+    }
+}
+
+/// ROUNDING_MINUTES in .env takes precedence over rounding_minutes in config.toml
+pub fn resolve_rounding_minutes(configured: u64) -> u64 {
+    std::env::var("ROUNDING_MINUTES")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(configured)
+}
+
+/// TIME_FORMAT in .env ("hms" or "decimal") takes precedence over time_format in config.toml
+pub fn resolve_time_format(configured: TimeFormat) -> TimeFormat {
+    match std::env::var("TIME_FORMAT").map(|name| name.to_lowercase()).as_deref() {
+        Ok("decimal") => TimeFormat::Decimal,
+        Ok("hms") => TimeFormat::Hms,
+        _ => configured,
+    }
+}
+
+/// EMOJI in .env ("true" or "false") takes precedence over emoji in config.toml
+pub fn resolve_emoji(configured: bool) -> bool {
+    std::env::var("EMOJI")
+        .ok()
+        .and_then(|v| v.to_lowercase().parse::<bool>().ok())
+        .unwrap_or(configured)
+}
+
+/// One line for a tmux status bar, e.g. "💻4h12m 🌐1h03m": the first `count` categories,
+/// each shown by its emoji (or its name when it has none)
+pub fn tmux_status(category_usage: &[(String, i64)], count: usize, rounding_minutes: u64) -> String {
+    category_usage
+        .iter()
+        .take(count)
+        .map(|(category, secs)| {
+            let label = category.split_whitespace().next().unwrap_or(category);
+            let minutes = crate::ui::render::round_minutes(*secs, rounding_minutes);
+            if minutes >= 60 {
+                format!("{}{}h{:02}m", label, minutes / 60, minutes % 60)
+            } else {
+                format!("{}{}m", label, minutes)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// First day of the weekly view containing `today`
+pub fn week_start_date(today: NaiveDate, week_start: WeekStart) -> NaiveDate {
+    let days_back = match week_start {
+        WeekStart::Rolling => 6,
+        WeekStart::Monday => today.weekday().num_days_from_monday(),
+        WeekStart::Sunday => today.weekday().num_days_from_sunday(),
+    };
+    today - chrono::Duration::days(days_back as i64)
+}
+
+/// One value per hour for the last `hours` hours (oldest first) from hourly unread-count points.
+/// Unread counts persist until read, so hours without a reading repeat the previous hour's value.
+pub fn notification_trend(points: &[(DateTime<Local>, i64)], now: DateTime<Local>, hours: i64) -> Vec<u64> {
+    let hours = hours.max(1);
+    let mut readings: Vec<Option<u64>> = vec![None; hours as usize];
+    for (time, count) in points {
+        let hours_ago = now.signed_duration_since(*time).num_hours();
+        if (0..hours).contains(&hours_ago) {
+            readings[(hours - 1 - hours_ago) as usize] = Some((*count).max(0) as u64);
+        }
+    }
+
+    let mut last = 0;
+    readings
+        .into_iter()
+        .map(|reading| {
+            last = reading.unwrap_or(last);
+            last
+        })
+        .collect()
+}
+
+/// Dashboard rows for the AFK and Idle pseudo-apps from `get_app_usage_with_afk`, adding the
+/// in-progress away session (pseudo-app name, elapsed seconds). Rows without any time are left out
+pub fn away_usage_items(usage_with_afk: &[(String, i64)], live_away: Option<(&str, i64)>) -> Vec<HierarchicalDisplayItem> {
+    [AFK_PSEUDO_APP, IDLE_PSEUDO_APP]
+        .into_iter()
+        .filter_map(|name| {
+            let stored: i64 = usage_with_afk.iter().filter(|(app, _)| app == name).map(|(_, secs)| secs).sum();
+            let live = live_away.filter(|(app, _)| *app == name).map_or(0, |(_, secs)| secs);
+            (stored + live > 0).then(|| HierarchicalDisplayItem {
+                display_name: name.to_string(),
+                unique_id: format!("app_name:{}", name),
+                duration: stored + live,
+                category: Some(AWAY_CATEGORY.to_string()),
+                parent_app_name: Some(name.to_string()),
+                is_sub_entry: false,
+            })
+        })
+        .collect()
+}
+
+/// A row in the history popup: one session, or a run of consecutive same-app sessions merged for display
+#[derive(Debug, Clone)]
+pub struct HistoryRow {
+    /// The latest session in the run, which [x], [t] and [n] act on
+    pub session: Session,
+    pub start_time: DateTime<Local>,
+    pub end_time: DateTime<Local>,
+    /// Summed durations of the merged sessions, so gaps between them don't count
+    pub duration: i64,
+    pub merged: usize,
+}
+
+/// History popup rows, newest first. With `merge_same_app`, sessions of the same app with no other
+/// app's session between them (among those passed in) become one row spanning all of them.
+/// Display only, the stored sessions stay separate
+pub fn history_rows(sessions: &[&Session], merge_same_app: bool) -> Vec<HistoryRow> {
+    let mut sorted = sessions.to_vec();
+    sorted.sort_by_key(|session| Reverse(session.start_time));
+
+    let mut rows: Vec<HistoryRow> = Vec::new();
+    for session in sorted {
+        let end_time = session.start_time + chrono::Duration::seconds(session.duration);
+        match rows.last_mut() {
+            Some(row) if merge_same_app && row.session.app_name == session.app_name => {
+                row.start_time = row.start_time.min(session.start_time);
+                row.end_time = row.end_time.max(end_time);
+                row.duration += session.duration;
+                row.merged += 1;
+            }
+            _ => rows.push(HistoryRow {
+                session: session.clone(),
+                start_time: session.start_time,
+                end_time,
+                duration: session.duration,
+                merged: 1,
+            }),
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap()
+    }
+
+    #[test]
+    fn test_compute_streak_counts_consecutive_days() {
+        let days = vec![date(10), date(9), date(8), date(6)];
+        assert_eq!(compute_streak(&days, date(10)), 3);
+    }
+
+    #[test]
+    fn test_pinned_rank_orders_pinned_first() {
+        let pinned = vec!["💻 Development".to_string(), "📅 Meetings".to_string()];
+        let mut categories = [("🌐 Browsing", 900), ("📅 Meetings", 300), ("💬 Communication", 600), ("💻 Development", 60)];
+        categories.sort_by_key(|(category, duration)| (pinned_rank(category, &pinned), std::cmp::Reverse(*duration)));
+        let order: Vec<&str> = categories.iter().map(|(category, _)| *category).collect();
+        assert_eq!(order, ["💻 Development", "📅 Meetings", "🌐 Browsing", "💬 Communication"]);
+
+        // Nothing pinned keeps duration order
+        categories.sort_by_key(|(category, duration)| (pinned_rank(category, &[]), std::cmp::Reverse(*duration)));
+        assert_eq!(categories[0].0, "🌐 Browsing");
+    }
+
+    #[test]
+    fn test_busiest_project_label() {
+        assert_eq!(busiest_project_label("neura-hustle-tracker", 30000, 0), "🎯 Most time this week: neura-hustle-tracker (8h 20m)");
+        assert_eq!(busiest_project_label("firefox", 2700, 0), "🎯 Most time this week: firefox (45m)");
+        assert_eq!(busiest_project_label("firefox", 2700, 60), "🎯 Most time this week: firefox (1h 0m)");
+    }
+
+    #[test]
+    fn test_afk_stats() {
+        let stats = AfkStats::from_sessions(&[(300, false), (2100, true), (600, false), (1320, true), (60, false)]);
+        assert_eq!(stats.breaks, 5);
+        assert_eq!(stats.total_secs, 4380);
+        assert_eq!(stats.average_secs(), 876);
+        assert_eq!(stats.longest_secs, 2100);
+        assert_eq!(stats.idle_breaks, 2);
+        assert_eq!(stats.idle_secs, 3420);
+        assert_eq!(stats.label(0), "5 breaks, 1h 13m total, avg 14m, longest 35m");
+        assert_eq!(stats.idle_label(0).as_deref(), Some("2 idle (10m+), 57m"));
+
+        let none = AfkStats::from_sessions(&[]);
+        assert_eq!(none.average_secs(), 0);
+        assert_eq!(none.label(0), "No breaks yet");
+        assert_eq!(none.idle_label(0), None);
+        assert_eq!(AfkStats::from_sessions(&[(900, false)]).label(0), "1 break, 15m total, avg 15m, longest 15m");
+    }
+
+    #[test]
+    fn test_hostname_usage_label() {
+        assert_eq!(hostname_usage_label(&[], 0), None);
+        assert_eq!(hostname_usage_label(&[("laptop".to_string(), 10800)], 0), None);
+        let usage = [("desktop".to_string(), 18720), ("laptop".to_string(), 10800), ("unknown".to_string(), 300)];
+        assert_eq!(hostname_usage_label(&usage, 0).as_deref(), Some("desktop 5h 12m / laptop 3h 0m / unknown 5m"));
+    }
+
+    #[test]
+    fn test_tmux_status() {
+        let usage = [
+            ("💻 Development".to_string(), 15120),
+            ("🌐 Browsing".to_string(), 3780),
+            ("Other".to_string(), 600),
+        ];
+        assert_eq!(tmux_status(&usage, 2, 0), "💻4h12m 🌐1h03m");
+        assert_eq!(tmux_status(&usage, 5, 0), "💻4h12m 🌐1h03m Other10m");
+        assert_eq!(tmux_status(&usage, 2, 15), "💻4h15m 🌐1h00m");
+        assert_eq!(tmux_status(&[], 2, 0), "");
+    }
+
+    #[test]
+    fn test_compute_streak_today_not_yet_active() {
+        let days = vec![date(9), date(8)];
+        assert_eq!(compute_streak(&days, date(10)), 2);
+    }
+
+    #[test]
+    fn test_iso_week_totals_year_boundary() {
+        // 2020 has 53 ISO weeks; Monday 2024-12-30 already starts 2025-W01
+        let weeks = iso_week_totals(
+            &[(2020, 53, 3600), (2021, 1, 7200)],
+            NaiveDate::from_ymd_opt(2020, 12, 24).unwrap(),
+            NaiveDate::from_ymd_opt(2021, 1, 12).unwrap(),
+        );
+        assert_eq!(weeks, vec![(2020, 52, 0), (2020, 53, 3600), (2021, 1, 7200), (2021, 2, 0)]);
+
+        let weeks = iso_week_totals(
+            &[(2025, 1, 1800)],
+            NaiveDate::from_ymd_opt(2024, 12, 23).unwrap(),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap(),
+        );
+        assert_eq!(weeks, vec![(2024, 52, 0), (2025, 1, 1800)]);
+    }
+
+    #[test]
+    fn test_weekday_averages_partial_week() {
+        // Monday 2025-10-06 through Wednesday 2025-10-15: two Mondays..Wednesdays, one Thursday..Sunday
+        let start = NaiveDate::from_ymd_opt(2025, 10, 6).unwrap();
+        let end = NaiveDate::from_ymd_opt(2025, 10, 15).unwrap();
+        let averages = weekday_averages([7200, 3600, 0, 1800, 0, 0, 0], start, end);
+
+        assert_eq!(averages[0], 3600.0);
+        assert_eq!(averages[1], 1800.0);
+        assert_eq!(averages[3], 1800.0);
+        assert_eq!(averages[6], 0.0);
+    }
+
+    #[test]
+    fn test_tracking_since_label() {
+        let today = NaiveDate::from_ymd_opt(2024, 7, 21).unwrap();
+        assert_eq!(
+            tracking_since_label(NaiveDate::from_ymd_opt(2024, 3, 1), today),
+            "Tracking since 2024-03-01 (142 days)"
+        );
+        assert_eq!(tracking_since_label(Some(today), today), "Tracking since 2024-07-21 (0 days)");
+        assert_eq!(tracking_since_label(None, today), "No data yet");
+    }
+
+    #[test]
+    fn test_billable_amount() {
+        let usage = vec![
+            ("💻 Development".to_string(), 5400),
+            ("🌐 Browsing".to_string(), 3600),
+            ("📅 Meetings".to_string(), 1800),
+        ];
+        let billable = vec!["💻 Development".to_string(), "📅 Meetings".to_string()];
+        assert!((billable_amount(&usage, &billable, 80.0) - 160.0).abs() < 1e-9);
+        assert_eq!(billable_amount(&usage, &[], 80.0), 0.0);
+
+        // Sub-categories are billable with their parent
+        let usage = vec![("💻 Development/Rust".to_string(), 3600), ("🌐 Browsing/Research".to_string(), 3600)];
+        assert!((billable_amount(&usage, &billable, 80.0) - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_sort_categories_groups_subcategories() {
+        assert_eq!(parent_category("💻 Development/Rust"), "💻 Development");
+        assert_eq!(parent_category("🌐 Browsing"), "🌐 Browsing");
+
+        let totals = BTreeMap::from([
+            ("💻 Development".to_string(), 600),
+            ("💻 Development/Rust".to_string(), 1800),
+            ("💻 Development/Frontend".to_string(), 900),
+            ("🌐 Browsing".to_string(), 3000),
+            ("📅 Meetings".to_string(), 300),
+        ]);
+        let order: Vec<String> = sort_categories(totals.clone(), &[]).into_iter().map(|(category, _)| category).collect();
+        // Development's family (3300s) outranks Browsing; the parent leads its children
+        assert_eq!(order, [
+            "💻 Development", "💻 Development/Rust", "💻 Development/Frontend", "🌐 Browsing", "📅 Meetings",
+        ]);
+
+        let order: Vec<String> = sort_categories(totals, &["📅 Meetings".to_string()]).into_iter().map(|(category, _)| category).collect();
+        assert_eq!(order[0], "📅 Meetings");
+    }
+
+    #[test]
+    fn test_week_start_date() {
+        // 2025-03-12 is a Wednesday
+        let wednesday = date(12);
+        assert_eq!(week_start_date(wednesday, WeekStart::Rolling), date(6));
+        assert_eq!(week_start_date(wednesday, WeekStart::Monday), date(10));
+        assert_eq!(week_start_date(wednesday, WeekStart::Sunday), date(9));
+        // The week's first day starts a new week
+        assert_eq!(week_start_date(date(10), WeekStart::Monday), date(10));
+        assert_eq!(week_start_date(date(9), WeekStart::Monday), date(3));
+        assert_eq!(week_start_date(date(9), WeekStart::Sunday), date(9));
+    }
+
+    #[test]
+    fn test_notification_trend() {
+        use chrono::TimeZone;
+        let now = Local.with_ymd_and_hms(2025, 3, 10, 14, 30, 0).unwrap();
+        let hour = |h: u32| Local.with_ymd_and_hms(2025, 3, 10, h, 0, 0).unwrap();
+        let points = vec![(hour(9), 7), (hour(11), 3), (hour(14), 5)];
+
+        // 10:00..=14:00; the 09:00 reading is out of range, 12:00 and 13:00 carry 11:00's count
+        assert_eq!(notification_trend(&points, now, 5), vec![0, 3, 3, 3, 5]);
+        assert_eq!(notification_trend(&[], now, 3), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_compute_streak_broken_by_missing_day() {
+        let days = vec![date(8), date(7)];
+        assert_eq!(compute_streak(&days, date(10)), 0);
+        assert_eq!(compute_streak(&[], date(10)), 0);
+    }
+
+    #[test]
+    fn test_category_days() {
+        let start = NaiveDate::from_ymd_opt(2026, 10, 12).unwrap();
+        let day = |offset| start + chrono::Duration::days(offset);
+        let rows = vec![
+            (day(0), "🌐 Browsing".to_string(), 600),
+            (day(0), "💻 Development/Rust".to_string(), 1800),
+            (day(0), "💻 Development".to_string(), 1200),
+            (day(2), "📧 Email".to_string(), 300),
+        ];
+
+        let days = category_days(&rows, start, 3);
+        assert_eq!(days.len(), 3);
+        assert_eq!(days[0], (day(0), vec![("💻 Development".to_string(), 3000), ("🌐 Browsing".to_string(), 600)]));
+        // A day with nothing recorded keeps its column
+        assert_eq!(days[1], (day(1), vec![]));
+        assert_eq!(days[2], (day(2), vec![("📧 Email".to_string(), 300)]));
+    }
+
+    #[test]
+    fn test_renamed_category() {
+        assert_eq!(renamed_category("🎮 Gaming", "🎮 Gaming", "🕹️ Games").as_deref(), Some("🕹️ Games"));
+        // Sub-categories follow their parent
+        assert_eq!(renamed_category("🎮 Gaming/Steam", "🎮 Gaming", "🕹️ Games").as_deref(), Some("🕹️ Games/Steam"));
+        assert_eq!(renamed_category("🎮 Gaming / Steam", "🎮 Gaming", "🕹️ Games").as_deref(), Some("🕹️ Games / Steam"));
+        // A longer name sharing the prefix is a different category
+        assert_eq!(renamed_category("🎮 Gaming Club", "🎮 Gaming", "🕹️ Games"), None);
+        assert_eq!(renamed_category("🌐 Browsing", "🎮 Gaming", "🕹️ Games"), None);
+    }
+
+    #[test]
+    fn test_daemon_warning() {
+        let now = Local::now();
+        let ago = |secs: i64| Some(now - chrono::Duration::seconds(secs));
+
+        assert_eq!(daemon_warning(ago(45), now, 120), None);
+        assert_eq!(daemon_warning(ago(300), now, 120).as_deref(), Some("⚠ Daemon not running (last seen 5m ago)"));
+        assert_eq!(daemon_warning(ago(2 * 3600 + 600), now, 120).as_deref(), Some("⚠ Daemon not running (last seen 2h ago)"));
+        assert_eq!(daemon_warning(ago(3 * 86400), now, 120).as_deref(), Some("⚠ Daemon not running (last seen 3d ago)"));
+        // Never ran here, or the warning is off
+        assert_eq!(daemon_warning(None, now, 120), None);
+        assert_eq!(daemon_warning(ago(86400), now, 0), None);
+    }
+
+    #[test]
+    fn test_app_limit_banner() {
+        assert_eq!(app_limit_banner(&[], 0), None);
+        let alerts = vec![("firefox".to_string(), 3900, 60), ("slack".to_string(), 1860, 30)];
+        assert_eq!(
+            app_limit_banner(&alerts, 0).as_deref(),
+            Some("⛔ firefox 1h 5m today (limit 1h 0m) · slack 31m today (limit 30m)")
+        );
+    }
+
+    #[test]
+    fn test_away_usage_items() {
+        let usage = vec![("Firefox".to_string(), 7200), ("AFK".to_string(), 1800), ("Idle".to_string(), 0)];
+        let rows = |live| -> Vec<(String, i64, Option<String>)> {
+            away_usage_items(&usage, live).into_iter().map(|item| (item.display_name, item.duration, item.category)).collect()
+        };
+
+        // Apps stay out, and Idle has no time yet
+        assert_eq!(rows(None), vec![("AFK".to_string(), 1800, Some("💤 Away".to_string()))]);
+        // The in-progress away session counts towards its pseudo-app
+        assert_eq!(
+            rows(Some(("Idle", 600))),
+            vec![("AFK".to_string(), 1800, Some("💤 Away".to_string())), ("Idle".to_string(), 600, Some("💤 Away".to_string()))]
+        );
+        assert_eq!(rows(Some(("AFK", 60)))[0].1, 1860);
+        assert!(away_usage_items(&[], None).is_empty());
+    }
+
+    fn history_session(hour: u32, minute: u32, duration: i64, app: &str) -> Session {
+        Session::for_test(app, Local.with_ymd_and_hms(2025, 3, 11, hour, minute, 0).unwrap(), duration)
+    }
+
+    #[test]
+    fn test_history_rows_merge_same_app() {
+        // Newest first, as the history page loads them
+        let sessions = [
+            history_session(10, 30, 600, "firefox"),
+            history_session(10, 10, 900, "firefox"),
+            history_session(10, 0, 300, "code"),
+            history_session(9, 50, 300, "firefox"),
+            history_session(9, 0, 1800, "code"),
+            history_session(8, 30, 1200, "code"),
+        ];
+        let refs: Vec<&Session> = sessions.iter().collect();
+        let summary = |rows: Vec<HistoryRow>| -> Vec<(String, String, String, i64, usize)> {
+            rows.into_iter()
+                .map(|row| (row.session.app_name, row.start_time.format("%H:%M").to_string(), row.end_time.format("%H:%M").to_string(), row.duration, row.merged))
+                .collect()
+        };
+
+        // Off, every session keeps its own row
+        assert_eq!(history_rows(&refs, false).len(), 6);
+
+        // Runs merge into one row spanning them, summing durations but not the gaps between them;
+        // a different app in between keeps firefox's runs apart
+        let merged = summary(history_rows(&refs, true));
+        assert_eq!(merged, vec![
+            ("firefox".to_string(), "10:10".to_string(), "10:40".to_string(), 1500, 2),
+            ("code".to_string(), "10:00".to_string(), "10:05".to_string(), 300, 1),
+            ("firefox".to_string(), "09:50".to_string(), "09:55".to_string(), 300, 1),
+            ("code".to_string(), "08:30".to_string(), "09:30".to_string(), 3000, 2),
+        ]);
+
+        // The row keeps the latest session in the run, for splitting and tagging
+        let rows = history_rows(&refs, true);
+        assert_eq!(rows[0].session.start_time, sessions[0].start_time);
+
+        // Out-of-order input is sorted by time before merging
+        let shuffled = [&sessions[4], &sessions[0], &sessions[5], &sessions[1]];
+        assert_eq!(summary(history_rows(&shuffled, true)), vec![
+            ("firefox".to_string(), "10:10".to_string(), "10:40".to_string(), 1500, 2),
+            ("code".to_string(), "08:30".to_string(), "09:30".to_string(), 3000, 2),
+        ]);
+
+        assert!(history_rows(&[], true).is_empty());
+    }
+
+    #[test]
+    fn test_other_hint() {
+        let other = vec![("foo-app".to_string(), 3600), ("bar".to_string(), 1800), ("baz".to_string(), 600), ("qux".to_string(), 60)];
+        // 1h 41m of Other out of 4h is 42%
+        assert_eq!(
+            other_hint(&other, 14400, 25, 0).as_deref(),
+            Some("42% of this view is 📦 Other. Top unrecognized apps: foo-app 1h 0m, bar 30m, baz 10m. Press [c] to give them a category")
+        );
+        assert_eq!(other_hint(&other, 14400, 50, 0), None);
+        assert_eq!(other_hint(&other, 14400, 0, 0), None);
+        assert_eq!(other_hint(&[], 14400, 25, 0), None);
+        assert_eq!(other_hint(&other, 0, 25, 0), None);
+    }
+}
@@ -1,7 +1,12 @@
 pub mod app;
 pub mod commands;
 pub mod hierarchical;
+pub mod icons;
+pub mod metrics;
 pub mod parser;
 pub mod render;
+pub mod reports;
 pub mod session;
+pub mod theme;
 pub mod tracking;
+pub mod ui_state;
@@ -1,45 +1,192 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     text::Line,
-    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, Paragraph},
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, List, ListItem, Paragraph, Sparkline},
     style::{Color, Style, Modifier},
     Frame,
 };
  use chrono::Local;
  use std::collections::BTreeMap;
- use crate::ui::app::{App, AppState, InputAction, ViewMode};
+ use std::sync::atomic::{AtomicBool, Ordering};
+ use crate::config::settings::{DashboardPanel, TimeFormat, WeekStart};
+ use crate::models::session::Session;
+ use crate::ui::app::{App, AppState, InputAction, SettingsField, ViewMode};
+ use crate::ui::icons;
+ use crate::ui::theme;
+
+/// Smallest terminal the layout is drawn in; below this only a "too small" message is shown
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+
+pub fn is_too_small(size: Rect) -> bool {
+    size.width < MIN_WIDTH || size.height < MIN_HEIGHT
+}
 
-pub fn draw(app: &App, f: &mut Frame) {
-    let size = f.area();
-    let chunks = Layout::default()
+/// Rows a panel needs in the vertical dashboard
+fn panel_min_height(panel: DashboardPanel) -> u16 {
+    match panel {
+        DashboardPanel::Bar | DashboardPanel::History => 10,
+        DashboardPanel::Timeline | DashboardPanel::Afk | DashboardPanel::Stats | DashboardPanel::Pie | DashboardPanel::Switches => 8,
+    }
+}
+
+/// Relative height of a panel's row in the two-column dashboard
+fn panel_weight(panel: DashboardPanel) -> u16 {
+    match panel {
+        DashboardPanel::History => 45,
+        DashboardPanel::Bar => 40,
+        DashboardPanel::Timeline | DashboardPanel::Afk | DashboardPanel::Stats | DashboardPanel::Pie => 30,
+        DashboardPanel::Switches => 25,
+    }
+}
+
+/// Rows of the two-column dashboard in `layout` order. Timeline and AFK panels listed next to each
+/// other share a row, as they do in the default layout
+pub fn dashboard_rows(layout: &[DashboardPanel]) -> Vec<Vec<DashboardPanel>> {
+    let mut rows: Vec<Vec<DashboardPanel>> = Vec::new();
+    for &panel in layout {
+        let pairs = |row: &Vec<DashboardPanel>| {
+            row.len() == 1
+                && matches!(
+                    (row[0], panel),
+                    (DashboardPanel::Timeline, DashboardPanel::Afk) | (DashboardPanel::Afk, DashboardPanel::Timeline)
+                )
+        };
+        match rows.last_mut() {
+            Some(row) if pairs(row) => row.push(panel),
+            _ => rows.push(vec![panel]),
+        }
+    }
+    rows
+}
+
+/// Status bar and main content areas; shared with mouse hit-testing so clicks line up with what is drawn
+pub fn screen_chunks(size: Rect) -> std::rc::Rc<[Rect]> {
+    Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(1)].as_ref())
-        .split(size);
+        .split(size)
+}
+
+/// Index of the list row at the given terminal position inside a bordered list block
+pub fn list_row_at(area: Rect, column: u16, row: u16) -> Option<usize> {
+    let inner = area.inner(ratatui::layout::Margin { horizontal: 1, vertical: 1 });
+    let inside = column >= inner.x && column < inner.x + inner.width && row >= inner.y && row < inner.y + inner.height;
+    inside.then(|| (row - inner.y) as usize)
+}
+
+fn history_popup_area(size: Rect) -> Rect {
+    App::centered_rect(80, 70, size)
+}
+
+fn history_max_visible_items(popup_area: Rect) -> usize {
+    (popup_area.height.saturating_sub(4) as usize).max(10)
+}
+
+/// Rows shown in the history popup, filtered and merged at render time so the underlying history stays intact
+pub fn history_filtered(history_page: &[Session], hide_afk: bool, merge_same_app: bool) -> Vec<crate::ui::metrics::HistoryRow> {
+    let sessions: Vec<&Session> = history_page.iter()
+        .filter(|session| !hide_afk || !App::is_away_session(session))
+        .collect();
+    crate::ui::metrics::history_rows(&sessions, merge_same_app)
+}
+
+/// Filtered-history index of the row drawn at a terminal position in the history popup.
+/// Gap and note lines belong to the row above them.
+pub fn history_session_at(app: &App, hide_afk: bool, merge_same_app: bool, scroll_position: usize, size: Rect, column: u16, row: u16) -> Option<usize> {
+    let popup_area = history_popup_area(size);
+    let clicked_row = list_row_at(popup_area, column, row)?;
+
+    let filtered_history = history_filtered(&app.history_page, hide_afk, merge_same_app);
+    let start_idx = scroll_position.min(filtered_history.len());
+    let end_idx = (start_idx + history_max_visible_items(popup_area)).min(filtered_history.len());
+    let visible_sessions = &filtered_history[start_idx..end_idx];
+
+    let mut row_idx = 0;
+    for (idx, history_row) in visible_sessions.iter().enumerate() {
+        let has_gap = visible_sessions.get(idx + 1).and_then(|next| session_gap_secs(history_row, next)).is_some();
+        let shows_note = idx == 0 && history_row.session.note.is_some() && !app.redact_titles;
+        row_idx += 1 + usize::from(has_gap) + usize::from(shows_note);
+        if clicked_row < row_idx {
+            return Some(start_idx + idx);
+        }
+    }
+    None
+}
+
+pub fn draw(app: &App, f: &mut Frame) {
+    let size = f.area();
+    if is_too_small(size) {
+        let message = format!("Terminal too small (need at least {}x{})", MIN_WIDTH, MIN_HEIGHT);
+        f.render_widget(Paragraph::new(message).wrap(ratatui::widgets::Wrap { trim: true }), size);
+        return;
+    }
+    let chunks = screen_chunks(size);
 
     // Status bar with Shift+C indicator
     let status = match &app.state {
         AppState::Dashboard { .. } => {
-            if let Some(session) = &app.current_session {
+            let mut badges = if app.streak_days > 0 {
+                format!(" | 🔥 {}-day streak", app.streak_days)
+            } else {
+                String::new()
+            };
+            badges.push_str(&format!(
+                " | {}",
+                crate::ui::metrics::tracking_since_label(app.first_session_date, Local::now().date_naive())
+            ));
+            if let Some(machines) = app.hostname_usage_label() {
+                badges.push_str(&format!(" | 🖥️ {}", machines));
+            }
+            if let Some(warning) = app.daemon_warning() {
+                badges.push_str(&format!(" | {}", warning));
+            }
+            if let Some(message) = app.flash_message() {
+                badges.push_str(&format!(" | {}", message));
+            }
+            if let Some(database) = &app.viewer_database {
+                format!("Viewing {} (read-only) | [Shift+C] Commands | [h] History{}", database, badges)
+            } else if let Some(session) = &app.current_session {
                 let duration = Local::now().signed_duration_since(session.start_time).num_seconds();
-                let display_name = app.manual_app_name.as_ref().unwrap_or(&session.app_name);
-                format!("Tracking: {} for {}s | [Shift+C] Commands | [h] History", display_name, duration)
+                if let Some(tag) = &session.tag {
+                    let sticky = if app.sticky_tag.as_ref() == Some(tag) { " (sticky)" } else { "" };
+                    badges.insert_str(0, &format!(" | 🏷️ {}{}", tag, sticky));
+                }
+                let display_name = app.manual_app_name.clone().unwrap_or_else(|| App::clean_app_name(&session.app_name));
+                format!("Tracking: {} for {}s{} | [Shift+C] Commands | [h] History", display_name, duration, badges)
             } else {
-                format!("Not tracking - Current app: {} | [Shift+C] Commands | [h] History", app.current_app)
+                format!("Not tracking - Current app: {} | [Shift+C] Commands | [h] History{}", App::clean_app_name(&app.current_app), badges)
             }
         }
+        AppState::ViewingLogs if app.follow_logs => "Following detection live (--follow) - [q] Quit".to_string(),
         AppState::ViewingLogs => "Viewing Logs - Press any key to return".to_string(),
         AppState::SelectingApp { .. } => "Rename Mode - Use arrow keys to select an app".to_string(),
         AppState::SelectingCategory { .. } => "Category Mode - Use arrow keys to select an app".to_string(),
         AppState::CategoryMenu { .. } => "Category Mode - Use arrow keys to select a category".to_string(),
+        AppState::SelectingCategoryToRename { .. } => "Rename Category - Use arrow keys to select a category".to_string(),
         AppState::Input { action, .. } => {
             match action {
                 InputAction::RenameApp { .. } => "Rename Mode - Enter new name for the app".to_string(),
-                InputAction::CreateCategory { .. } => "Category Mode - Enter custom category name (e.g., 🎮 Gaming)".to_string(),
+                InputAction::CreateCategory { .. } => "Category Mode - Enter custom category name (e.g., 🎮 Gaming or 💻 Development/Rust)".to_string(),
+                InputAction::RenameCategory { .. } => "Rename Category - Enter the new name, Esc to cancel".to_string(),
+                InputAction::SplitSession { .. } => "Split Mode - Enter the split time and an optional new app, Esc to cancel".to_string(),
+                InputAction::TagSession { .. } => "Tag Mode - Enter a project or ticket label, empty to clear, Esc to cancel".to_string(),
+                InputAction::NoteSession { .. } => "Note Mode - Enter a note for the session, empty to remove it, Esc to cancel".to_string(),
+                InputAction::JumpToDate { .. } => "History - Enter a date to show that day's sessions, Esc to cancel".to_string(),
+                InputAction::MarkAway => "Mark AFK - Enter how many minutes ago you left, Esc to cancel".to_string(),
+                InputAction::Search { .. } => "Search - Enter a word to find in your sessions (case-insensitive), Esc to cancel".to_string(),
             }
         }
         AppState::CommandsPopup => "Commands Menu - Press key to execute or Esc to close".to_string(),
-        AppState::HistoryPopup { .. } => "Session History - Use ↑/↓/PgUp/PgDn to scroll, Esc to close".to_string(),
-        AppState::BreakdownDashboard { .. } => "📊 Activity Breakdown Dashboard - [Tab] Switch Panels | [Enter] Select | [↑/↓/PgUp/PgDn] Navigate | [Esc] Close".to_string(),
+        AppState::HistoryPopup { .. } => "Session History - Use ↑/↓/PgUp/PgDn to scroll, [g] go to date, [ / ] previous/next day, [f] toggle AFK/idle, [m] merge same-app runs, [x] split / [t] tag / [n] note top session, Esc to close".to_string(),
+        AppState::BreakdownDashboard { .. } => "📊 Activity Breakdown Dashboard - [Tab] Switch Panels | [Enter] Select | [↑/↓/PgUp/PgDn] Navigate | [c] Collapse/Expand Sub-categories | [Esc] Close".to_string(),
+        AppState::SearchResults { .. } => "Search Results - Use ↑/↓/PgUp/PgDn to scroll, [/] new search, Esc to close".to_string(),
+        AppState::CodingStats => "Coding Stats (this week) - Press any key to return".to_string(),
+        AppState::WeekdayAverages => "Weekday Averages - Press any key to return".to_string(),
+        AppState::WeeklyTotals => "Weekly Totals - Press any key to return".to_string(),
+        AppState::CategoryDays => "Category Mix per Day - Press any key to return".to_string(),
+        AppState::ConfirmQuit => "Quit? [y] Save and quit | [n/Esc] Cancel".to_string(),
+        AppState::Settings { .. } => "Settings - [↑/↓] Select | [←/→] Adjust | [Enter] Save | [Esc] Cancel".to_string(),
     };
 
     let status_widget = Paragraph::new(status)
@@ -48,6 +195,17 @@ pub fn draw(app: &App, f: &mut Frame) {
 
     // Main content area
     match &app.state {
+        AppState::ViewingLogs if app.follow_logs => {
+            // Tail: as many of the latest lines as fit, newest at the bottom
+            let visible = chunks[1].height.saturating_sub(2) as usize;
+            let log_items: Vec<ListItem> = app.logs[app.logs.len().saturating_sub(visible)..]
+                .iter()
+                .map(|log| ListItem::new(Line::from(log.clone())))
+                .collect();
+            let log_list = List::new(log_items)
+                .block(Block::default().borders(Borders::ALL).title(emoji_label("📡 Live Log")));
+            f.render_widget(log_list, chunks[1]);
+        }
         AppState::ViewingLogs => {
             let log_items: Vec<ListItem> = app
                 .logs
@@ -61,26 +219,19 @@ pub fn draw(app: &App, f: &mut Frame) {
             f.render_widget(log_list, chunks[1]);
         }
 
-        AppState::SelectingApp { selected_index, selected_unique_id: _ } => {
+        AppState::SelectingApp { selected_index, items, .. } => {
             // Full-screen app selection view
-            let max_items = (chunks[1].height.saturating_sub(2) as usize).min(app.daily_usage.len()).max(5);
+            // Snapshot taken on entry so background refreshes don't move rows under the cursor
+            let selector_usage = app.shown_items(items);
+            let max_items = (chunks[1].height.saturating_sub(2) as usize).min(selector_usage.len()).max(5);
             let mut last_parent_color = Color::White;
-            let usage_items: Vec<ListItem> = app
-                .daily_usage
+            let usage_items: Vec<ListItem> = selector_usage
                 .iter()
                 .enumerate()
                 .take(max_items)
                 .map(|(i, item)| {
-                    let duration = item.duration;
-                    let hours = duration / 3600;
-                    let minutes = (duration % 3600) / 60;
                     let prefix = if i == *selected_index { "→ " } else { "  " };
-
-                    let time_display = if hours > 0 {
-                        format!("{}h {}m", hours, minutes)
-                    } else {
-                        format!("{}m", minutes)
-                    };
+                    let time_display = format_duration(item.duration, app.rounding_minutes);
 
                     let clean_app = App::clean_app_name(&item.display_name);
                     let (_, color) = if item.is_sub_entry {
@@ -114,30 +265,23 @@ pub fn draw(app: &App, f: &mut Frame) {
             let usage_list = List::new(usage_items)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title("📝 Select App to Rename (↑/↓ to navigate, Enter to select, Esc to cancel)"));
+                    .title(emoji_label("📝 Select App to Rename (↑/↓ to navigate, Enter to select, Esc to cancel)")));
             f.render_widget(usage_list, chunks[1]);
         }
 
-        AppState::SelectingCategory { selected_index, selected_unique_id: _ } => {
+        AppState::SelectingCategory { selected_index, items, .. } => {
             // Full-screen app selection view for category assignment
-            let max_items = (chunks[1].height.saturating_sub(2) as usize).min(app.daily_usage.len()).max(5);
+            // Snapshot taken on entry so background refreshes don't move rows under the cursor
+            let selector_usage = app.shown_items(items);
+            let max_items = (chunks[1].height.saturating_sub(2) as usize).min(selector_usage.len()).max(5);
             let mut last_parent_color = Color::White;
-            let usage_items: Vec<ListItem> = app
-                .daily_usage
+            let usage_items: Vec<ListItem> = selector_usage
                 .iter()
                 .enumerate()
                 .take(max_items)
                 .map(|(i, item)| {
-                    let duration = item.duration;
-                    let hours = duration / 3600;
-                    let minutes = (duration % 3600) / 60;
                     let prefix = if i == *selected_index { "→ " } else { "  " };
-
-                    let time_display = if hours > 0 {
-                        format!("{}h {}m", hours, minutes)
-                    } else {
-                        format!("{}m", minutes)
-                    };
+                    let time_display = format_duration(item.duration, app.rounding_minutes);
 
                     let clean_app = App::clean_app_name(&item.display_name);
                     let (category, color) = if item.is_sub_entry {
@@ -154,7 +298,7 @@ pub fn draw(app: &App, f: &mut Frame) {
                         last_parent_color = color;
                     }
 
-                    let display = format!("{}{:<30} {} [{}]", prefix, clean_app, time_display, category);
+                    let display = format!("{}{:<30} {} [{}]", prefix, clean_app, time_display, emoji_label(&category));
 
                     let style = if i == *selected_index {
                         Style::default().fg(Color::Yellow)
@@ -171,7 +315,7 @@ pub fn draw(app: &App, f: &mut Frame) {
             let usage_list = List::new(usage_items)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title("🏷️  Select App to Change Category (↑/↓ to navigate, Enter to select, Esc to cancel)"));
+                    .title(emoji_label("🏷️  Select App to Change Category (↑/↓ to navigate, Enter to select, Esc to cancel)")));
             f.render_widget(usage_list, chunks[1]);
         }
 
@@ -183,7 +327,7 @@ pub fn draw(app: &App, f: &mut Frame) {
                 .enumerate()
                 .map(|(i, category)| {
                     let prefix = if i == *selected_index { "→ " } else { "  " };
-                    let display = format!("{}{}", prefix, category);
+                    let display = format!("{}{}", prefix, emoji_label(category));
 
                     let style = if i == *selected_index {
                         Style::default().fg(Color::Yellow)
@@ -208,7 +352,29 @@ pub fn draw(app: &App, f: &mut Frame) {
             let category_list = List::new(category_items)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title(title));
+                    .title(emoji_label(&title)));
+            f.render_widget(category_list, chunks[1]);
+        }
+
+        AppState::SelectingCategoryToRename { selected_index, categories } => {
+            let category_items: Vec<ListItem> = categories
+                .iter()
+                .enumerate()
+                .map(|(i, category)| {
+                    let prefix = if i == *selected_index { "→ " } else { "  " };
+                    let style = if i == *selected_index {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default().fg(App::category_from_string(category).1)
+                    };
+                    ListItem::new(Line::from(format!("{}{}", prefix, emoji_label(category)))).style(style)
+                })
+                .collect();
+
+            let category_list = List::new(category_items)
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji_label("✏️  Select Category to Rename (↑/↓ to navigate, Enter to select, Esc to cancel)")));
             f.render_widget(category_list, chunks[1]);
         }
 
@@ -223,12 +389,20 @@ pub fn draw(app: &App, f: &mut Frame) {
             let title = match action {
                 InputAction::RenameApp { .. } => "✏️  Rename App",
                 InputAction::CreateCategory { .. } => "🏷️  Create Custom Category",
+                InputAction::RenameCategory { .. } => "✏️  Rename Category",
+                InputAction::SplitSession { .. } => "✂️  Split Session",
+                InputAction::TagSession { sticky: true, .. } => "🏷️  Sticky Tag",
+                InputAction::TagSession { .. } => "🏷️  Tag Session",
+                InputAction::NoteSession { .. } => "📝 Session Note",
+                InputAction::JumpToDate { .. } => "📅 Go to Date",
+                InputAction::MarkAway => "💤 Mark as AFK",
+                InputAction::Search { .. } => "🔎 Search",
             };
 
-            // Create input text with cursor
-            let input_text = vec![
-                Line::from(""),
-                Line::from(prompt.clone()).style(Style::default().fg(Color::Cyan)),
+            // Create input text with cursor (the prompt may carry an extra info line)
+            let mut input_text = vec![Line::from("")];
+            input_text.extend(prompt.lines().map(|line| Line::from(line.to_string()).style(Style::default().fg(Color::Cyan))));
+            input_text.extend([
                 Line::from(""),
                 Line::from(vec![
                     ratatui::text::Span::styled("  ", Style::default()),
@@ -237,12 +411,12 @@ pub fn draw(app: &App, f: &mut Frame) {
                 ]),
                 Line::from(""),
                 Line::from("  Press Enter to confirm, Esc to cancel").style(Style::default().fg(Color::Gray)),
-            ];
+            ]);
 
             let input_widget = Paragraph::new(input_text)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title(title)
+                    .title(emoji_label(title))
                     .style(Style::default().bg(Color::Black)));
 
             f.render_widget(input_widget, input_area);
@@ -265,9 +439,21 @@ pub fn draw(app: &App, f: &mut Frame) {
                 Line::from("  [Tab]  Switch View (Daily/Weekly/Monthly)"),
                 Line::from("  [h]    View session history (scrollable popup)"),
                 Line::from("  [b]    View activity breakdowns (scrollable popup)"),
+                Line::from("  [e]    Coding stats: top files and time by language this week"),
+                Line::from("  [w]    Average active time per weekday"),
+                Line::from("  [W]    Total active time per ISO week over the last 12 weeks"),
+                Line::from("  [m]    Category mix per day over the last week"),
+                Line::from("  [p]    Hide/show window titles (for screen sharing)"),
+                Line::from("  [a]    Show/hide AFK and idle time as bars"),
+                Line::from("  [A]    Mark the last N minutes of the current session as AFK"),
+                Line::from("  [t]    Tag the current session (project/ticket); [T] keeps the tag for new sessions"),
+                Line::from("  [/]    Search sessions: total time on anything matching a word"),
+                Line::from("  [y]    Copy the current session (app, name, duration) to the clipboard"),
                 Line::from("  [r]    Rename app/tab"),
                 Line::from("  [c]    Change app category"),
+                Line::from("  [R]    Rename a category (all sessions)"),
                 Line::from("  [l]    View logs"),
+                Line::from("  [s]    Settings (AFK threshold, refresh interval, debug logs)"),
                 Line::from("  [q]    Quit application (auto-saves)"),
                 Line::from(""),
                 Line::from("  Press Esc to close this menu"),
@@ -276,62 +462,234 @@ pub fn draw(app: &App, f: &mut Frame) {
             let popup = Paragraph::new(commands_text)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title("📋 Commands Menu")
+                    .title(emoji_label("📋 Commands Menu"))
+                    .style(Style::default().bg(Color::Black)));
+            f.render_widget(popup, popup_area);
+        }
+
+        AppState::ConfirmQuit => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            let popup_area = App::centered_rect(50, 30, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+
+            let session_line = match app.live_session_elapsed() {
+                Some((live_app, elapsed)) => {
+                    format!("  Current session: {} running for {}", live_app, format_duration(elapsed, app.rounding_minutes))
+                }
+                None => "  No active session (AFK)".to_string(),
+            };
+            let confirm_text = vec![
+                Line::from(""),
+                Line::from(session_line),
+                Line::from("  It will be saved before exiting."),
+                Line::from(""),
+                Line::styled("  Quit now? [y] Yes  [n/Esc] No", Style::default().fg(Color::Yellow)),
+            ];
+
+            let popup = Paragraph::new(confirm_text)
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji_label("🚪 Quit"))
                     .style(Style::default().bg(Color::Black)));
             f.render_widget(popup, popup_area);
         }
 
-        AppState::HistoryPopup { view_mode, scroll_position } => {
+        AppState::WeekdayAverages => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            let popup_area = App::centered_rect(70, 50, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            draw_weekday_averages(app, f, popup_area);
+        }
+
+        AppState::WeeklyTotals => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            let popup_area = App::centered_rect(80, 50, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            draw_weekly_totals(app, f, popup_area);
+        }
+
+        AppState::CategoryDays => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            let popup_area = App::centered_rect(80, 60, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            draw_category_days(app, f, popup_area);
+        }
+
+        AppState::SearchResults { view_mode, term, total_secs, sessions, scroll_position } => {
             // Show dashboard in background
             app.draw_dashboard(f, chunks[1], view_mode);
 
-            // Draw popup overlay
             let popup_area = App::centered_rect(80, 70, size);
             f.render_widget(ratatui::widgets::Clear, popup_area);
 
+            let range = match view_mode {
+                ViewMode::Daily => "today",
+                ViewMode::Weekly => "this week",
+                ViewMode::Monthly => "last 30 days",
+            };
+            let items: Vec<ListItem> = if sessions.is_empty() {
+                vec![ListItem::new(Line::from(format!("No sessions match '{}' {}", term, range)))]
+            } else {
+                sessions.iter().skip(*scroll_position).map(|session| {
+                    let display_name = session_label(app, session, App::clean_app_name(&session.app_name), false);
+                    let mut display = format!("{}  {} - {}", session.start_time.format("%Y-%m-%d %H:%M"), display_name, format_duration(session.duration, app.rounding_minutes));
+                    if let Some(tag) = &session.tag {
+                        display.push_str(&format!("  🏷️ {}", tag));
+                    }
+                    ListItem::new(Line::from(display))
+                }).collect()
+            };
+
+            let results = List::new(items)
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji_label(&format!("🔎 '{}' {} - {} in {} sessions", term, range, format_duration(*total_secs, app.rounding_minutes), sessions.len())))
+                    .style(Style::default().bg(Color::Black)));
+            f.render_widget(results, popup_area);
+        }
+
+        AppState::CodingStats => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            let popup_area = App::centered_rect(80, 70, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+            let popup_block = Block::default()
+                .borders(Borders::ALL)
+                .title(emoji_label("🧑‍💻 Coding Stats (this week)"))
+                .style(Style::default().bg(Color::Black));
+            f.render_widget(popup_block, popup_area);
+
+            let inner_area = popup_area.inner(ratatui::layout::Margin { horizontal: 1, vertical: 1 });
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)].as_ref())
+                .split(inner_area);
+
+            draw_breakdown_section_with_style(f, columns[0], "📝 Top Files", &app.shown_breakdown(&app.weekly_top_files), BreakdownOptions { color: Color::Cyan, rounding_minutes: app.rounding_minutes, ..Default::default() });
+            draw_breakdown_section_with_style(f, columns[1], "🔤 Time by Language", &app.weekly_languages, BreakdownOptions { color: Color::Yellow, rounding_minutes: app.rounding_minutes, ..Default::default() });
+        }
+
+        AppState::Settings { fields, selected } => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], &app.current_view_mode);
+
+            // Draw popup overlay
+            let popup_area = App::centered_rect(60, 40, size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+
+            let mut settings_text = vec![Line::from("")];
+            for (idx, field) in fields.iter().enumerate() {
+                let label = match field {
+                    SettingsField::AfkThreshold { secs } => format!("AFK threshold:     {} min", secs / 60),
+                    SettingsField::RefreshInterval { secs } => format!("Refresh interval:  {} s", secs),
+                    SettingsField::DebugLogs { enabled } => format!(
+                        "Debug logging:     {} (requires restart)",
+                        if *enabled { "on" } else { "off" }
+                    ),
+                    SettingsField::Theme { theme: selected } => format!("Theme:             {}", theme::label(*selected)),
+                };
+                if idx == *selected {
+                    settings_text.push(Line::styled(format!("  > {}", label), Style::default().fg(Color::Yellow)));
+                } else {
+                    settings_text.push(Line::from(format!("    {}", label)));
+                }
+            }
+            settings_text.push(Line::from(""));
+            settings_text.push(Line::from("  [←/→] Adjust  [Enter] Save  [Esc] Cancel"));
+
+            let popup = Paragraph::new(settings_text)
+                .block(Block::default()
+                    .borders(Borders::ALL)
+                    .title(emoji_label("⚙️ Settings"))
+                    .style(Style::default().bg(Color::Black)));
+            f.render_widget(popup, popup_area);
+        }
+
+        AppState::HistoryPopup { view_mode, scroll_position, hide_afk, merge_same_app, date } => {
+            // Show dashboard in background
+            app.draw_dashboard(f, chunks[1], view_mode);
+
+            // Draw popup overlay
+            let popup_area = history_popup_area(size);
+            f.render_widget(ratatui::widgets::Clear, popup_area);
+
             // Calculate how many items can fit in the popup
-            let max_visible_items = (popup_area.height.saturating_sub(4) as usize).max(10);
+            let max_visible_items = history_max_visible_items(popup_area);
 
             // Create history list items
             let mut history_items: Vec<ListItem> = Vec::new();
 
-            // Get the visible slice of history based on scroll position
-            let start_idx = *scroll_position;
-            let end_idx = (start_idx + max_visible_items).min(app.current_history.len());
-
-            for (idx, session) in app.current_history[start_idx..end_idx].iter().enumerate() {
-                let minutes = session.duration / 60;
-                let time = session.start_time.format("%Y-%m-%d %H:%M");
+            // Filter at render time so the underlying history stays intact
+            let filtered_history = history_filtered(&app.history_page, *hide_afk, *merge_same_app);
 
-                // Create display name with window name if available
-                let clean_app = App::clean_app_name(&session.app_name);
-                let display_name = if let Some(window_name) = &session.window_name {
-                    format!("{} ({})", clean_app, window_name)
+            // Get the visible slice of history based on scroll position
+            let start_idx = (*scroll_position).min(filtered_history.len());
+            let end_idx = (start_idx + max_visible_items).min(filtered_history.len());
+
+            let visible_sessions = &filtered_history[start_idx..end_idx];
+            for (idx, history_row) in visible_sessions.iter().enumerate() {
+                let session = &history_row.session;
+                let time = history_row.start_time.format("%Y-%m-%d %H:%M");
+                let end_time = history_row.end_time.format("%H:%M");
+
+                // A merged run shows the app alone, since its sessions' window names differ
+                let display_name = if history_row.merged > 1 {
+                    format!("{} ×{}", App::clean_app_name(&session.app_name), history_row.merged)
                 } else {
-                    clean_app
+                    session_label(app, session, App::clean_app_name(&session.app_name), false)
                 };
 
-                let display = format!("{}  {} - {}m", time, display_name, minutes);
-                let style = if idx == 0 && start_idx == 0 {
-                    Style::default().fg(Color::Yellow)  // Highlight first (most recent)
+                let mut display = format!("{} → {}  {} - {}", time, end_time, display_name, format_duration(history_row.duration, app.rounding_minutes));
+                if let Some(tag) = &session.tag {
+                    display.push_str(&format!("  🏷️ {}", tag));
+                }
+                if session.note.is_some() {
+                    display.push_str("  📝");
+                }
+                let style = if idx == 0 {
+                    Style::default().fg(Color::Yellow)  // Highlight the top row, which [x] splits, [t] tags and [n] notes
                 } else {
                     Style::default()
                 };
 
                 history_items.push(ListItem::new(Line::from(display)).style(style));
+
+                // The highlighted session's note is spelled out below it, unless titles are hidden
+                if let Some(note) = session.note.as_ref().filter(|_| idx == 0 && !app.redact_titles) {
+                    history_items.push(ListItem::new(Line::from(format!("    📝 {}", note))).style(Style::default().fg(Color::Cyan)));
+                }
+
+                // Show untracked time between this session and the next one in the list
+                if let Some(gap_secs) = visible_sessions.get(idx + 1).and_then(|next| session_gap_secs(history_row, next)) {
+                    let gap_line = format!("    ⋯ gap {}", format_duration(gap_secs, 0));
+                    history_items.push(ListItem::new(Line::from(gap_line)).style(Style::default().fg(Color::DarkGray)));
+                }
             }
 
             // Add indicator if there are more items to scroll
-            let scroll_indicator = if app.current_history.len() > max_visible_items {
-                format!(" (Showing {}-{} of {} sessions)", start_idx + 1, end_idx, app.current_history.len())
+            let more = if app.history_has_more { "+" } else { "" };
+            let scroll_indicator = if filtered_history.len() > max_visible_items {
+                format!(" (Showing {}-{} of {}{} sessions)", start_idx + 1, end_idx, filtered_history.len(), more)
             } else {
-                format!(" ({} sessions)", app.current_history.len())
+                format!(" ({} sessions)", filtered_history.len())
             };
+            let filter_indicator = if *hide_afk { " [AFK/idle hidden]" } else { "" };
+            let merge_indicator = if *merge_same_app { " [same-app runs merged]" } else { "" };
+            let day = date.map(|date| format!(" - {}", date.format("%a %Y-%m-%d"))).unwrap_or_default();
 
             let history_list = List::new(history_items)
                 .block(Block::default()
                     .borders(Borders::ALL)
-                    .title(format!("📜 Session History{}", scroll_indicator))
+                    .title(emoji_label(&format!("📜 Session History{}{}{}{}", day, scroll_indicator, filter_indicator, merge_indicator)))
                     .style(Style::default().bg(Color::Black)));
             f.render_widget(history_list, popup_area);
         }
@@ -347,7 +705,7 @@ pub fn draw(app: &App, f: &mut Frame) {
             // Main popup container
             let popup_block = Block::default()
                 .borders(Borders::ALL)
-                .title("📊 Activity Breakdown Dashboard")
+                .title(emoji_label("📊 Activity Breakdown Dashboard"))
                 .style(Style::default().bg(Color::Black));
             f.render_widget(popup_block, popup_area);
 
@@ -397,11 +755,11 @@ pub fn draw(app: &App, f: &mut Frame) {
                     Style::default()
                 };
 
-                draw_breakdown_section_with_style(f, sections[0], "📦 Categories", &app.category_breakdown, Color::Magenta, true, category_style, panel_scrolls[0]);
-                draw_breakdown_section_with_style(f, sections[1], "🌐 Browser Services", &app.browser_breakdown, Color::Blue, false, browser_style, panel_scrolls[1]);
-                draw_breakdown_section_with_style(f, sections[2], "📁 Projects", &app.project_breakdown, Color::Yellow, false, project_style, panel_scrolls[2]);
+                draw_breakdown_section_with_style(f, sections[0], "📦 Categories", &app.category_breakdown, BreakdownOptions { color: Color::Magenta, is_category: true, style: category_style, scroll_position: panel_scrolls[0], rounding_minutes: app.rounding_minutes });
+                draw_browser_services(app, f, sections[1], browser_style, panel_scrolls[1]);
+                draw_breakdown_section_with_style(f, sections[2], "📁 Projects", &app.shown_breakdown(&app.project_breakdown), BreakdownOptions { color: Color::Yellow, style: project_style, scroll_position: panel_scrolls[2], rounding_minutes: app.rounding_minutes, ..Default::default() });
                 app.draw_file_breakdown_section_with_style(f, sections[3], panel_scrolls[3], file_style);
-                draw_breakdown_section_with_style(f, sections[4], "💻 Terminal Sessions", &app.terminal_breakdown, Color::Green, false, terminal_style, panel_scrolls[4]);
+                draw_breakdown_section_with_style(f, sections[4], "💻 Terminal Sessions", &app.shown_breakdown(&app.terminal_breakdown), BreakdownOptions { color: Color::Green, style: terminal_style, scroll_position: panel_scrolls[4], rounding_minutes: app.rounding_minutes, ..Default::default() });
             } else {
                 // Grid layout for larger screens
                 let rows = Layout::default()
@@ -455,11 +813,11 @@ pub fn draw(app: &App, f: &mut Frame) {
                     Style::default()
                 };
 
-                draw_breakdown_section_with_style(f, row1_cols[0], "📦 Categories", &app.category_breakdown, Color::Magenta, true, category_style, panel_scrolls[0]);
-                draw_breakdown_section_with_style(f, row1_cols[1], "🌐 Browser Services", &app.browser_breakdown, Color::Blue, false, browser_style, panel_scrolls[1]);
-                draw_breakdown_section_with_style(f, row2_cols[0], "📁 Projects", &app.project_breakdown, Color::Yellow, false, project_style, panel_scrolls[2]);
+                draw_breakdown_section_with_style(f, row1_cols[0], "📦 Categories", &app.category_breakdown, BreakdownOptions { color: Color::Magenta, is_category: true, style: category_style, scroll_position: panel_scrolls[0], rounding_minutes: app.rounding_minutes });
+                draw_browser_services(app, f, row1_cols[1], browser_style, panel_scrolls[1]);
+                draw_breakdown_section_with_style(f, row2_cols[0], "📁 Projects", &app.shown_breakdown(&app.project_breakdown), BreakdownOptions { color: Color::Yellow, style: project_style, scroll_position: panel_scrolls[2], rounding_minutes: app.rounding_minutes, ..Default::default() });
                 app.draw_file_breakdown_section_with_style(f, row2_cols[1], panel_scrolls[3], file_style);
-                draw_breakdown_section_with_style(f, row3_area, "💻 Terminal Sessions", &app.terminal_breakdown, Color::Green, false, terminal_style, panel_scrolls[4]);
+                draw_breakdown_section_with_style(f, row3_area, "💻 Terminal Sessions", &app.shown_breakdown(&app.terminal_breakdown), BreakdownOptions { color: Color::Green, style: terminal_style, scroll_position: panel_scrolls[4], rounding_minutes: app.rounding_minutes, ..Default::default() });
             }
         }
     }
@@ -489,10 +847,49 @@ pub fn draw_dashboard(app: &App, f: &mut Frame, area: Rect, view_mode: &ViewMode
     // Adaptive layout based on terminal size
     let use_vertical_layout = area.width < 120 || area.height < 30;
 
-    let (data, title) = match view_mode {
-        ViewMode::Daily => (app.daily_usage.clone(), "📊 Daily Usage"),
-        ViewMode::Weekly => (app.weekly_usage.clone(), "📊 Weekly Usage (7 days)"),
-        ViewMode::Monthly => (app.monthly_usage.clone(), "📊 Monthly Usage (30 days)"),
+    let (mut data, title) = match view_mode {
+        ViewMode::Daily => (app.shown_items(&app.daily_usage), "📊 Daily Usage"),
+        ViewMode::Weekly => (app.shown_items(&app.weekly_usage), match app.week_start {
+            WeekStart::Rolling => "📊 Weekly Usage (7 days)",
+            WeekStart::Monday => "📊 Weekly Usage (since Monday)",
+            WeekStart::Sunday => "📊 Weekly Usage (since Sunday)",
+        }),
+        ViewMode::Monthly => (app.shown_items(&app.monthly_usage), "📊 Monthly Usage (30 days)"),
+    };
+    add_live_session_time(app, &mut data);
+
+    // Apps past their daily limit get a red banner above the panels
+    let area = match app.app_limit_banner() {
+        Some(banner) if area.height > 6 => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(area);
+            let banner = Paragraph::new(banner)
+                .style(Style::default().fg(Color::White).bg(Color::Red).add_modifier(Modifier::BOLD))
+                .block(Block::default().borders(Borders::ALL).title("App limit reached"));
+            f.render_widget(banner, chunks[0]);
+            chunks[1]
+        }
+        _ => area,
+    };
+
+    // Too much unrecognized time gets a hint line under the panels
+    let total_secs: i64 = data.iter().filter(|item| !item.is_sub_entry).map(|item| item.duration).sum();
+    data.extend(app.away_usage_items());
+    let area = match app.other_hint(total_secs) {
+        Some(hint) if area.height > 6 => {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(0), Constraint::Length(3)])
+                .split(area);
+            let hint = Paragraph::new(hint)
+                .style(Style::default().fg(Color::Yellow))
+                .block(Block::default().borders(Borders::ALL).title(emoji_label("💡 Uncategorized time")));
+            f.render_widget(hint, chunks[1]);
+            chunks[0]
+        }
+        _ => area,
     };
 
     // Create a mutable clone to sort for the bar chart, filtering out sub-entries
@@ -503,72 +900,211 @@ pub fn draw_dashboard(app: &App, f: &mut Frame, area: Rect, view_mode: &ViewMode
     let max_bars = if area.width < 80 { 5 } else if area.width < 120 { 8 } else { 10 };
     let bar_data: &[crate::ui::hierarchical::HierarchicalDisplayItem] = &sorted_bar_data[..sorted_bar_data.len().min(max_bars)];
 
+    let layout = &app.config.layout;
+    if layout.is_empty() {
+        let empty = Paragraph::new("No dashboard panels. List some under `layout` in config.toml, e.g. [\"bar\", \"history\"]")
+            .style(Style::default().fg(Color::Gray))
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(empty, area);
+        return;
+    }
+    let draw_panel = |f: &mut Frame, panel: DashboardPanel, panel_area: Rect| match panel {
+        DashboardPanel::Bar => app.draw_bar_chart(f, panel_area, title, bar_data),
+        DashboardPanel::Timeline => app.draw_timeline(f, panel_area),
+        DashboardPanel::Afk => app.draw_afk(f, panel_area),
+        DashboardPanel::Stats => draw_stats(f, panel_area, &data, app.billable_today_label(), app.busiest_project_label(), app.rounding_minutes),
+        DashboardPanel::History => app.draw_history(f, panel_area),
+        DashboardPanel::Pie => app.draw_pie_chart(f, panel_area),
+        DashboardPanel::Switches => app.draw_switches_per_hour(f, panel_area),
+    };
+
     if use_vertical_layout {
-        // VERTICAL LAYOUT for small terminals
+        // VERTICAL LAYOUT for small terminals: one panel per row
+        // When the minimums don't fit, the solver would squeeze the last panels to nothing; share the height evenly instead
+        let min_height: u16 = layout.iter().map(|&panel| panel_min_height(panel)).sum();
+        let constraints: Vec<Constraint> = if area.height >= min_height {
+            layout.iter().map(|&panel| Constraint::Min(panel_min_height(panel))).collect()
+        } else {
+            vec![Constraint::Ratio(1, layout.len() as u32); layout.len()]
+        };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Min(10),  // Bar chart
-                Constraint::Min(8),   // Timeline
-                Constraint::Min(8),   // AFK
-                Constraint::Min(8),   // Stats
-                Constraint::Min(10),  // History
-                Constraint::Min(8),   // Categories
-            ].as_ref())
+            .constraints(constraints)
             .split(area);
 
-        app.draw_bar_chart(f, chunks[0], title, bar_data);
-        app.draw_timeline(f, chunks[1]);
-        app.draw_afk(f, chunks[2]);
-        draw_stats(f, chunks[3], &data);
-        app.draw_history(f, chunks[4]);
-        app.draw_pie_chart(f, chunks[5], &data);
+        for (&panel, &chunk) in layout.iter().zip(chunks.iter()) {
+            draw_panel(f, panel, chunk);
+        }
     } else {
-        // HORIZONTAL LAYOUT for larger terminals (50/50 split)
-        let main_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Percentage(50),
-                Constraint::Percentage(50),
-            ].as_ref())
-            .split(area);
+        // HORIZONTAL LAYOUT for larger terminals: the first half of the rows on the left, the rest
+        // on the right (50/50 split); a single row takes the full width
+        let rows = dashboard_rows(layout);
+        let (left_rows, right_rows) = rows.split_at(rows.len().div_ceil(2));
+        let columns: Vec<(&[Vec<DashboardPanel>], Rect)> = if right_rows.is_empty() {
+            vec![(left_rows, area)]
+        } else {
+            let main_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(50),
+                    Constraint::Percentage(50),
+                ].as_ref())
+                .split(area);
+            vec![(left_rows, main_chunks[0]), (right_rows, main_chunks[1])]
+        };
 
-        // LEFT SIDE: Bar Chart + Timeline/AFK + Stats
-        let left_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(40),   // Bar chart
-                Constraint::Percentage(30),   // Timeline + AFK
-                Constraint::Percentage(30),   // Detailed stats
-            ].as_ref())
-            .split(main_chunks[0]);
-
-        // RIGHT SIDE: Session History + Pie Chart
-        let right_chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Percentage(70),
-                Constraint::Percentage(30),
-            ].as_ref())
-            .split(main_chunks[1]);
-
-        app.draw_bar_chart(f, left_chunks[0], title, bar_data);
-        let timeline_afk_chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
-            .split(left_chunks[1]);
-        app.draw_timeline(f, timeline_afk_chunks[0]);
-        app.draw_afk(f, timeline_afk_chunks[1]);
-        draw_stats(f, left_chunks[2], &data);
-        app.draw_history(f, right_chunks[0]);
-        app.draw_pie_chart(f, right_chunks[1], &data);
+        for (column_rows, column_area) in columns {
+            let row_weight = |row: &Vec<DashboardPanel>| row.iter().map(|&panel| panel_weight(panel)).max().unwrap_or(1);
+            let row_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(column_rows.iter().map(|row| Constraint::Fill(row_weight(row))).collect::<Vec<_>>())
+                .split(column_area);
+            for (row, &row_area) in column_rows.iter().zip(row_chunks.iter()) {
+                // Panels sharing a row split it evenly
+                let panel_chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![Constraint::Ratio(1, row.len() as u32); row.len()])
+                    .split(row_area);
+                for (&panel, &panel_area) in row.iter().zip(panel_chunks.iter()) {
+                    draw_panel(f, panel, panel_area);
+                }
+            }
+        }
+    }
+}
+
+/// Untracked seconds between two history rows, if more than a minute.
+/// Works regardless of sort order by comparing the earlier row's end with the later one's start.
+fn session_gap_secs(a: &crate::ui::metrics::HistoryRow, b: &crate::ui::metrics::HistoryRow) -> Option<i64> {
+    let (earlier, later) = if a.start_time <= b.start_time { (a, b) } else { (b, a) };
+    let gap = later.start_time.signed_duration_since(earlier.end_time).num_seconds();
+    if gap > 60 { Some(gap) } else { None }
+}
+
+/// "app (name)" for a history row, using the session's display name (renames included) when it has one.
+/// Narrow terminals get both parts truncated
+fn session_label(app: &App, session: &Session, clean_app: String, narrow: bool) -> String {
+    let Some((name, _, _)) = session.effective_display_name() else {
+        if narrow {
+            return truncate_str(&clean_app, 12);
+        }
+        return clean_app;
+    };
+    let name = app.shown_title(&name);
+    if !narrow {
+        return format!("{} ({})", clean_app, name);
+    }
+    format!("{} ({})", truncate_str(&clean_app, 8), truncate_str(name, 8))
+}
+
+/// `text` cut to at most `max_chars` characters, ending in "..." when it was cut. Counts characters,
+/// not bytes, so emoji and CJK names are never split mid-character
+pub fn truncate_str(text: &str, max_chars: usize) -> String {
+    if text.char_indices().nth(max_chars).is_none() {
+        return text.to_string();
+    }
+    // Too short for an ellipsis to fit
+    if max_chars <= 3 {
+        return text.chars().take(max_chars).collect();
+    }
+    let end = text.char_indices().nth(max_chars - 3).map_or(text.len(), |(index, _)| index);
+    format!("{}...", &text[..end])
+}
+
+/// Set once at startup from `emoji`; when off, category labels and panel titles lose their leading emoji
+static NO_EMOJI: AtomicBool = AtomicBool::new(false);
+
+pub fn set_emoji(enabled: bool) {
+    NO_EMOJI.store(!enabled, Ordering::Relaxed);
+}
+
+pub fn emoji_enabled() -> bool {
+    !NO_EMOJI.load(Ordering::Relaxed)
+}
+
+/// A category or panel title as displayed: without its leading emoji when emoji are off. Only the
+/// display changes; stored categories keep theirs, so data matches machines with emoji on
+pub fn emoji_label(label: &str) -> String {
+    if emoji_enabled() { label.to_string() } else { strip_emoji(label) }
+}
+
+/// `label` without its leading emoji, e.g. "💻 Development" -> "Development". Handles multi-codepoint
+/// emoji (variation selectors, ZWJ sequences, flags); a label that is only emoji is left as it is
+pub fn strip_emoji(label: &str) -> String {
+    let is_emoji_part = |c: char| {
+        matches!(c as u32,
+            0x200D | 0x20E3 | 0xFE0F
+            | 0x2190..=0x21FF   // arrows
+            | 0x2300..=0x23FF   // misc technical (⌛, ⏰)
+            | 0x2460..=0x24FF   // enclosed alphanumerics (Ⓜ)
+            | 0x2600..=0x27BF   // misc symbols, dingbats (⚙, ✏)
+            | 0x2B00..=0x2BFF   // misc symbols and arrows (⭐)
+            | 0x1F000..=0x1FAFF // emoticons, pictographs, transport, flags
+            | 0xE0020..=0xE007F // tag sequences
+        )
+    };
+    let rest = label.trim_start_matches(|c: char| is_emoji_part(c) || c.is_whitespace());
+    if rest.is_empty() { label.to_string() } else { rest.to_string() }
+}
+
+/// Set once at startup from `time_format`, so every panel formats durations the same way
+static DECIMAL_HOURS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_time_format(time_format: TimeFormat) {
+    DECIMAL_HOURS.store(time_format == TimeFormat::Decimal, Ordering::Relaxed);
+}
+
+pub fn time_format() -> TimeFormat {
+    if DECIMAL_HOURS.load(Ordering::Relaxed) { TimeFormat::Decimal } else { TimeFormat::Hms }
+}
+
+/// "1h 37m" or "37m" ("1.62h" with decimal hours); with `rounding_minutes` set, rounded to the
+/// nearest multiple of it (display only, stored durations stay exact)
+pub fn format_duration(seconds: i64, rounding_minutes: u64) -> String {
+    format_duration_as(seconds, rounding_minutes, time_format())
+}
+
+pub fn format_duration_as(seconds: i64, rounding_minutes: u64, time_format: TimeFormat) -> String {
+    let minutes = round_minutes(seconds, rounding_minutes);
+    match time_format {
+        TimeFormat::Decimal => format!("{:.2}h", minutes as f64 / 60.0),
+        TimeFormat::Hms if minutes >= 60 => format!("{}h {}m", minutes / 60, minutes % 60),
+        TimeFormat::Hms => format!("{}m", minutes),
+    }
+}
+
+pub fn round_minutes(seconds: i64, rounding_minutes: u64) -> i64 {
+    let minutes = seconds / 60;
+    match rounding_minutes as i64 {
+        0 => minutes,
+        step => (seconds + step * 30) / (step * 60) * step,
+    }
+}
+
+/// Add the in-progress session's unsaved time onto its app's bucket so totals tick every frame.
+/// Display only: the stored usage is replaced on the next data refresh.
+pub fn add_live_session_time(app: &App, data: &mut Vec<crate::ui::hierarchical::HierarchicalDisplayItem>) {
+    let Some((live_app, elapsed)) = app.live_session_unsaved() else {
+        return;
+    };
+
+    match data.iter_mut().find(|item| !item.is_sub_entry && item.display_name.trim() == live_app) {
+        Some(item) => item.duration += elapsed,
+        None => data.push(crate::ui::hierarchical::HierarchicalDisplayItem {
+            display_name: live_app.to_string(),
+            unique_id: format!("app_name:{}", live_app),
+            duration: elapsed,
+            category: None,
+            parent_app_name: Some(live_app.to_string()),
+            is_sub_entry: false,
+        }),
     }
 }
 
 pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_data: &[crate::ui::hierarchical::HierarchicalDisplayItem]) {
     if bar_data.is_empty() {
         let empty_msg = Paragraph::new("No data available yet. Start tracking!")
-            .block(Block::default().borders(Borders::ALL).title(title));
+            .block(Block::default().borders(Borders::ALL).title(emoji_label(title)));
         f.render_widget(empty_msg, area);
     } else {
         // Adaptive bar width based on terminal width
@@ -576,7 +1112,7 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
         let bar_gap = if area.width < 60 { 0 } else { 1 };
 
         // Find max value in minutes
-        let max_minutes = bar_data.iter().map(|item| (item.duration / 60) as u64).max().unwrap_or(0);
+        let max_minutes = bar_data.iter().map(|item| round_minutes(item.duration, app.rounding_minutes) as u64).max().unwrap_or(0);
 
         // Calculate scale: minimum 8h (480 min), or max_value + 2h (120 min)
         // This ensures bars never reach the top
@@ -593,9 +1129,9 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
         let bars: Vec<Bar> = bar_data
             .iter()
             .map(|item| {
-                let value_minutes = (item.duration / 60) as u64;
+                let value_minutes = round_minutes(item.duration, app.rounding_minutes) as u64;
                 // Determine color: if sub-entry, use parent's color; otherwise use own category
-                let (_, color) = if item.is_sub_entry {
+                let (category, color) = if item.is_sub_entry {
                     // This is a sub-entry - use parent app's category color
                     if let Some(parent) = &item.parent_app_name {
                         app.get_app_category(parent)
@@ -616,7 +1152,9 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
                 let mins = value_minutes % 60;
 
                 // Format label: show hours only, or hours + minutes
-                let value_label = if mins == 0 {
+                let value_label = if time_format() == TimeFormat::Decimal {
+                    format_duration(item.duration, app.rounding_minutes)
+                } else if mins == 0 {
                     format!("{}h", hours)
                 } else if hours == 0 {
                     format!("{}m", mins)
@@ -624,12 +1162,14 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
                     format!("{}h{}m", hours, mins)
                 };
 
-                let clean_app = App::clean_app_name(&item.display_name).trim().to_string();
-                let label = if clean_app.len() > bar_width as usize {
-                    format!("{:<width$.width$}", &clean_app[..bar_width as usize], width = bar_width as usize)
-                } else {
-                    format!("{:<width$}", clean_app, width = bar_width as usize)
-                };
+                // Mono theme prefixes the category glyph since every bar has the same color
+                let marker = theme::marker(&category);
+                // Emoji icons take two columns
+                let icon = icons::icon_for(&item.display_name).unwrap_or_default();
+                let icon_width = if icon.is_empty() { 0 } else { 2 };
+                let name_width = (bar_width as usize).saturating_sub(marker.chars().count() + icon_width);
+                let clean_app: String = App::clean_app_name(&item.display_name).trim().chars().take(name_width).collect();
+                let label = format!("{}{}{:<width$}", marker, icon, clean_app, width = name_width);
                 Bar::default()
                     .value(value_minutes)
                     .label(Line::from(label))
@@ -642,7 +1182,7 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
         let chart_title = format!("{} (scale: 0-{}h)", title, scale_hours);
 
         let barchart = BarChart::default()
-            .block(Block::default().borders(Borders::ALL).title(chart_title))
+            .block(Block::default().borders(Borders::ALL).title(emoji_label(&chart_title)))
             .bar_width(bar_width)
             .bar_gap(bar_gap)
             .max(scale_minutes)  // Set max scale directly instead of padding bar
@@ -651,14 +1191,22 @@ pub fn draw_bar_chart(app: &App, f: &mut Frame, area: Rect, title: &str, bar_dat
     }
 }
 
-pub fn draw_stats(f: &mut Frame, area: Rect, data: &[crate::ui::hierarchical::HierarchicalDisplayItem]) {
+pub fn draw_stats(
+    f: &mut Frame,
+    area: Rect,
+    data: &[crate::ui::hierarchical::HierarchicalDisplayItem],
+    billable: Option<String>,
+    busiest_project: Option<String>,
+    rounding_minutes: u64,
+) {
     // Adaptive number of items based on available height - more items for hierarchical view
     let max_items = (area.height.saturating_sub(3) as usize).min(30).max(5);
 
     let mut stats_items: Vec<ListItem> = Vec::new();
 
-    // Add top margin
-    stats_items.push(ListItem::new(Line::from("")));
+    // Top line: where most of this week went, or an empty margin
+    let summary = busiest_project.unwrap_or_default();
+    stats_items.push(ListItem::new(Line::from(summary)).style(Style::default().add_modifier(Modifier::BOLD)));
 
     // Group data hierarchically by category
     // We'll detect if an item is a sub-entry
@@ -669,29 +1217,18 @@ pub fn draw_stats(f: &mut Frame, area: Rect, data: &[crate::ui::hierarchical::Hi
             break;
         }
 
-        let hours = item.duration / 3600;
-        let minutes = (item.duration % 3600) / 60;
-
         // Check if this is a child item (hierarchical sub-entry)
         let is_child = item.is_sub_entry;
 
         // Clean and truncate app name if terminal is narrow
         let clean_app = App::clean_app_name(&item.display_name);
         let app_display = if area.width < 40 {
-            if clean_app.len() > 20 {
-                format!("{}...", &clean_app[..17])
-            } else {
-                clean_app
-            }
+            truncate_str(&clean_app, 20)
         } else {
             clean_app
         };
 
-        let time_str = if hours > 0 {
-            format!("{}h {}m", hours, minutes)
-        } else {
-            format!("{}m", minutes)
-        };
+        let time_str = format_duration(item.duration, rounding_minutes);
 
         // Format display based on whether it's a parent or child entry
         let display = if is_child {
@@ -699,7 +1236,8 @@ pub fn draw_stats(f: &mut Frame, area: Rect, data: &[crate::ui::hierarchical::Hi
             format!("  {}  {}", app_display, time_str)
         } else {
             // Parent entries
-            format!("  {} - {}", app_display, time_str)
+            let marker = item.category.as_deref().map(theme::marker).unwrap_or_default();
+            format!("  {}{} - {}", marker, icons::with_icon(&item.display_name, &app_display), time_str)
         };
 
         // Color based on category
@@ -724,16 +1262,13 @@ pub fn draw_stats(f: &mut Frame, area: Rect, data: &[crate::ui::hierarchical::Hi
         .filter(|item| !item.is_sub_entry) // Only count parent entries
         .map(|item| item.duration)
         .sum();
-    let total_hours = total_duration / 3600;
-    let total_minutes = (total_duration % 3600) / 60;
-    let stats_title = if total_hours > 0 {
-        format!("📈 Detailed Stats (Total: {}h {}m)", total_hours, total_minutes)
-    } else {
-        format!("📈 Detailed Stats (Total: {}m)", total_minutes)
-    };
+    let mut stats_title = format!("📈 Detailed Stats (Total: {})", format_duration(total_duration, rounding_minutes));
+    if let Some(billable) = billable {
+        stats_title.push_str(&format!(" · {} billable today", billable));
+    }
 
     let stats_list = List::new(stats_items)
-        .block(Block::default().borders(Borders::ALL).title(stats_title));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label(&stats_title)));
     f.render_widget(stats_list, area);
 }
 
@@ -752,37 +1287,8 @@ pub fn draw_history(app: &App, f: &mut Frame, area: Rect) {
         let minutes = current_duration / 60;
         let time = current_session.start_time.format("%H:%M");
 
-        // Create display name with window name if available
-        let clean_app = App::clean_app_name(&current_session.app_name);
-        let display_name = if let Some(window_name) = &current_session.window_name {
-            if area.width < 40 {
-                // Truncate both app and window names for narrow terminals
-                let app_short = if clean_app.len() > 8 {
-                    format!("{}...", &clean_app[..5])
-                } else {
-                    clean_app.clone()
-                };
-                let window_short = if window_name.len() > 8 {
-                    format!("{}...", &window_name[..5])
-                } else {
-                    window_name.clone()
-                };
-                format!("{} ({})", app_short, window_short)
-            } else {
-                format!("{} ({})", clean_app, window_name)
-            }
-        } else {
-            // Fallback to just app name if no window name
-            if area.width < 40 {
-                if clean_app.len() > 12 {
-                    format!("{}...", &clean_app[..9])
-                } else {
-                    clean_app
-                }
-            } else {
-                clean_app
-            }
-        };
+        let clean_app = icons::with_icon(&current_session.app_name, &App::clean_app_name(&current_session.app_name));
+        let display_name = session_label(app, current_session, clean_app, area.width < 40);
 
         let display = format!("{} - {}: {}m [LIVE]", time, display_name, minutes);
         history_items.push(ListItem::new(Line::from(display)).style(Style::default().fg(Color::Green)));
@@ -795,113 +1301,262 @@ pub fn draw_history(app: &App, f: &mut Frame, area: Rect) {
             .iter()
             .take(remaining_slots)
             .map(|session| {
-                let minutes = session.duration / 60;
                 let time = session.start_time.format("%H:%M");
 
-                // Create display name with window name if available
-                let clean_app = App::clean_app_name(&session.app_name);
-                let display_name = if let Some(window_name) = &session.window_name {
-                    if area.width < 40 {
-                        // Truncate both app and window names for narrow terminals
-                        let app_short = if clean_app.len() > 8 {
-                            format!("{}...", &clean_app[..5])
-                        } else {
-                            clean_app.clone()
-                        };
-                        let window_short = if window_name.len() > 8 {
-                            format!("{}...", &window_name[..5])
-                        } else {
-                            window_name.clone()
-                        };
-                        format!("{} ({})", app_short, window_short)
-                    } else {
-                        format!("{} ({})", clean_app, window_name)
-                    }
-                } else {
-                    // Fallback to just app name if no window name
-                    if area.width < 40 {
-                        if clean_app.len() > 12 {
-                            format!("{}...", &clean_app[..9])
-                        } else {
-                            clean_app
-                        }
-                    } else {
-                        clean_app
-                    }
-                };
+                let clean_app = icons::with_icon(&session.app_name, &App::clean_app_name(&session.app_name));
+                let display_name = session_label(app, session, clean_app, area.width < 40);
 
-                let display = format!("{} - {}: {}m", time, display_name, minutes);
+                let display = format!("{} - {}: {}", time, display_name, format_duration(session.duration, app.rounding_minutes));
                 ListItem::new(Line::from(display))
             })
             .collect::<Vec<ListItem>>()
     );
 
     let history_list = List::new(history_items)
-        .block(Block::default().borders(Borders::ALL).title("📜 Session History"));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label("📜 Session History")));
     f.render_widget(history_list, area);
 }
 
-pub fn draw_pie_chart(app: &App, f: &mut Frame, area: Rect, data: &[crate::ui::hierarchical::HierarchicalDisplayItem]) {
-    // Calculate category totals - using BTreeMap for stable sorted order
-    // Filter out sub-entries
-    let mut categories: BTreeMap<String, (i64, Color)> = BTreeMap::new();
-    let total: i64 = data.iter()
-        .filter(|item| !item.is_sub_entry)
-        .map(|item| item.duration)
-        .sum();
+pub fn draw_pie_chart(app: &App, f: &mut Frame, area: Rect) {
+    // Category totals share app.category_breakdown with the breakdown dashboard so both panels agree
+    // Using BTreeMap to merge legacy names that map to the same display category
+    let mut categories: BTreeMap<String, i64> = BTreeMap::new();
+    let total: i64 = app.category_breakdown.iter().map(|(_, duration)| duration).sum();
 
-    for item in data.iter().filter(|item| !item.is_sub_entry) {
-        let (category, color) = if let Some(cat) = &item.category {
-            App::category_from_string(cat)
-        } else {
-            app.get_app_category(&item.display_name)
-        };
-        let entry = categories.entry(category).or_insert((0, color));
-        entry.0 += item.duration;
+    for (stored_category, duration) in &app.category_breakdown {
+        *categories.entry(App::category_from_string(stored_category).0).or_insert(0) += duration;
     }
 
     // Create pie chart representation as text
     let mut pie_lines = vec![];
     pie_lines.push(Line::from(""));
 
-    // Sort by duration descending for consistent display
-    let mut sorted_cats: Vec<_> = categories.iter().collect();
-    sorted_cats.sort_by(|a, b| b.1.0.cmp(&a.1.0));
-
-    for (category, (duration, color)) in sorted_cats {
+    // Pinned categories first, then by duration descending, with sub-categories next to their parent
+    for (category, duration) in crate::ui::metrics::sort_categories(categories, &app.config.pinned_categories) {
         if total > 0 {
-            let percentage = (*duration as f64 / total as f64 * 100.0) as u64;
+            let color = App::category_from_string(&category).1;
+            let percentage = (duration as f64 / total as f64 * 100.0) as u64;
             let bar_length = (percentage / 5).max(1) as usize; // Scale down for display
-            let bar = "█".repeat(bar_length);
-            let hours = duration / 3600;
-            let minutes = (duration % 3600) / 60;
-            let time_str = if hours > 0 {
-                format!("{}h {}m", hours, minutes)
-            } else {
-                format!("{}m", minutes)
-            };
+            let bar = theme::bar_glyph(&category).to_string().repeat(bar_length);
+            let time_str = format_duration(duration, app.rounding_minutes);
 
             pie_lines.push(Line::from(vec![
-                ratatui::text::Span::styled(format!("{} ", category), Style::default().fg(*color)),
-                ratatui::text::Span::styled(bar, Style::default().fg(*color)),
+                ratatui::text::Span::styled(format!("{} ", emoji_label(&category)), Style::default().fg(color)),
+                ratatui::text::Span::styled(bar, Style::default().fg(color)),
                 ratatui::text::Span::raw(format!(" {}% ({})", percentage, time_str)),
             ]));
         }
     }
 
     let pie_chart = Paragraph::new(pie_lines)
-        .block(Block::default().borders(Borders::ALL).title("🥧 Categories"));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label("🥧 Categories")));
     f.render_widget(pie_chart, area);
 }
 
+/// Fragmentation by hour of day: how many times tracking switched to a new session in each local hour
+pub fn draw_switches_per_hour(app: &App, f: &mut Frame, area: Rect) {
+    let title = format!("🔀 Switches per Hour (last {} days)", crate::ui::app::SWITCHES_DAYS);
+    let total: i64 = app.switches_per_hour.iter().sum();
+    if total == 0 {
+        let empty_msg = Paragraph::new("No switches recorded yet")
+            .block(Block::default().borders(Borders::ALL).title(emoji_label(&title)));
+        f.render_widget(empty_msg, area);
+        return;
+    }
+
+    // Fit all 24 hours in the panel; hour labels only when bars are wide enough to hold them
+    let bar_width = (area.width.saturating_sub(2) / 24).max(1);
+    let current_hour = chrono::Timelike::hour(&Local::now()) as usize;
+    let bars: Vec<Bar> = app.switches_per_hour
+        .iter()
+        .enumerate()
+        .map(|(hour, count)| {
+            let color = if hour == current_hour { Color::Yellow } else { Color::Cyan };
+            let bar = Bar::default()
+                .value((*count).max(0) as u64)
+                .text_value(String::new())
+                .style(Style::default().fg(color));
+            if bar_width >= 2 {
+                bar.label(Line::from(format!("{:02}", hour)))
+            } else {
+                bar
+            }
+        })
+        .collect();
+
+    let peak = app.switches_per_hour.iter().enumerate().max_by_key(|(_, count)| **count).map(|(hour, _)| hour).unwrap_or(0);
+    let chart = BarChart::default()
+        .block(Block::default().borders(Borders::ALL).title(emoji_label(&format!("{} - peak {:02}:00", title, peak))))
+        .bar_width(bar_width)
+        .bar_gap(0)
+        .data(BarGroup::default().bars(&bars));
+    f.render_widget(chart, area);
+}
+
+pub fn draw_weekday_averages(app: &App, f: &mut Frame, area: Rect) {
+    const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+    let title = format!("📅 Average Active Time per Weekday (last {} weeks)", crate::ui::app::WEEKDAY_WEEKS);
+    let block = Block::default().borders(Borders::ALL).title(emoji_label(&title)).style(Style::default().bg(Color::Black));
+
+    if app.weekday_averages.iter().all(|secs| *secs <= 0.0) {
+        f.render_widget(Paragraph::new("No activity recorded in this range").block(block), area);
+        return;
+    }
+
+    let today = chrono::Datelike::weekday(&Local::now()).num_days_from_monday() as usize;
+    let bars: Vec<Bar> = app.weekday_averages
+        .iter()
+        .enumerate()
+        .map(|(idx, secs)| {
+            let minutes = (*secs / 60.0).round() as u64;
+            let color = if idx == today { Color::Yellow } else { Color::Cyan };
+            Bar::default()
+                .value(minutes)
+                .label(Line::from(WEEKDAYS[idx]))
+                .text_value(format_duration(minutes as i64 * 60, app.rounding_minutes))
+                .style(Style::default().fg(color))
+        })
+        .collect();
+
+    // Seven bars share the popup width evenly
+    let bar_width = (area.width.saturating_sub(2) / 7).saturating_sub(1).max(3);
+    let chart = BarChart::default()
+        .block(block)
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .data(BarGroup::default().bars(&bars));
+    f.render_widget(chart, area);
+}
+
+pub fn draw_weekly_totals(app: &App, f: &mut Frame, area: Rect) {
+    let range = match (app.weekly_totals.first(), app.weekly_totals.last()) {
+        (Some((first_year, first_week, _)), Some((last_year, last_week, _))) => {
+            format!(", {}-W{:02} to {}-W{:02}", first_year, first_week, last_year, last_week)
+        }
+        _ => String::new(),
+    };
+    let title = format!("📈 Active Time per ISO Week (last {} weeks{})", crate::ui::app::TREND_WEEKS, range);
+    let block = Block::default().borders(Borders::ALL).title(emoji_label(&title)).style(Style::default().bg(Color::Black));
+
+    if app.weekly_totals.iter().all(|(_, _, secs)| *secs <= 0) {
+        f.render_widget(Paragraph::new("No activity recorded in this range").block(block), area);
+        return;
+    }
+
+    let last = app.weekly_totals.len() - 1;
+    let bars: Vec<Bar> = app.weekly_totals
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, week, secs))| {
+            let minutes = (*secs / 60).max(0) as u64;
+            let color = if idx == last { Color::Yellow } else { Color::Cyan };
+            Bar::default()
+                .value(minutes)
+                .label(Line::from(format!("W{:02}", week)))
+                .text_value(format_duration(minutes as i64 * 60, app.rounding_minutes))
+                .style(Style::default().fg(color))
+        })
+        .collect();
+
+    // One bar per week shares the popup width evenly
+    let bar_width = (area.width.saturating_sub(2) / app.weekly_totals.len() as u16).saturating_sub(1).max(3);
+    let chart = BarChart::default()
+        .block(block)
+        .bar_width(bar_width)
+        .bar_gap(1)
+        .data(BarGroup::default().bars(&bars));
+    f.render_widget(chart, area);
+}
+
+/// Rows each segment of a stacked bar gets when `max_total` seconds fill `height` rows. Rounding
+/// follows the running total so the stack's height matches the whole day's share
+pub fn stack_heights(segments: &[(String, i64)], max_total: i64, height: u16) -> Vec<u16> {
+    let mut cumulative = 0;
+    let mut drawn = 0;
+    segments
+        .iter()
+        .map(|(_, secs)| {
+            cumulative += (*secs).max(0);
+            let top = (cumulative * height as i64 / max_total.max(1)) as u16;
+            let rows = top.saturating_sub(drawn);
+            drawn = top;
+            rows
+        })
+        .collect()
+}
+
+/// One stacked bar per day, oldest on the left, each segment a category in its theme color
+pub fn draw_category_days(app: &App, f: &mut Frame, area: Rect) {
+    let title = format!("📊 Category Mix per Day (last {} days)", crate::ui::app::CATEGORY_DAYS);
+    let block = Block::default().borders(Borders::ALL).title(emoji_label(&title)).style(Style::default().bg(Color::Black));
+
+    let day_total = |categories: &[(String, i64)]| categories.iter().map(|(_, secs)| secs).sum::<i64>();
+    let max_total = app.category_days.iter().map(|(_, categories)| day_total(categories)).max().unwrap_or(0);
+    if max_total <= 0 {
+        f.render_widget(Paragraph::new("No activity recorded in this range").block(block), area);
+        return;
+    }
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Legend on top, the day and its total under each bar
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(2)])
+        .split(inner);
+
+    // Legend lists the week's categories, biggest first
+    let mut week_totals: BTreeMap<&str, i64> = BTreeMap::new();
+    for (_, categories) in &app.category_days {
+        for (category, secs) in categories {
+            *week_totals.entry(category.as_str()).or_default() += secs;
+        }
+    }
+    let mut legend: Vec<(&str, i64)> = week_totals.into_iter().collect();
+    legend.sort_by_key(|(_, secs)| std::cmp::Reverse(*secs));
+    let legend_spans: Vec<ratatui::text::Span> = legend
+        .iter()
+        .map(|(category, _)| ratatui::text::Span::styled(format!("{} {}  ", theme::bar_glyph(category), emoji_label(category)), Style::default().fg(App::category_from_string(category).1)))
+        .collect();
+    f.render_widget(Paragraph::new(Line::from(legend_spans)), rows[0]);
+
+    let chart = rows[1];
+    let column_width = (inner.width / app.category_days.len().max(1) as u16).max(1);
+    let bar_width = column_width.saturating_sub(1).max(1) as usize;
+    let today = Local::now().date_naive();
+    let buffer = f.buffer_mut();
+    for (idx, (day, categories)) in app.category_days.iter().enumerate() {
+        let x = inner.x + idx as u16 * column_width;
+        if x + bar_width as u16 > inner.right() {
+            break;
+        }
+
+        // Segments stack upwards from the bottom, largest first
+        let mut y = chart.bottom();
+        for ((category, _), height) in categories.iter().zip(stack_heights(categories, max_total, chart.height)) {
+            let style = Style::default().fg(App::category_from_string(category).1);
+            let segment = theme::bar_glyph(category).to_string().repeat(bar_width);
+            for _ in 0..height {
+                y -= 1;
+                buffer.set_string(x, y, &segment, style);
+            }
+        }
+
+        let label_style = if *day == today { Style::default().fg(Color::Yellow) } else { Style::default().fg(Color::White) };
+        buffer.set_string(x, rows[2].y, truncate_str(&day.format("%a %d").to_string(), bar_width), label_style);
+        let total = format_duration(day_total(categories), app.rounding_minutes);
+        buffer.set_string(x, rows[2].y + 1, truncate_str(&total, bar_width), label_style);
+    }
+}
+
 pub fn draw_timeline(app: &App, f: &mut Frame, area: Rect) {
     // Real-time progress bars showing % of day for each app
     let mut progress_lines = vec![];
 
-    if app.flat_daily_usage.is_empty() {
+    if app.flat_daily_usage.is_empty() && app.live_session_elapsed().is_none() {
         progress_lines.push(Line::from("No activity data yet today"));
         let progress = Paragraph::new(progress_lines)
-            .block(Block::default().borders(Borders::ALL).title("📊 Today's Activity Progress"));
+            .block(Block::default().borders(Borders::ALL).title(emoji_label("📊 Today's Activity Progress")));
         f.render_widget(progress, area);
         return;
     }
@@ -911,8 +1566,15 @@ pub fn draw_timeline(app: &App, f: &mut Frame, area: Rect) {
     let start_of_day = now.date_naive().and_hms_opt(0, 0, 0).unwrap().and_local_timezone(Local).unwrap();
     let seconds_since_midnight = now.signed_duration_since(start_of_day).num_seconds() as f64;
 
-    // Sort apps by usage time (descending) - use flat_daily_usage for progress bars
-    let mut sorted_apps: Vec<_> = app.flat_daily_usage.iter().collect();
+    // Sort apps by usage time (descending) - use flat_daily_usage for progress bars, ticking the live session
+    let mut live_usage = app.flat_daily_usage.clone();
+    if let Some((live_app, elapsed)) = app.live_session_unsaved() {
+        match live_usage.iter_mut().find(|(name, _)| name.trim() == live_app) {
+            Some((_, total)) => *total += elapsed,
+            None => live_usage.push((live_app.to_string(), elapsed)),
+        }
+    }
+    let mut sorted_apps: Vec<_> = live_usage.iter().collect();
     sorted_apps.sort_by(|a, b| b.1.cmp(&a.1));
 
     // Limit to top apps that fit in the area
@@ -924,7 +1586,8 @@ pub fn draw_timeline(app: &App, f: &mut Frame, area: Rect) {
 
     for (app_name, total_seconds) in top_apps {
         let clean_app_name = App::clean_app_name(app_name);
-        let (_, color) = app.get_app_category(app_name);
+        let (category, color) = app.get_app_category(app_name);
+        let glyph = theme::bar_glyph(&category);
 
         // Calculate percentage of day
         let percentage = if seconds_since_midnight > 0.0 {
@@ -940,7 +1603,7 @@ pub fn draw_timeline(app: &App, f: &mut Frame, area: Rect) {
         let mut bar_chars = String::new();
         for i in 0..bar_width {
             if i < filled_width {
-                bar_chars.push('█');
+                bar_chars.push(glyph);
             } else {
                 bar_chars.push(' ');
             }
@@ -960,12 +1623,12 @@ pub fn draw_timeline(app: &App, f: &mut Frame, area: Rect) {
 
 
     let progress = Paragraph::new(progress_lines)
-        .block(Block::default().borders(Borders::ALL).title("📊 Today's Activity Progress"));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label("📊 Today's Activity Progress")));
     f.render_widget(progress, area);
 }
 
 pub fn draw_afk(app: &App, f: &mut Frame, area: Rect) {
-    let afk_threshold_secs = 300; // 5 minutes
+    let afk_threshold_secs = app.current_afk_threshold_secs() as i64;
     let is_afk = app.is_afk(afk_threshold_secs);
     let last_input = *app.last_input.lock().unwrap();
     let idle_duration = Local::now().signed_duration_since(last_input).num_seconds();
@@ -973,7 +1636,7 @@ pub fn draw_afk(app: &App, f: &mut Frame, area: Rect) {
     let idle_seconds = idle_duration % 60;
 
     // Determine AFK and IDLE status
-    let is_idle = idle_duration >= 600; // 10 minutes = IDLE
+    let is_idle = idle_duration >= app.config.idle_threshold_secs as i64;
     let status = if is_idle { "IDLE" } else if is_afk { "AFK" } else { "Active" };
     let color = if is_idle { Color::Yellow } else if is_afk { Color::Red } else { Color::Green };
 
@@ -1007,7 +1670,7 @@ pub fn draw_afk(app: &App, f: &mut Frame, area: Rect) {
         100.0 // Default to 100% if no data yet
     };
 
-    let afk_lines = vec![
+    let mut afk_lines = vec![
         Line::from(""),
         Line::from(vec![
             ratatui::text::Span::styled("Status: ", Style::default()),
@@ -1023,27 +1686,63 @@ pub fn draw_afk(app: &App, f: &mut Frame, area: Rect) {
                 Style::default().fg(Color::Cyan)
             ),
         ]),
+        Line::from(vec![
+            ratatui::text::Span::styled("Breaks: ", Style::default()),
+            ratatui::text::Span::styled(app.afk_stats.label(app.rounding_minutes), Style::default().fg(Color::Cyan)),
+        ]),
+    ];
+    if let Some(idle) = app.afk_stats.idle_label(app.rounding_minutes) {
+        afk_lines.push(Line::from(format!("        {}", idle)));
+    }
+    afk_lines.extend([
         Line::from(""),
         Line::from("Detects keyboard/mouse activity"),
-        Line::from("AFK if idle > 5 minutes"),
-        Line::from("IDLE if idle > 10 minutes"),
-    ];
+        Line::from(format!("AFK if idle > {} minutes", afk_threshold_secs / 60)),
+        Line::from(format!("IDLE if idle > {} minutes", app.config.idle_threshold_secs / 60)),
+    ]);
 
     let afk_paragraph = Paragraph::new(afk_lines)
-        .block(Block::default().borders(Borders::ALL).title("🚫 AFK Status"));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label("🚫 AFK Status")));
     f.render_widget(afk_paragraph, area);
 }
 
-pub fn draw_breakdown_section_with_style(
-    f: &mut Frame,
-    area: Rect,
-    title: &str,
-    data: &[(String, i64)],
-    color: Color,
-    is_category: bool,
-    style: Style,
-    scroll_position: usize,
-) {
+/// Browser services list, with the unread-count sparkline underneath when any service showed unread counts
+fn draw_browser_services(app: &App, f: &mut Frame, area: Rect, style: Style, scroll: usize) {
+    let peak = app.notification_trend.iter().copied().max().unwrap_or(0);
+    let area = if peak > 0 && area.height >= 8 {
+        let split = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(3)].as_ref())
+            .split(area);
+        let latest = app.notification_trend.last().copied().unwrap_or(0);
+        let sparkline = Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(emoji_label(&format!(
+                "🔔 Unread, last {}h (now {}, peak {})",
+                crate::ui::app::NOTIFICATION_TREND_HOURS, latest, peak
+            ))))
+            .data(&app.notification_trend)
+            .style(Style::default().fg(Color::Blue));
+        f.render_widget(sparkline, split[1]);
+        split[0]
+    } else {
+        area
+    };
+    draw_breakdown_section_with_style(f, area, "🌐 Browser Services", &app.shown_breakdown(&app.browser_breakdown), BreakdownOptions { color: Color::Blue, style, scroll_position: scroll, rounding_minutes: app.rounding_minutes, ..Default::default() });
+}
+
+/// How a breakdown list is drawn: its text color (category rows use their own), the block style that
+/// marks the selected panel, how far it's scrolled and the duration rounding
+#[derive(Default)]
+pub struct BreakdownOptions {
+    pub color: Color,
+    pub is_category: bool,
+    pub style: Style,
+    pub scroll_position: usize,
+    pub rounding_minutes: u64,
+}
+
+pub fn draw_breakdown_section_with_style(f: &mut Frame, area: Rect, title: &str, data: &[(String, i64)], options: BreakdownOptions) {
+    let BreakdownOptions { color, is_category, style, scroll_position, rounding_minutes } = options;
     let max_items = (area.height.saturating_sub(3) as usize).max(3);
     let mut items: Vec<ListItem> = Vec::new();
 
@@ -1055,13 +1754,7 @@ pub fn draw_breakdown_section_with_style(
         let end_idx = (start_idx + max_items).min(data.len());
         
         for (name, duration) in data[start_idx..end_idx].iter() {
-            let hours = duration / 3600;
-            let minutes = (duration % 3600) / 60;
-            let time_str = if hours > 0 {
-                format!("{}h {}m", hours, minutes)
-            } else {
-                format!("{}m", minutes)
-            };
+            let time_str = format_duration(*duration, rounding_minutes);
 
             // For categories, extract color from category name
             let item_color = if is_category {
@@ -1070,13 +1763,14 @@ pub fn draw_breakdown_section_with_style(
                 color
             };
 
-            let display = format!("  {}  {}", name, time_str);
+            let (marker, name) = if is_category { (theme::marker(name), emoji_label(name)) } else { (String::new(), name.clone()) };
+            let display = format!("  {}{}  {}", marker, name, time_str);
             items.push(ListItem::new(Line::from(display)).style(Style::default().fg(item_color)));
         }
     }
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title(title).style(style));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label(title)).style(style));
     f.render_widget(list, area);
 }
 
@@ -1098,20 +1792,112 @@ pub fn draw_file_breakdown_section_with_style(
         let end_idx = (start_idx + max_items).min(app.file_breakdown.len());
         
         for (filename, language, duration) in app.file_breakdown[start_idx..end_idx].iter() {
-            let hours = duration / 3600;
-            let minutes = (duration % 3600) / 60;
-            let time_str = if hours > 0 {
-                format!("{}h {}m", hours, minutes)
-            } else {
-                format!("{}m", minutes)
-            };
+            let time_str = format_duration(*duration, app.rounding_minutes);
 
-            let display = format!("  {} ({})  {}", filename, language, time_str);
+            let display = format!("  {} ({})  {}", app.shown_title(filename), language, time_str);
             items.push(ListItem::new(Line::from(display)).style(Style::default().fg(Color::Cyan)));
         }
     }
 
     let list = List::new(items)
-        .block(Block::default().borders(Borders::ALL).title("📝 Files Edited").style(style));
+        .block(Block::default().borders(Borders::ALL).title(emoji_label("📝 Files Edited")).style(style));
     f.render_widget(list, area);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mouse_list_row_hit_testing() {
+        // Lists are drawn below the 3-line status bar inside a bordered block
+        let content_area = screen_chunks(Rect::new(0, 0, 80, 24))[1];
+        assert_eq!(list_row_at(content_area, 10, 4), Some(0));
+        assert_eq!(list_row_at(content_area, 10, 7), Some(3));
+        assert_eq!(list_row_at(content_area, 10, 3), None); // top border
+        assert_eq!(list_row_at(content_area, 0, 5), None); // left border
+        assert_eq!(list_row_at(content_area, 10, 1), None); // status bar
+    }
+
+    #[test]
+    fn test_terminal_too_small() {
+        assert!(!is_too_small(Rect::new(0, 0, MIN_WIDTH, MIN_HEIGHT)));
+        assert!(!is_too_small(Rect::new(0, 0, 80, 24)));
+        assert!(is_too_small(Rect::new(0, 0, MIN_WIDTH - 1, 24)));
+        assert!(is_too_small(Rect::new(0, 0, 80, MIN_HEIGHT - 1)));
+        assert!(is_too_small(Rect::new(0, 0, 0, 0)));
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(5820, 0), "1h 37m");
+        assert_eq!(format_duration(59, 0), "0m");
+        assert_eq!(format_duration(3600, 0), "1h 0m");
+        // Nearest multiple of 15 minutes, halves round up
+        assert_eq!(format_duration(5820, 15), "1h 30m");
+        assert_eq!(format_duration(6300, 15), "1h 45m");
+        assert_eq!(format_duration(420, 15), "0m");
+        assert_eq!(format_duration(3540, 15), "1h 0m");
+    }
+
+    #[test]
+    fn test_truncate_str() {
+        assert_eq!(truncate_str("Firefox", 12), "Firefox");
+        assert_eq!(truncate_str("abcdefghijkl", 12), "abcdefghijkl");
+        assert_eq!(truncate_str("abcdefghijklm", 12), "abcdefghi...");
+        // Byte 12 falls inside the third character here, where `&name[..12]` would panic
+        assert_eq!(truncate_str("日本語のウィンドウタイトルです", 12), "日本語のウィンドウ...");
+        assert_eq!(truncate_str("🦀🦀🦀🦀🦀🦀🦀🦀🦀", 8), "🦀🦀🦀🦀🦀...");
+        assert_eq!(truncate_str("éééééé", 6), "éééééé");
+        assert_eq!(truncate_str("日本語", 2), "日本");
+    }
+
+    #[test]
+    fn test_format_duration_decimal() {
+        assert_eq!(format_duration_as(9000, 0, TimeFormat::Decimal), "2.50h");
+        assert_eq!(format_duration_as(5820, 0, TimeFormat::Decimal), "1.62h");
+        assert_eq!(format_duration_as(59, 0, TimeFormat::Decimal), "0.00h");
+        // Rounded first, so 1h 37m at 15 minutes is 1.50h
+        assert_eq!(format_duration_as(5820, 15, TimeFormat::Decimal), "1.50h");
+        assert_eq!(format_duration_as(5820, 15, TimeFormat::Hms), "1h 30m");
+    }
+
+    #[test]
+    fn test_stack_heights() {
+        let segments = |secs: &[i64]| -> Vec<(String, i64)> { secs.iter().map(|s| ("💻 Development".to_string(), *s)).collect() };
+        assert_eq!(stack_heights(&segments(&[3600, 3600]), 7200, 10), vec![5, 5]);
+        // Rounding follows the running total, so the stack is exactly as tall as the day's share
+        assert_eq!(stack_heights(&segments(&[1000, 1000, 1000]), 3000, 10), vec![3, 3, 4]);
+        assert_eq!(stack_heights(&segments(&[1800]), 7200, 10), vec![2]);
+        assert_eq!(stack_heights(&[], 7200, 10), Vec::<u16>::new());
+    }
+
+    #[test]
+    fn test_dashboard_rows() {
+        use crate::config::settings::{AppConfig, DashboardPanel::*};
+
+        // The default layout: timeline and AFK share the second row, six rows split three and three
+        let rows = dashboard_rows(&AppConfig::default().layout);
+        assert_eq!(rows, vec![vec![Bar], vec![Timeline, Afk], vec![Stats], vec![History], vec![Pie], vec![Switches]]);
+
+        assert_eq!(dashboard_rows(&[Bar, Timeline, History, Stats]), vec![vec![Bar], vec![Timeline], vec![History], vec![Stats]]);
+        assert_eq!(dashboard_rows(&[Afk, Timeline, Afk]), vec![vec![Afk, Timeline], vec![Afk]]);
+        assert!(dashboard_rows(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_strip_emoji() {
+        assert_eq!(strip_emoji("💻 Development"), "Development");
+        assert_eq!(strip_emoji("💻 Development/Rust"), "Development/Rust");
+        // Variation selectors and ZWJ sequences go with the emoji
+        assert_eq!(strip_emoji("🗒️ Notes"), "Notes");
+        assert_eq!(strip_emoji("⚙️ Settings"), "Settings");
+        assert_eq!(strip_emoji("🧑‍💻 Coding Stats (this week)"), "Coding Stats (this week)");
+        // Only leading emoji are removed
+        assert_eq!(strip_emoji("Work 💼"), "Work 💼");
+        assert_eq!(strip_emoji("Gaming"), "Gaming");
+        assert_eq!(strip_emoji("日本語"), "日本語");
+        // Nothing but emoji: kept rather than shown blank
+        assert_eq!(strip_emoji("🎮"), "🎮");
+    }
+}
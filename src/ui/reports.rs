@@ -0,0 +1,179 @@
+//! Plain-text reports built from stored sessions, for use outside the TUI
+
+use crate::models::session::Session;
+use crate::ui::app::App;
+use crate::ui::render::format_duration;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+/// One day as a Markdown timesheet: a table of sessions in order, category totals and the total
+/// tracked time. AFK and idle sessions stay out of the table and totals; their time is in the footer
+pub fn day_markdown(sessions: &[Session]) -> String {
+    let mut sessions: Vec<&Session> = sessions.iter().collect();
+    sessions.sort_by_key(|session| session.start_time);
+    let Some(first) = sessions.first() else {
+        return "No sessions recorded.\n".to_string();
+    };
+
+    let mut out = format!("# Timesheet {}\n\n", first.start_time.format("%A %Y-%m-%d"));
+    out.push_str("| Time | App | Window | Duration |\n|---|---|---|---|\n");
+
+    let mut categories: BTreeMap<String, i64> = BTreeMap::new();
+    let mut tracked_secs = 0;
+    let mut afk_secs = 0;
+    for session in sessions {
+        if App::is_away_session(session) {
+            afk_secs += session.duration;
+            continue;
+        }
+        let end = session.start_time + chrono::Duration::seconds(session.duration);
+        out.push_str(&format!(
+            "| {}–{} | {} | {} | {} |\n",
+            session.start_time.format("%H:%M"),
+            end.format("%H:%M"),
+            markdown_cell(&App::clean_app_name(&session.app_name)),
+            markdown_cell(session.window_name.as_deref().unwrap_or("")),
+            format_duration(session.duration, 0),
+        ));
+
+        let category = match &session.category {
+            Some(category) => App::category_from_string(category).0,
+            None => App::categorize_window(&session.app_name, session.window_name.as_deref()).0,
+        };
+        *categories.entry(category).or_insert(0) += session.duration;
+        tracked_secs += session.duration;
+    }
+
+    let mut categories: Vec<(String, i64)> = categories.into_iter().collect();
+    categories.sort_by_key(|(_, secs)| Reverse(*secs));
+    out.push_str("\n## Categories\n\n| Category | Duration |\n|---|---|\n");
+    for (category, secs) in &categories {
+        out.push_str(&format!("| {} | {} |\n", markdown_cell(category), format_duration(*secs, 0)));
+    }
+
+    out.push_str(&format!("\n**Total tracked:** {}\n", format_duration(tracked_secs, 0)));
+    if afk_secs > 0 {
+        out.push_str(&format!("\n_AFK: {} (not included above)_\n", format_duration(afk_secs, 0)));
+    }
+    out
+}
+
+/// Today at a glance for `--stats`: tracked and AFK time, focus score (the active share of tracked
+/// plus AFK time, as Avg Activity on the dashboard), category totals and the top 5 apps. Durations
+/// come first and are right-aligned so the columns line up whatever the names' widths
+pub fn day_stats(sessions: &[Session], category_usage: &[(String, i64)], rounding_minutes: u64) -> String {
+    let duration = |secs| format_duration(secs, rounding_minutes);
+
+    let mut apps: BTreeMap<String, i64> = BTreeMap::new();
+    let mut tracked_secs = 0;
+    let mut afk_secs = 0;
+    for session in sessions {
+        if App::is_away_session(session) {
+            afk_secs += session.duration;
+        } else {
+            *apps.entry(App::clean_app_name(&session.app_name)).or_insert(0) += session.duration;
+            tracked_secs += session.duration;
+        }
+    }
+    if tracked_secs + afk_secs == 0 {
+        return "No sessions recorded today.\n".to_string();
+    }
+
+    let focus = tracked_secs * 100 / (tracked_secs + afk_secs);
+    let mut out = format!("Today ({})\n", chrono::Local::now().format("%A %Y-%m-%d"));
+    out.push_str(&format!("  {:>9}  Tracked\n", duration(tracked_secs)));
+    out.push_str(&format!("  {:>9}  AFK\n", duration(afk_secs)));
+    out.push_str(&format!("  {:>9}  Focus score\n", format!("{}%", focus)));
+
+    // Legacy category names share a display name, so merge them
+    let mut categories: BTreeMap<String, i64> = BTreeMap::new();
+    for (category, secs) in category_usage {
+        *categories.entry(App::category_from_string(category).0).or_insert(0) += secs;
+    }
+    let mut categories: Vec<(String, i64)> = categories.into_iter().collect();
+    categories.sort_by_key(|(_, secs)| Reverse(*secs));
+    if !categories.is_empty() {
+        out.push_str("\nCategories\n");
+        for (category, secs) in &categories {
+            let percent = if tracked_secs > 0 { secs * 100 / tracked_secs } else { 0 };
+            out.push_str(&format!("  {:>9} {:>4}%  {}\n", duration(*secs), percent, category));
+        }
+    }
+
+    let mut apps: Vec<(String, i64)> = apps.into_iter().collect();
+    apps.sort_by_key(|(_, secs)| Reverse(*secs));
+    if !apps.is_empty() {
+        out.push_str("\nTop apps\n");
+        for (app, secs) in apps.iter().take(5) {
+            out.push_str(&format!("  {:>9}  {}\n", duration(*secs), app));
+        }
+    }
+    out
+}
+
+// Pipes would end the cell and newlines the row
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn session(hour: u32, minute: u32, duration: i64, app: &str, window: &str, is_afk: bool) -> Session {
+        Session {
+            window_name: Some(window.to_string()),
+            is_afk: Some(is_afk),
+            ..Session::for_test(app, Local.with_ymd_and_hms(2025, 3, 11, hour, minute, 0).unwrap(), duration)
+        }
+    }
+
+    #[test]
+    fn test_day_markdown() {
+        let sessions = vec![
+            session(10, 0, 1800, "firefox", "Docs | MDN", false),
+            session(9, 0, 3600, "code", "main.rs", false),
+            session(9, 30, 900, "AFK", "Away from keyboard", true),
+        ];
+        let markdown = day_markdown(&sessions);
+
+        assert!(markdown.starts_with("# Timesheet Tuesday 2025-03-11\n"));
+        let code_row = markdown.find("| 09:00–10:00 | code | main.rs | 1h 0m |").unwrap();
+        let firefox_row = markdown.find("| 10:00–10:30 | firefox | Docs \\| MDN | 30m |").unwrap();
+        assert!(code_row < firefox_row);
+        assert!(!markdown.contains("| AFK |"));
+        assert!(markdown.contains("| 💻 Development | 1h 0m |"));
+        assert!(markdown.contains("| 🌐 Browsing | 30m |"));
+        assert!(markdown.contains("**Total tracked:** 1h 30m"));
+        assert!(markdown.contains("_AFK: 15m (not included above)_"));
+
+        assert_eq!(day_markdown(&[]), "No sessions recorded.\n");
+    }
+
+    #[test]
+    fn test_day_stats() {
+        let sessions = vec![
+            session(9, 0, 3600, "code", "main.rs", false),
+            session(10, 0, 1800, "firefox", "Docs | MDN", false),
+            session(10, 30, 600, "code", "lib.rs", false),
+            session(11, 0, 1800, "AFK", "Away from keyboard", true),
+        ];
+        let categories = vec![("💻 Development".to_string(), 4200), ("🌐 Browsing".to_string(), 1800)];
+        let stats = day_stats(&sessions, &categories, 0);
+
+        assert!(stats.contains("     1h 40m  Tracked\n"));
+        assert!(stats.contains("        30m  AFK\n"));
+        // 6000s active out of 7800s
+        assert!(stats.contains("        76%  Focus score\n"));
+        assert!(stats.contains("     1h 10m   70%  💻 Development\n"));
+        assert!(stats.contains("        30m   30%  🌐 Browsing\n"));
+        let code = stats.find("     1h 10m  code\n").unwrap();
+        let firefox = stats.find("        30m  firefox\n").unwrap();
+        assert!(code < firefox);
+        let top_apps = &stats[stats.find("Top apps").unwrap()..];
+        assert!(!top_apps.contains("AFK"));
+
+        assert_eq!(day_stats(&[], &[], 0), "No sessions recorded today.\n");
+    }
+}
@@ -2,13 +2,18 @@ use chrono::{DateTime, Local};
 use crate::models::session::Session;
 use crate::ui::parser;
 use crate::database::connection::Database;
+use crate::daemon::media;
 use anyhow::Result;
 
-pub async fn create_session_with_parsing(db: &Database, app_name: String, window_name: Option<String>, start_time: DateTime<Local>, category: String) -> Result<Session> {
+/// `category` picks the category once the window title is parsed, so it can go by directory or project
+pub async fn create_session_with_parsing(db: &Database, app_name: String, window_name: Option<String>, start_time: DateTime<Local>, category: impl FnOnce(&Session) -> String) -> Result<Session> {
     create_session_with_parsing_and_afk(db, app_name, window_name, start_time, category, Some(false)).await
 }
 
-pub async fn create_session_with_parsing_and_afk(db: &Database, app_name: String, window_name: Option<String>, start_time: DateTime<Local>, category: String, is_afk: Option<bool>) -> Result<Session> {
+pub async fn create_session_with_parsing_and_afk(db: &Database, app_name: String, window_name: Option<String>, start_time: DateTime<Local>, category: impl FnOnce(&Session) -> String, is_afk: Option<bool>) -> Result<Session> {
+    // Media players record the playing track in place of the player window
+    let window_name = media::session_window(&app_name, window_name).await;
+
     // Parse window name if available
     let parsed = if let Some(ref win_name) = window_name {
         parser::parse_window_name(&app_name, win_name)
@@ -25,7 +30,7 @@ pub async fn create_session_with_parsing_and_afk(db: &Database, app_name: String
         window_name,
         start_time,
         duration: 0,
-        category: Some(category),
+        category: None,
         // Browser fields
         browser_url: parsed.browser_url,
         browser_page_title: parsed.browser_page_title,
@@ -62,8 +67,13 @@ pub async fn create_session_with_parsing_and_afk(db: &Database, app_name: String
         // AFK tracking
         is_afk,
         is_idle: Some(false),  // Default to not idle for new sessions
+        tag: None,
+        hostname: crate::models::session::current_hostname(),
+        note: None,
+        resumed_id: None,
     };
 
+    session.category = Some(category(&session));
     let id = db.insert_session(&session).await?;
     session.id = Some(id);
     Ok(session)
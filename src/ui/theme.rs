@@ -0,0 +1,176 @@
+use crate::config::settings::Theme;
+use ratatui::style::Color;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::RwLock;
+
+/// Theme used by the category color lookups; set from the config at startup and from the settings screen
+static ACTIVE_THEME: RwLock<Theme> = RwLock::new(Theme::Default);
+
+pub const THEMES: [Theme; 3] = [Theme::Default, Theme::Colorblind, Theme::Mono];
+
+pub fn set_active(theme: Theme) {
+    *ACTIVE_THEME.write().unwrap() = theme;
+}
+
+pub fn active() -> Theme {
+    *ACTIVE_THEME.read().unwrap()
+}
+
+/// Parse a THEME value from .env (case-insensitive)
+pub fn parse(name: &str) -> Option<Theme> {
+    THEMES.into_iter().find(|theme| name.trim().eq_ignore_ascii_case(label(*theme)))
+}
+
+pub fn label(theme: Theme) -> &'static str {
+    match theme {
+        Theme::Default => "default",
+        Theme::Colorblind => "colorblind",
+        Theme::Mono => "mono",
+    }
+}
+
+/// Next or previous theme, wrapping around
+pub fn cycle(theme: Theme, forward: bool) -> Theme {
+    let idx = THEMES.iter().position(|t| *t == theme).unwrap_or(0);
+    let next = if forward { idx + 1 } else { idx + THEMES.len() - 1 };
+    THEMES[next % THEMES.len()]
+}
+
+/// Color for a category display name under the active theme
+pub fn category_color(category: &str) -> Color {
+    palette_color(active(), category)
+}
+
+pub fn palette_color(theme: Theme, category: &str) -> Color {
+    // Sub-categories ("💻 Development/Rust") take a shade of their parent's color
+    if let Some((_, child)) = category.split_once('/') {
+        return shade(palette_color(theme, crate::ui::metrics::parent_category(category)), child);
+    }
+    match theme {
+        Theme::Default => match category {
+            "💻 Development" => Color::Yellow,
+            "🌐 Browsing" => Color::Blue,
+            "💬 Communication" => Color::Green,
+            "🎵 Media" => Color::Magenta,
+            "📁 Files" => Color::Cyan,
+            "📧 Email" => Color::LightYellow,
+            "📄 Office" => Color::LightBlue,
+            "📅 Meetings" => Color::LightGreen,
+            "🗒️ Notes" => Color::LightCyan,
+            "📦 Other" => Color::White,
+            "💤 Away" => Color::DarkGray,
+            _ => Color::LightMagenta,
+        },
+        // Okabe-Ito palette, distinguishable with the common forms of color blindness
+        Theme::Colorblind => match category {
+            "💻 Development" => Color::Rgb(230, 159, 0),   // orange
+            "🌐 Browsing" => Color::Rgb(86, 180, 233),     // sky blue
+            "💬 Communication" => Color::Rgb(0, 158, 115), // bluish green
+            "🎵 Media" => Color::Rgb(204, 121, 167),       // reddish purple
+            "📁 Files" => Color::Rgb(240, 228, 66),        // yellow
+            "📧 Email" => Color::Rgb(213, 94, 0),          // vermillion
+            "📄 Office" => Color::Rgb(0, 114, 178),        // blue
+            "📅 Meetings" => Color::Rgb(153, 221, 201),    // light bluish green
+            "🗒️ Notes" => Color::Rgb(180, 215, 240),       // light sky blue
+            "📦 Other" => Color::White,
+            "💤 Away" => Color::DarkGray,
+            _ => Color::Gray,
+        },
+        Theme::Mono => Color::White,
+    }
+}
+
+/// Lighter or darker variant of a parent color; RGB colors vary with the child's name so siblings differ
+fn shade(color: Color, child: &str) -> Color {
+    match color {
+        Color::Rgb(r, g, b) => {
+            // Blend 20-50% towards white, picked by a hash of the name so anagrams don't collide
+            let mut hasher = DefaultHasher::new();
+            child.hash(&mut hasher);
+            let weight = 20 + (hasher.finish() % 7) as u32 * 5;
+            let blend = |c: u8| (c as u32 + (255 - c as u32) * weight / 100) as u8;
+            Color::Rgb(blend(r), blend(g), blend(b))
+        }
+        Color::Yellow => Color::LightYellow,
+        Color::LightYellow => Color::Yellow,
+        Color::Blue => Color::LightBlue,
+        Color::LightBlue => Color::Blue,
+        Color::Green => Color::LightGreen,
+        Color::LightGreen => Color::Green,
+        Color::Magenta => Color::LightMagenta,
+        Color::LightMagenta => Color::Magenta,
+        Color::Cyan => Color::LightCyan,
+        Color::LightCyan => Color::Cyan,
+        Color::White => Color::Gray,
+        Color::Gray => Color::White,
+        other => other,
+    }
+}
+
+/// Glyph used to draw bars for a category; mono tells categories apart by glyph instead of color
+pub fn bar_glyph(category: &str) -> char {
+    glyph(active(), category)
+}
+
+pub fn glyph(theme: Theme, category: &str) -> char {
+    if theme != Theme::Mono {
+        return '█';
+    }
+    match crate::ui::metrics::parent_category(category) {
+        "💻 Development" => '█',
+        "🌐 Browsing" => '▓',
+        "💬 Communication" => '▒',
+        "🎵 Media" => '░',
+        "📁 Files" => '▚',
+        "📧 Email" => '▞',
+        "📄 Office" => '▤',
+        "📅 Meetings" => '▥',
+        "🗒️ Notes" => '▦',
+        "📦 Other" => '▪',
+        "💤 Away" => '·',
+        _ => '▧',
+    }
+}
+
+/// Leading marker for list rows, only shown in mono where color carries no meaning
+pub fn marker(category: &str) -> String {
+    if active() == Theme::Mono {
+        format!("{} ", glyph(Theme::Mono, category))
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_palettes() {
+        assert_eq!(parse("Colorblind"), Some(Theme::Colorblind));
+        assert_eq!(parse("neon"), None);
+        assert_eq!(cycle(Theme::Mono, true), Theme::Default);
+        assert_eq!(cycle(Theme::Default, false), Theme::Mono);
+
+        assert_eq!(palette_color(Theme::Default, "💻 Development"), Color::Yellow);
+        assert_eq!(palette_color(Theme::Colorblind, "💻 Development"), Color::Rgb(230, 159, 0));
+
+        // Mono drops color entirely and tells categories apart by glyph
+        assert_eq!(palette_color(Theme::Mono, "🌐 Browsing"), palette_color(Theme::Mono, "🎵 Media"));
+        assert_ne!(glyph(Theme::Mono, "🌐 Browsing"), glyph(Theme::Mono, "🎵 Media"));
+        assert_eq!(glyph(Theme::Default, "🌐 Browsing"), '█');
+    }
+
+    #[test]
+    fn test_subcategory_colors() {
+        // Children get a shade of their parent's color, not the fallback for unknown categories
+        assert_eq!(palette_color(Theme::Default, "💻 Development/Rust"), Color::LightYellow);
+        let rust = palette_color(Theme::Colorblind, "💻 Development/Rust");
+        let devops = palette_color(Theme::Colorblind, "💻 Development/DevOps");
+        assert_ne!(rust, palette_color(Theme::Colorblind, "💻 Development"));
+        assert_ne!(rust, palette_color(Theme::Colorblind, "🎮 Gaming"));
+        assert_ne!(rust, devops);
+        // Mono children keep their parent's glyph
+        assert_eq!(glyph(Theme::Mono, "🌐 Browsing/Research"), glyph(Theme::Mono, "🌐 Browsing"));
+    }
+}
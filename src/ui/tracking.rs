@@ -1,23 +1,30 @@
 use anyhow::Result;
 use chrono::Local;
+use serde::{Deserialize, Serialize};
+use crate::config::settings::{AppConfig, ShortSessionAction};
 use crate::database::connection::Database;
 use crate::models::session::Session;
 use crate::tracker::monitor::AppMonitor;
 use crate::ui::session;
 use crate::ui::hierarchical::HierarchicalDisplayItem;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ViewMode {
     Daily,
     Weekly,
     Monthly,
 }
 
+/// Picks a new session's category from its app, window and parsed title
+pub type CategorizeFn = fn(&Session, &AppConfig) -> (String, ratatui::style::Color);
+
 /// Context for tracking operations
 pub struct TrackingContext<'a> {
     pub monitor: &'a AppMonitor,
     pub database: &'a Database,
     pub manual_app_name: Option<String>,
+    pub config: &'a AppConfig,
+    pub dnd_active: bool,
 }
 
 /// Result of starting a new tracking session
@@ -49,7 +56,7 @@ pub struct RefreshData {
 
 pub async fn start_tracking(
     ctx: &TrackingContext<'_>,
-    categorize_fn: fn(&str) -> (String, ratatui::style::Color),
+    categorize_fn: CategorizeFn,
 ) -> Result<TrackingResult> {
     let app_name = if let Some(manual_name) = &ctx.manual_app_name {
         manual_name.clone()
@@ -66,17 +73,32 @@ pub async fn start_tracking(
 
     let window_name = ctx.monitor.get_active_window_name_async().await.ok();
     let start_time = Local::now();
-    let (category_name, _) = categorize_fn(&app_name);
 
-    let session = session::create_session_with_parsing(
+    let mut session = session::create_session_with_parsing(
         ctx.database,
         app_name.clone(),
         window_name.clone(),
         start_time,
-        category_name.to_string(),
+        |session| new_session_category(ctx, categorize_fn, session),
     ).await?;
 
-    let log_message = format!("[{}] Started tracking: {}", Local::now().format("%H:%M:%S"), app_name);
+    // A quick restart (crash, update) carries on the last session rather than splitting it in two
+    let resumed = match ctx.database.reopen_last_session_if_recent(&session, ctx.config.resume_window_secs).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            log::warn!("Failed to check the last session for resuming: {}", e);
+            None
+        }
+    };
+    let log_message = if let Some(previous) = resumed {
+        session.start_time = previous.start_time;
+        session.tag = session.tag.take().or(previous.tag);
+        session.resumed_id = previous.id;
+        session.duration = previous.duration;
+        format!("[{}] Resumed tracking: {} (started {})", Local::now().format("%H:%M:%S"), app_name, previous.start_time.format("%H:%M:%S"))
+    } else {
+        format!("[{}] Started tracking: {}", Local::now().format("%H:%M:%S"), app_name)
+    };
 
     Ok(TrackingResult {
         session,
@@ -86,11 +108,18 @@ pub async fn start_tracking(
     })
 }
 
+/// The categorizer's pick, then the fullscreen and Do-Not-Disturb overrides
+fn new_session_category(ctx: &TrackingContext<'_>, categorize_fn: CategorizeFn, session: &Session) -> String {
+    let (category_name, _) = categorize_fn(session, ctx.config);
+    let category_name = ctx.config.category_for_window(&session.app_name, category_name, ctx.monitor.is_fullscreen());
+    ctx.config.category_for_dnd(&session.app_name, category_name, ctx.dnd_active)
+}
+
 pub async fn switch_app(
     ctx: &TrackingContext<'_>,
     current_session: Option<Session>,
     new_app: String,
-    categorize_fn: fn(&str) -> (String, ratatui::style::Color),
+    categorize_fn: CategorizeFn,
 ) -> Result<SwitchResult> {
     switch_app_with_afk(ctx, current_session, new_app, categorize_fn, None).await
 }
@@ -99,33 +128,16 @@ pub async fn switch_app_with_afk(
     ctx: &TrackingContext<'_>,
     current_session: Option<Session>,
     new_app: String,
-    categorize_fn: fn(&str) -> (String, ratatui::style::Color),
+    categorize_fn: CategorizeFn,
     is_afk: Option<bool>,
 ) -> Result<SwitchResult> {
     let mut logs = Vec::new();
-    let saved_session;
-
-    // End current session
-    if let Some(mut session) = current_session {
-        session.duration = Local::now().signed_duration_since(session.start_time).num_seconds();
-
-        // Save ALL sessions regardless of duration
-        if let Err(e) = ctx.database.insert_session(&session).await {
-            log::error!("Failed to save session: {}", e);
-            logs.push(format!("[{}] Failed to save session: {}", Local::now().format("%H:%M:%S"), e));
-            saved_session = None;
-        } else {
-            logs.push(format!("[{}] Saved session: {} for {}s", Local::now().format("%H:%M:%S"), session.app_name, session.duration));
-            saved_session = Some(session);
-        }
-    } else {
-        saved_session = None;
-    }
+    let saved_session = end_session(ctx, current_session, &mut logs).await;
 
     // Start new session
     let window_name = ctx.monitor.get_active_window_name_async().await.ok();
     let start_time = Local::now();
-    let (category_name, _) = categorize_fn(&new_app);
+    let category_name = |session: &Session| new_session_category(ctx, categorize_fn, session);
 
     let new_session = if let Some(afk_flag) = is_afk {
         session::create_session_with_parsing_and_afk(
@@ -133,7 +145,7 @@ pub async fn switch_app_with_afk(
             new_app.clone(),
             window_name.clone(),
             start_time,
-            category_name.to_string(),
+            category_name,
             Some(afk_flag),
         ).await?
     } else {
@@ -142,7 +154,7 @@ pub async fn switch_app_with_afk(
             new_app.clone(),
             window_name.clone(),
             start_time,
-            category_name.to_string(),
+            category_name,
         ).await?
     };
 
@@ -157,8 +169,48 @@ pub async fn switch_app_with_afk(
     })
 }
 
+/// Save an ended session (merged or dropped when it's too short), returning it when it was recorded
+pub async fn end_session(ctx: &TrackingContext<'_>, current_session: Option<Session>, logs: &mut Vec<String>) -> Option<Session> {
+    let saved_session;
+    if let Some(mut session) = current_session {
+        session.duration = Local::now().signed_duration_since(session.start_time).num_seconds();
+
+        // Sessions below the configured minimum are merged or dropped instead of getting their own row
+        match ctx.config.short_session_action_for(&session) {
+            Some(ShortSessionAction::Merge) => match ctx.database.merge_into_previous_session(&session).await {
+                Ok(true) => {
+                    logs.push(format!("[{}] Merged short session: {} ({}s) into previous", Local::now().format("%H:%M:%S"), session.app_name, session.duration));
+                    saved_session = Some(session);
+                }
+                Ok(false) => saved_session = None,
+                Err(e) => {
+                    log::error!("Failed to merge short session: {}", e);
+                    saved_session = None;
+                }
+            },
+            Some(ShortSessionAction::Discard) => {
+                log::debug!("Discarded short session: {} for {}s", session.app_name, session.duration);
+                saved_session = None;
+            }
+            None => {
+                if let Err(e) = ctx.database.insert_session(&session).await {
+                    log::error!("Failed to save session: {}", e);
+                    logs.push(format!("[{}] Failed to save session: {}", Local::now().format("%H:%M:%S"), e));
+                    saved_session = None;
+                } else {
+                    logs.push(format!("[{}] Saved session: {} for {}s", Local::now().format("%H:%M:%S"), session.app_name, session.duration));
+                    saved_session = Some(session);
+                }
+            }
+        }
+    } else {
+        saved_session = None;
+    }
+    saved_session
+}
+
 pub async fn refresh_all_data(database: &Database, view_mode: &ViewMode) -> Result<RefreshData> {
-    let usage = database.get_app_usage().await?;
+    let usage = database.get_app_usage_for_range(view_mode.clone()).await?;
     let history = database.get_recent_sessions(30).await.unwrap_or_default();
 
     let current_history = match view_mode {
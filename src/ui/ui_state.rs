@@ -0,0 +1,55 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::ui::tracking::ViewMode;
+
+/// Dashboard preferences remembered between launches, persisted to ui_state.json
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    pub view_mode: ViewMode,
+    pub breakdown_panel: usize,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self {
+            view_mode: ViewMode::Daily,
+            breakdown_panel: 0,
+        }
+    }
+}
+
+impl UiState {
+    fn get_state_path() -> std::path::PathBuf {
+        std::env::current_dir().unwrap().join("ui_state.json")
+    }
+
+    /// Load the saved state, falling back to defaults if the file is missing or corrupt
+    pub fn load() -> Self {
+        let state_path = Self::get_state_path();
+        if !state_path.exists() {
+            return Self::default();
+        }
+
+        match fs::read_to_string(&state_path).map_err(anyhow::Error::from).and_then(|content| Ok(serde_json::from_str::<Self>(&content)?)) {
+            Ok(mut state) => {
+                // Guard against a hand-edited panel index outside the breakdown dashboard
+                if state.breakdown_panel >= 5 {
+                    state.breakdown_panel = 0;
+                }
+                state
+            }
+            Err(e) => {
+                log::warn!("Failed to read ui_state.json, using defaults: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        fs::write(Self::get_state_path(), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}